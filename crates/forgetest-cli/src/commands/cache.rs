@@ -0,0 +1,49 @@
+//! The `forgetest cache` command.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use forgetest_core::cache::{CacheMode, ResponseCache};
+
+/// Summarize the response cache.
+pub fn stats(dir: PathBuf, format: String) -> Result<()> {
+    let cache = ResponseCache::new(dir, CacheMode::Off);
+    let stats = cache.stats()?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    println!("Cache: {}", cache.dir().display());
+    println!(
+        "  {} responses, {:.1} KiB, ${:.4} of generations",
+        stats.entries,
+        stats.bytes as f64 / 1024.0,
+        stats.cost_usd
+    );
+    if let (Some(oldest), Some(newest)) = (stats.oldest, stats.newest) {
+        println!(
+            "  cached between {} and {}",
+            oldest.format("%Y-%m-%d %H:%M"),
+            newest.format("%Y-%m-%d %H:%M")
+        );
+    }
+    for (model, count) in &stats.per_model {
+        println!("  {model}: {count}");
+    }
+    Ok(())
+}
+
+/// Remove old (or all) entries from the response cache.
+pub fn prune(dir: PathBuf, older_than_days: Option<u64>) -> Result<()> {
+    let cache = ResponseCache::new(dir, CacheMode::Off);
+    let removed = cache.prune(older_than_days.map(|days| Duration::from_secs(days * 86_400)))?;
+    println!(
+        "Removed {removed} cached responses from {}",
+        cache.dir().display()
+    );
+    Ok(())
+}
@@ -4,7 +4,10 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 
+use forgetest_core::head_to_head::{format_delta, format_metric};
 use forgetest_core::report::EvalReport;
+use forgetest_core::significance::SignificanceConfig;
+use forgetest_report::html::generate_head_to_head_html;
 
 pub fn execute(
     baseline_path: PathBuf,
@@ -12,11 +15,27 @@ pub fn execute(
     threshold: f64,
     fail_on_regression: bool,
     format: String,
+    significance: Option<SignificanceConfig>,
 ) -> Result<()> {
+    if let Some(config) = &significance {
+        anyhow::ensure!(
+            config.alpha > 0.0 && config.alpha < 1.0,
+            "--alpha must be between 0 and 1"
+        );
+    }
     let baseline = EvalReport::load_json(&baseline_path)?;
     let current = EvalReport::load_json(&current_path)?;
 
-    let report = current.compare(&baseline, threshold);
+    let report = match &significance {
+        Some(config) => current.compare_significance(&baseline, threshold, config),
+        None => current.compare(&baseline, threshold),
+    };
+    if report.scoring_mismatch {
+        eprintln!(
+            "Warning: the reports were scored with different scoring profiles; \
+             deltas may reflect scoring changes rather than model behaviour."
+        );
+    }
 
     match format.as_str() {
         "markdown" | "md" => {
@@ -34,16 +53,39 @@ pub fn execute(
                 report.unchanged
             );
 
+            let stats = |p: Option<f64>, effect: Option<f64>| match (p, effect) {
+                (Some(p), Some(effect)) => format!(" p={p:.4} effect={effect:+.2}"),
+                _ => String::new(),
+            };
+
+            if !report.models.is_empty() {
+                println!("\nModels:");
+                for m in &report.models {
+                    println!(
+                        "  {} {:.1}% -> {:.1}% ({:+.1}%) over {} cases, p={:.4} effect={:+.2}{}",
+                        m.model,
+                        m.baseline_score * 100.0,
+                        m.current_score * 100.0,
+                        m.delta * 100.0,
+                        m.cases,
+                        m.p_value,
+                        m.effect_size,
+                        if m.significant { " (significant)" } else { "" }
+                    );
+                }
+            }
+
             if !report.regressions.is_empty() {
                 println!("\nRegressions:");
                 for r in &report.regressions {
                     println!(
-                        "  {} ({}) {:.1}% -> {:.1}% ({:+.1}%)",
+                        "  {} ({}) {:.1}% -> {:.1}% ({:+.1}%){}",
                         r.case_id,
                         r.model,
                         r.baseline_score * 100.0,
                         r.current_score * 100.0,
-                        r.delta * 100.0
+                        r.delta * 100.0,
+                        stats(r.p_value, r.effect_size)
                     );
                 }
             }
@@ -52,16 +94,38 @@ pub fn execute(
                 println!("\nImprovements:");
                 for i in &report.improvements {
                     println!(
-                        "  {} ({}) {:.1}% -> {:.1}% (+{:.1}%)",
+                        "  {} ({}) {:.1}% -> {:.1}% (+{:.1}%){}",
                         i.case_id,
                         i.model,
                         i.baseline_score * 100.0,
                         i.current_score * 100.0,
-                        i.delta * 100.0
+                        i.delta * 100.0,
+                        stats(i.p_value, i.effect_size)
+                    );
+                }
+            }
+
+            if !report.failure_modes.is_empty() {
+                println!("\nFailure modes (share of attempts):");
+                for f in &report.failure_modes {
+                    println!(
+                        "  {} {}: {:.1}% -> {:.1}% ({:+.1}%)",
+                        f.model,
+                        f.category,
+                        f.baseline_rate * 100.0,
+                        f.current_rate * 100.0,
+                        f.delta() * 100.0
                     );
                 }
             }
 
+            if !report.changed_cases.is_empty() {
+                println!(
+                    "\nChanged case definitions (not compared): {}",
+                    report.changed_cases.join(", ")
+                );
+            }
+
             if report.new_cases > 0 {
                 println!("\n{} new case(s)", report.new_cases);
             }
@@ -77,3 +141,73 @@ pub fn execute(
 
     Ok(())
 }
+
+/// Compare two models within a single report.
+pub fn head_to_head(report_path: PathBuf, models: String, format: String) -> Result<()> {
+    let names: Vec<&str> = models.split(',').map(str::trim).collect();
+    let [model_a, model_b] = names[..] else {
+        anyhow::bail!("--models takes exactly two comma-separated models, got '{models}'");
+    };
+    let report = EvalReport::load_json(&report_path)?;
+    let h2h = report.head_to_head(model_a, model_b)?;
+
+    match format.as_str() {
+        "markdown" | "md" => {
+            println!("{}", h2h.to_markdown());
+        }
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&h2h)?);
+        }
+        "html" => {
+            println!("{}", generate_head_to_head_html(&h2h));
+        }
+        _ => {
+            // text format
+            println!(
+                "{model_a} vs {model_b}: {} wins, {} losses, {} ties over {} shared cases",
+                h2h.wins_a,
+                h2h.wins_b,
+                h2h.ties,
+                h2h.cases.len()
+            );
+            println!(
+                "Solved: both {}, only {model_a} {}, only {model_b} {}, neither {} (McNemar p={:.4})",
+                h2h.both_solved,
+                h2h.only_a_solved.len(),
+                h2h.only_b_solved.len(),
+                h2h.neither_solved,
+                h2h.mcnemar_p
+            );
+            for (model, cases) in [(model_a, &h2h.only_a_solved), (model_b, &h2h.only_b_solved)] {
+                if !cases.is_empty() {
+                    println!("  only {model}: {}", cases.join(", "));
+                }
+            }
+
+            println!("\nMetrics ({model_a} -> {model_b}):");
+            for d in &h2h.deltas {
+                println!(
+                    "  {:<12} {} -> {} ({}) p={:.4}",
+                    d.metric,
+                    format_metric(&d.metric, d.a),
+                    format_metric(&d.metric, d.b),
+                    format_delta(&d.metric, d.delta),
+                    d.p_value
+                );
+            }
+
+            println!("\nCases:");
+            for c in &h2h.cases {
+                println!(
+                    "  {} {:.1}% vs {:.1}% -> {}",
+                    c.case_id,
+                    c.score_a * 100.0,
+                    c.score_b * 100.0,
+                    h2h.winner_name(c.winner)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! The `forgetest leaderboard` command.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use forgetest_core::leaderboard::{Leaderboard, LeaderboardConfig};
+use forgetest_core::report::EvalReport;
+use forgetest_report::html::{generate_leaderboard_html, write_leaderboard_html};
+
+pub fn execute(
+    paths: Vec<PathBuf>,
+    format: String,
+    html: Option<PathBuf>,
+    config: LeaderboardConfig,
+) -> Result<()> {
+    // Expand directories to the report JSON files they contain
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = std::fs::read_dir(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path);
+        }
+    }
+    anyhow::ensure!(!files.is_empty(), "no report JSON files found");
+
+    let reports = files
+        .iter()
+        .map(|path| EvalReport::load_json(path).with_context(|| format!("in {}", path.display())))
+        .collect::<Result<Vec<_>>>()?;
+    let board = Leaderboard::from_reports(&reports, &config)?;
+
+    if let Some(path) = &html {
+        write_leaderboard_html(&board, path)?;
+        eprintln!("Leaderboard written to {}", path.display());
+    }
+
+    match format.as_str() {
+        "markdown" | "md" => {
+            println!("{}", board.to_markdown());
+        }
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&board)?);
+        }
+        "html" => {
+            println!("{}", generate_leaderboard_html(&board));
+        }
+        _ => {
+            // text format
+            println!(
+                "Leaderboard: {} models over {} shared cases from {} reports ({:.0}% intervals)",
+                board.entries.len(),
+                board.cases,
+                board.reports,
+                board.level * 100.0
+            );
+            if board.groups > 1 {
+                println!(
+                    "Some models never ran on the same cases; ratings are not comparable across groups."
+                );
+            }
+
+            for group in 0..board.groups {
+                if board.groups > 1 {
+                    println!("\nGroup {}:", group + 1);
+                } else {
+                    println!();
+                }
+                println!(
+                    "  {:<4} {:<32} {:>6}  {:<11} {:>5}  W-L-T",
+                    "Rank", "Model", "Rating", "Interval", "Cases"
+                );
+                for e in board.entries.iter().filter(|e| e.group == group) {
+                    println!(
+                        "  {:<4} {:<32} {:>6.0}  {:<11} {:>5}  {}-{}-{}",
+                        e.rank,
+                        e.model,
+                        e.rating,
+                        format!("{:.0}-{:.0}", e.interval.lower, e.interval.upper),
+                        e.cases,
+                        e.wins,
+                        e.losses,
+                        e.ties
+                    );
+                }
+            }
+
+            if !board.unrated.is_empty() {
+                println!(
+                    "\nUnrated (no cases shared with another model): {}",
+                    board.unrated.join(", ")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
+pub mod cache;
 pub mod compare;
 pub mod init;
+pub mod leaderboard;
 pub mod list_models;
+pub mod rescore;
 pub mod run;
 pub mod validate;
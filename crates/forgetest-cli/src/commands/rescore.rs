@@ -0,0 +1,113 @@
+//! The `forgetest rescore` command.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use forgetest_core::engine::{EvalEngine, EvalEngineConfig};
+use forgetest_core::parser;
+use forgetest_core::report::EvalReport;
+use forgetest_core::scoring::DefaultScorer;
+use forgetest_providers::config::load_config_from;
+use forgetest_runner::LocalRunner;
+
+use super::run::{print_summary, write_reports, ConsoleReporter};
+
+pub async fn execute(
+    report_path: PathBuf,
+    eval_set_path: PathBuf,
+    parallelism: usize,
+    output: PathBuf,
+    format: String,
+    config_path: Option<PathBuf>,
+) -> Result<()> {
+    anyhow::ensure!(parallelism >= 1, "parallelism must be at least 1");
+
+    let report = EvalReport::load_json(&report_path)?;
+    let config = load_config_from(config_path.as_deref())?;
+
+    // The report's eval set, as currently defined
+    let eval_sets = if eval_set_path.is_dir() {
+        parser::load_eval_directory(&eval_set_path)?
+    } else {
+        vec![parser::parse_eval_set(&eval_set_path)?]
+    };
+    let Some(eval_set) = eval_sets
+        .into_iter()
+        .find(|set| set.id == report.eval_set.id)
+    else {
+        anyhow::bail!(
+            "eval set '{}' of {} not found in {}",
+            report.eval_set.id,
+            report_path.display(),
+            eval_set_path.display()
+        );
+    };
+
+    // Generations made for a different prompt don't answer the case any more
+    if report.cases.is_empty() {
+        eprintln!(
+            "Warning: {} has no case snapshots; changed prompts can't be detected",
+            report_path.display()
+        );
+    }
+    for case_id in report.changed_prompts(&eval_set) {
+        eprintln!(
+            "Warning: the prompt of '{case_id}' changed since the report was made; \
+             its generations answer the old prompt and are no longer valid"
+        );
+    }
+    let dropped = report
+        .results
+        .iter()
+        .filter(|r| !eval_set.cases.iter().any(|c| c.id == r.case_id))
+        .count();
+    if dropped > 0 {
+        eprintln!("Warning: dropping {dropped} results for cases no longer in the eval set");
+    }
+
+    // Pass@k as in the original report
+    let mut pass_k: Vec<u32> = report
+        .aggregate
+        .per_model
+        .values()
+        .flat_map(|stats| stats.pass_at_k.keys().copied())
+        .collect();
+    pass_k.sort_unstable();
+    pass_k.dedup();
+    if pass_k.is_empty() {
+        pass_k.push(1);
+    }
+
+    let engine_config = EvalEngineConfig {
+        parallelism,
+        pass_k,
+        ..EvalEngineConfig::default()
+    };
+    let profile = eval_set
+        .scoring
+        .clone()
+        .or_else(|| config.scoring.clone())
+        .unwrap_or_default();
+    for problem in profile.problems() {
+        eprintln!("Warning: {problem}");
+    }
+    let runner = Arc::new(LocalRunner::new(output.join(".forgetest-target")));
+    let engine = EvalEngine::new(HashMap::new(), runner, engine_config)
+        .with_scorer(Arc::new(DefaultScorer::from_profile(profile)));
+
+    eprintln!(
+        "forgetest v0.1.0 — Rescoring {} generations from {} against the current eval set",
+        report.results.len() - dropped,
+        report_path.display()
+    );
+    eprintln!();
+
+    let rescored = engine.rescore(&eval_set, &report, &ConsoleReporter).await?;
+    print_summary(&rescored);
+    eprintln!("Rescored from report {}", report.id);
+
+    write_reports(&rescored, &output, &format)
+}
@@ -1,24 +1,48 @@
 //! The `forgetest run` command.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
 
+use forgetest_core::budget::{Budget, BudgetConfig, BudgetLimits};
+use forgetest_core::cache::ResponseCache;
+use forgetest_core::confidence::{percent_with_interval, IntervalConfig};
 use forgetest_core::engine::{EvalEngine, EvalEngineConfig, ModelSpec, ProgressReporter};
+use forgetest_core::journal::{Journal, JournalContents, JournalEntry, JournalHeader, RunSettings};
+use forgetest_core::judge::Judge;
+use forgetest_core::model::EvalSet;
 use forgetest_core::parser;
+use forgetest_core::plan::{plan_run, PlanConfig, RunPlan, CHARS_PER_TOKEN};
+use forgetest_core::report::{CaseSnapshot, EvalReport};
 use forgetest_core::results::EvalResult;
+use forgetest_core::scoring::DefaultScorer;
+use forgetest_core::statistics::{group_order, ModelStats};
+use forgetest_core::taxonomy::ErrorCategory;
 use forgetest_core::traits::LlmProvider;
 use forgetest_providers::config::load_config_from;
 use forgetest_providers::create_provider;
+use forgetest_providers::replay::{Cassette, CassetteRecorder, RecordingProvider, ReplayProvider};
 use forgetest_report::html::write_html_report;
+use forgetest_report::markdown::write_markdown_report;
 use forgetest_report::sarif::write_sarif_report;
 use forgetest_runner::LocalRunner;
 
+/// Name of the journal of the run in progress in an output directory.
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// Where provider interactions come from or go to.
+pub enum CassetteMode {
+    /// Call the configured providers and record their interactions here.
+    Record(PathBuf),
+    /// Replay the interactions recorded here instead of calling providers.
+    Replay(PathBuf),
+}
+
 /// Console progress reporter.
-struct ConsoleReporter;
+pub(crate) struct ConsoleReporter;
 
 impl ProgressReporter for ConsoleReporter {
     fn on_eval_start(&self, case_id: &str, model: &str, attempt: u32) {
@@ -35,14 +59,27 @@ impl ProgressReporter for ConsoleReporter {
             Some(t) => format!(" tests {}/{}", t.passed, t.passed + t.failed),
             None => String::new(),
         };
+        let judge_info = match &result.judge {
+            Some(j) => format!(" judge {:.0}%", j.score * 100.0),
+            None => String::new(),
+        };
+        let miri_info = match &result.miri {
+            Some(m) if m.skipped => " miri SKIPPED".to_string(),
+            Some(m) if m.ub_detected() => format!(" miri UB ({})", m.findings.len()),
+            Some(_) => " miri OK".to_string(),
+            None => String::new(),
+        };
         eprintln!(
-            "  Done: {} :: {} [{}] compile {}{} ({}ms)",
+            "  Done: {} :: {} [{}] compile {}{}{}{} ({}ms{})",
             result.model,
             result.case_id,
             result.attempt,
             compile_icon,
             test_info,
+            miri_info,
+            judge_info,
             result.timing.total_ms,
+            if result.cached { ", cached" } else { "" },
         );
     }
 
@@ -69,13 +106,61 @@ pub async fn execute(
     format: String,
     filter: Option<String>,
     config_path: Option<PathBuf>,
+    judge_str: Option<String>,
+    intervals: IntervalConfig,
+    resume: bool,
+    cache: Option<ResponseCache>,
+    cassette: Option<CassetteMode>,
+    budget: BudgetConfig,
+    dry_run: bool,
+    completion_tokens: u32,
 ) -> Result<()> {
     // Validate inputs
     anyhow::ensure!(parallelism >= 1, "parallelism must be at least 1");
+    anyhow::ensure!(
+        intervals.level > 0.0 && intervals.level < 1.0,
+        "confidence must be between 0.0 and 1.0 (exclusive)"
+    );
     anyhow::ensure!(
         (0.0..=2.0).contains(&temperature),
         "temperature must be between 0.0 and 2.0"
     );
+    // Cache hits and resumed results never reach a provider, so a cassette
+    // recorded alongside them would be missing interactions
+    if matches!(cassette, Some(CassetteMode::Record(_))) {
+        anyhow::ensure!(
+            !cache.as_ref().is_some_and(|c| c.mode().reads()),
+            "--record can't be used with --cache read: cached responses would be missing from the cassette"
+        );
+        anyhow::ensure!(
+            !resume,
+            "--record can't be used with --resume: earlier results would be missing from the cassette"
+        );
+    }
+
+    // Results stream into a journal in the output directory; finding one
+    // means a run there didn't finish
+    let journal_path = output.join(JOURNAL_FILE);
+    let journaled = if dry_run {
+        JournalContents::default()
+    } else if resume {
+        anyhow::ensure!(
+            journal_path.exists(),
+            "no unfinished run in {}: {} not found",
+            output.display(),
+            journal_path.display()
+        );
+        Journal::load(&journal_path)?
+    } else {
+        anyhow::ensure!(
+            !journal_path.exists(),
+            "{} holds an unfinished run; continue it with --resume {} or remove {}",
+            output.display(),
+            output.display(),
+            journal_path.display()
+        );
+        JournalContents::default()
+    };
 
     // Load config
     let config = load_config_from(config_path.as_deref())?;
@@ -97,23 +182,22 @@ pub async fn execute(
     }
 
     // Parse models
+    let parse_model = |s: &str| {
+        let parts: Vec<&str> = s.trim().splitn(2, '/').collect();
+        if parts.len() == 2 {
+            ModelSpec {
+                provider: parts[0].to_string(),
+                model: parts[1].to_string(),
+            }
+        } else {
+            ModelSpec {
+                provider: config.default_provider.clone(),
+                model: parts[0].to_string(),
+            }
+        }
+    };
     let models: Vec<ModelSpec> = if let Some(m) = &models_str {
-        m.split(',')
-            .map(|s| {
-                let parts: Vec<&str> = s.trim().splitn(2, '/').collect();
-                if parts.len() == 2 {
-                    ModelSpec {
-                        provider: parts[0].to_string(),
-                        model: parts[1].to_string(),
-                    }
-                } else {
-                    ModelSpec {
-                        provider: config.default_provider.clone(),
-                        model: parts[0].to_string(),
-                    }
-                }
-            })
-            .collect()
+        m.split(',').map(parse_model).collect()
     } else {
         vec![ModelSpec {
             provider: config.default_provider.clone(),
@@ -145,16 +229,46 @@ pub async fn execute(
         );
     }
 
-    // Create providers
+    let judge_spec = judge_str.as_deref().map(parse_model);
+
+    // Create providers; a replayed cassette stands in for all of them
+    let (replay, recorder) = match &cassette {
+        Some(CassetteMode::Replay(path)) => {
+            let cassette = Cassette::load(path)?;
+            eprintln!(
+                "Replaying {} recorded interactions from {}",
+                cassette.interactions.len(),
+                path.display()
+            );
+            (Some(cassette), None)
+        }
+        Some(CassetteMode::Record(path)) if !dry_run => {
+            eprintln!("Recording provider interactions to {}", path.display());
+            (None, Some(Arc::new(CassetteRecorder::create(path)?)))
+        }
+        _ => (None, None),
+    };
     let mut providers: HashMap<String, Arc<dyn LlmProvider>> = HashMap::new();
-    for model_spec in &models {
+    for model_spec in models.iter().chain(&judge_spec) {
         if providers.contains_key(&model_spec.provider) {
             continue;
         }
-        if let Some(pconfig) = config.providers.get(&model_spec.provider) {
-            let provider = create_provider(&model_spec.provider, pconfig)?;
-            providers.insert(model_spec.provider.clone(), Arc::from(provider));
-        } else {
+        if let Some(cassette) = &replay {
+            let provider = ReplayProvider::new(cassette, &model_spec.provider);
+            providers.insert(model_spec.provider.clone(), Arc::new(provider));
+        } else if let Some(pconfig) = config.providers.get(&model_spec.provider) {
+            let mut provider: Arc<dyn LlmProvider> =
+                Arc::from(create_provider(&model_spec.provider, pconfig)?);
+            if let Some(recorder) = &recorder {
+                provider = Arc::new(RecordingProvider::new(
+                    provider,
+                    model_spec.provider.clone(),
+                    Arc::clone(recorder),
+                ));
+            }
+            providers.insert(model_spec.provider.clone(), provider);
+        } else if !dry_run {
+            // A dry run reports unknown providers with its other problems
             anyhow::bail!(
                 "provider '{}' not found in config. Available: {:?}",
                 model_spec.provider,
@@ -163,6 +277,11 @@ pub async fn execute(
         }
     }
 
+    let budget = BudgetConfig {
+        global: budget.global.or(config.budget.global),
+        per_model: budget.per_model.or(config.budget.per_model),
+    };
+
     let engine_config = EvalEngineConfig {
         parallelism,
         pass_k: pass_k.clone(),
@@ -171,16 +290,115 @@ pub async fn execute(
         max_retries_per_case: config.max_retries,
         retry_delay: Duration::from_millis(config.retry_delay_ms),
         system_prompt_override: None,
+        intervals,
     };
 
+    if dry_run {
+        let plan = plan_run(
+            &eval_sets,
+            &models,
+            &providers,
+            &PlanConfig {
+                attempts: max_k,
+                completion_tokens,
+                max_tokens: engine_config.max_tokens,
+                system_prompt: engine_config.system_prompt_override.clone(),
+                judge: judge_spec.clone(),
+            },
+        );
+        print_plan(&plan, completion_tokens, &budget);
+        anyhow::ensure!(
+            plan.problems.is_empty(),
+            "dry run found {} problem(s)",
+            plan.problems.len()
+        );
+        return Ok(());
+    }
+
     // Create the sandboxed code runner
     let shared_target = output.join(".forgetest-target");
     let runner = Arc::new(LocalRunner::new(shared_target));
 
-    let engine = EvalEngine::new(providers, runner, engine_config);
+    // One judge for all eval sets so its verdict cache is shared
+    let judge = judge_spec.map(|spec| {
+        Arc::new(Judge::new(
+            Arc::clone(&providers[&spec.provider]),
+            spec.model,
+        ))
+    });
+
     let reporter = ConsoleReporter;
 
+    // A resumed run continues with the settings and cases it started with
+    let header = JournalHeader::new(RunSettings::new(&engine_config, &models), &eval_sets);
+    let JournalContents {
+        header: started_with,
+        entries: mut completed,
+    } = journaled;
+    let journal = if resume {
+        match &started_with {
+            Some(before) => {
+                let differences = header.settings.differences(&before.settings);
+                anyhow::ensure!(
+                    differences.is_empty(),
+                    "the unfinished run in {} used different settings ({}); resume it with \
+                     the original options or remove {}",
+                    output.display(),
+                    differences.join("; "),
+                    journal_path.display()
+                );
+                drop_changed_cases(before, &eval_sets, &mut completed);
+            }
+            None => eprintln!(
+                "Warning: {} has no header, so changed settings or cases can't be detected",
+                journal_path.display()
+            ),
+        }
+        // Rewritten with the current case definitions, so results generated
+        // for changed cases from now on are kept by later resumes
+        Journal::write(&journal_path, &header, &completed)?;
+        Journal::open(&journal_path)?
+    } else {
+        std::fs::create_dir_all(&output)?;
+        Journal::create(&journal_path, &header)?
+    };
+    let journal = Arc::new(journal);
+    let budget = (!budget.is_unlimited()).then(|| {
+        eprintln!("Budget: {}", describe_budget(&budget));
+        Arc::new(Budget::new(budget))
+    });
+    let cache = cache.map(Arc::new);
+    if let Some(cache) = &cache {
+        eprintln!(
+            "Response cache: {} ({})",
+            cache.dir().display(),
+            cache.mode()
+        );
+    }
+
     for eval_set in &eval_sets {
+        // The eval set's scoring profile takes precedence over the config's
+        let profile = eval_set
+            .scoring
+            .clone()
+            .or_else(|| config.scoring.clone())
+            .unwrap_or_default();
+        for problem in profile.problems() {
+            eprintln!("Warning: {problem}");
+        }
+        let mut engine = EvalEngine::new(providers.clone(), runner.clone(), engine_config.clone())
+            .with_scorer(Arc::new(DefaultScorer::from_profile(profile)));
+        if let Some(judge) = &judge {
+            engine = engine.with_judge(Arc::clone(judge));
+        }
+        engine = engine.with_journal(Arc::clone(&journal));
+        if let Some(cache) = &cache {
+            engine = engine.with_cache(Arc::clone(cache));
+        }
+        if let Some(budget) = &budget {
+            engine = engine.with_budget(Arc::clone(budget));
+        }
+
         let case_count = eval_set.cases.len();
         let model_count = models.len();
         let max_k = pass_k.iter().copied().max().unwrap_or(1);
@@ -188,51 +406,218 @@ pub async fn execute(
             "forgetest v0.1.0 — Running {} eval cases x {} models x {} attempts",
             case_count, model_count, max_k
         );
+        let earlier: Vec<EvalResult> = completed
+            .iter()
+            .filter(|e| e.eval_set == eval_set.id)
+            .map(|e| e.result.clone())
+            .collect();
+        if !earlier.is_empty() {
+            eprintln!(
+                "Resuming: {} results already in {}",
+                earlier.len(),
+                journal_path.display()
+            );
+        }
         eprintln!();
 
-        let report = engine.run(eval_set, &models, &reporter).await?;
+        let report = engine.resume(eval_set, &models, &reporter, earlier).await?;
 
         // Print summary table
         print_summary(&report);
 
         // Save outputs
-        std::fs::create_dir_all(&output)?;
-        let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H%M%S");
+        write_reports(&report, &output, &format)?;
+    }
+
+    // The run is finalized: keep its journal next to the reports
+    drop(journal);
+    let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H%M%S");
+    let finished = output.join(format!("journal-{timestamp}.jsonl"));
+    std::fs::rename(&journal_path, &finished)?;
+    eprintln!("Journal: {}", finished.display());
 
-        let formats: Vec<&str> = if format == "all" {
-            vec!["json", "html", "sarif"]
+    Ok(())
+}
+
+/// Drop journaled results for cases whose definition changed since `before`
+/// was written, as `compare` does: they answer a different case.
+fn drop_changed_cases(
+    before: &JournalHeader,
+    eval_sets: &[EvalSet],
+    entries: &mut Vec<JournalEntry>,
+) {
+    let mut dropped: BTreeMap<(String, String), usize> = BTreeMap::new();
+    entries.retain(|entry| {
+        let changed = eval_sets
+            .iter()
+            .filter(|set| set.id == entry.eval_set)
+            .flat_map(|set| &set.cases)
+            .find(|case| case.id == entry.result.case_id)
+            .is_some_and(|case| {
+                before.case_changed(&entry.eval_set, &case.id, &CaseSnapshot::from_case(case))
+            });
+        if changed {
+            *dropped
+                .entry((entry.eval_set.clone(), entry.result.case_id.clone()))
+                .or_default() += 1;
+        }
+        !changed
+    });
+    for ((eval_set, case_id), count) in dropped {
+        eprintln!(
+            "Warning: case '{case_id}' of {eval_set} changed since the run started; \
+             dropping its {count} journaled results"
+        );
+    }
+}
+
+/// Print what a run would evaluate and its estimated cost.
+fn print_plan(plan: &RunPlan, completion_tokens: u32, budget: &BudgetConfig) {
+    use comfy_table::{Cell, Table};
+
+    println!("Dry run: nothing will be generated\n");
+
+    let mut table = Table::new();
+    table.set_header(vec![
+        "Eval set",
+        "Cases",
+        "Models",
+        "Attempts",
+        "Generations",
+    ]);
+    for set in &plan.sets {
+        table.add_row(vec![
+            Cell::new(&set.id),
+            Cell::new(set.cases),
+            Cell::new(set.models),
+            Cell::new(set.attempts),
+            Cell::new(set.generations),
+        ]);
+    }
+    println!("{table}");
+    println!("Total: {} generations\n", plan.generations());
+
+    let mut table = Table::new();
+    table.set_header(vec![
+        "Model",
+        "Requests",
+        "Input tokens",
+        "Output tokens",
+        "Est. cost",
+    ]);
+    for (model, role) in plan
+        .models
+        .iter()
+        .map(|m| (m, ""))
+        .chain(plan.judge.iter().map(|m| (m, " (judge)")))
+    {
+        table.add_row(vec![
+            Cell::new(format!("{}/{}{role}", model.provider, model.model)),
+            Cell::new(model.generations),
+            Cell::new(model.input_tokens),
+            Cell::new(model.output_tokens),
+            Cell::new(match model.cost_usd {
+                Some(cost) => format!("${cost:.4}"),
+                None => "unknown".to_string(),
+            }),
+        ]);
+    }
+    println!("{table}");
+    println!(
+        "Estimated cost: ${:.4}{}",
+        plan.cost_usd(),
+        if plan.cost_incomplete() {
+            " (at least; some models have no pricing)"
         } else {
-            format.split(',').collect()
-        };
+            ""
+        }
+    );
+    println!(
+        "Input tokens assume ~{CHARS_PER_TOKEN} characters per token; completions are assumed to be \
+         {completion_tokens} tokens (--completion-tokens), capped at each case's max_tokens."
+    );
+    if plan.judge.is_some() {
+        println!("The judge is assumed to grade every generation of a case with a rubric.");
+    }
+    if let Some(max) = budget.global.max_cost_usd {
+        if plan.cost_usd() > max {
+            println!("The estimate exceeds the run budget of ${max:.2}; the run would stop early.");
+        }
+    }
 
-        for fmt in &formats {
-            match *fmt {
-                "json" => {
-                    let path = output.join(format!("report-{timestamp}.json"));
-                    report.save_json(&path)?;
-                    eprintln!("Results saved to: {}", path.display());
-                }
-                "html" => {
-                    let path = output.join(format!("report-{timestamp}.html"));
-                    write_html_report(&report, &path)?;
-                    eprintln!("HTML report: {}", path.display());
-                }
-                "sarif" => {
-                    let path = output.join(format!("report-{timestamp}.sarif"));
-                    write_sarif_report(&report, &path)?;
-                    eprintln!("SARIF report: {}", path.display());
-                }
-                _ => {
-                    eprintln!("Unknown format: {fmt}");
-                }
-            }
+    if !plan.problems.is_empty() {
+        println!("\nProblems:");
+        for problem in &plan.problems {
+            println!("  - {problem}");
         }
     }
+}
 
+/// The limits of a budget, e.g. "run: $20.00, 500 requests; per model: 1000000 tokens".
+fn describe_budget(budget: &BudgetConfig) -> String {
+    let describe = |limits: &BudgetLimits| {
+        let mut parts = vec![];
+        if let Some(usd) = limits.max_cost_usd {
+            parts.push(format!("${usd:.2}"));
+        }
+        if let Some(tokens) = limits.max_tokens {
+            parts.push(format!("{tokens} tokens"));
+        }
+        if let Some(requests) = limits.max_requests {
+            parts.push(format!("{requests} requests"));
+        }
+        parts.join(", ")
+    };
+    [("run", &budget.global), ("per model", &budget.per_model)]
+        .into_iter()
+        .filter(|(_, limits)| !limits.is_unlimited())
+        .map(|(scope, limits)| format!("{scope}: {}", describe(limits)))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Write `report` to `output` in each of the comma-separated formats.
+pub(crate) fn write_reports(report: &EvalReport, output: &Path, format: &str) -> Result<()> {
+    std::fs::create_dir_all(output)?;
+    let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H%M%S");
+
+    let formats: Vec<&str> = if format == "all" {
+        vec!["json", "html", "markdown", "sarif"]
+    } else {
+        format.split(',').collect()
+    };
+
+    for fmt in &formats {
+        match *fmt {
+            "json" => {
+                let path = output.join(format!("report-{timestamp}.json"));
+                report.save_json(&path)?;
+                eprintln!("Results saved to: {}", path.display());
+            }
+            "html" => {
+                let path = output.join(format!("report-{timestamp}.html"));
+                write_html_report(report, &path)?;
+                eprintln!("HTML report: {}", path.display());
+            }
+            "markdown" | "md" => {
+                let path = output.join(format!("report-{timestamp}.md"));
+                write_markdown_report(report, &path)?;
+                eprintln!("Markdown report: {}", path.display());
+            }
+            "sarif" => {
+                let path = output.join(format!("report-{timestamp}.sarif"));
+                write_sarif_report(report, &path)?;
+                eprintln!("SARIF report: {}", path.display());
+            }
+            _ => {
+                eprintln!("Unknown format: {fmt}");
+            }
+        }
+    }
     Ok(())
 }
 
-fn print_summary(report: &forgetest_core::report::EvalReport) {
+pub(crate) fn print_summary(report: &EvalReport) {
     use comfy_table::{Cell, Table};
 
     let mut table = Table::new();
@@ -243,19 +628,165 @@ fn print_summary(report: &forgetest_core::report::EvalReport) {
         "Test Pass %",
         "Cost",
         "Latency",
+        "Judge",
+        "Halluc. APIs",
     ]);
 
+    let mut interval_note = None;
     for (model, stats) in &report.aggregate.per_model {
         let pass_1 = stats.pass_at_k.get(&1).copied().unwrap_or(0.0);
+        let ci = stats.intervals.as_ref();
+        if let Some(ci) = ci {
+            interval_note = Some(format!("Ranges in brackets: {}", ci.describe()));
+        }
         table.add_row(vec![
             Cell::new(model),
-            Cell::new(format!("{:.1}%", pass_1 * 100.0)),
-            Cell::new(format!("{:.1}%", stats.avg_compilation_rate * 100.0)),
-            Cell::new(format!("{:.1}%", stats.avg_test_pass_rate * 100.0)),
+            Cell::new(percent_with_interval(
+                pass_1,
+                ci.and_then(|c| c.pass_at_k.get(&1)),
+            )),
+            Cell::new(percent_with_interval(
+                stats.avg_compilation_rate,
+                ci.and_then(|c| c.compilation_rate.as_ref()),
+            )),
+            Cell::new(percent_with_interval(
+                stats.avg_test_pass_rate,
+                ci.and_then(|c| c.test_pass_rate.as_ref()),
+            )),
             Cell::new(format!("${:.4}", stats.total_cost_usd)),
             Cell::new(format!("{}ms", stats.avg_latency_ms)),
+            Cell::new(match stats.avg_judge_score {
+                Some(score) => format!("{:.1}% (${:.4})", score * 100.0, stats.judge_cost_usd),
+                None => "-".to_string(),
+            }),
+            Cell::new(format!(
+                "{:.1}% ({})",
+                stats.hallucination_rate * 100.0,
+                stats.hallucinated_apis
+            )),
         ]);
     }
 
     eprintln!("\n{table}");
+    if let Some(note) = interval_note {
+        eprintln!("{note}");
+    }
+    let cached: usize = report
+        .aggregate
+        .per_model
+        .values()
+        .map(|s| s.cached_results)
+        .sum();
+    if cached > 0 {
+        let saved: f64 = report
+            .aggregate
+            .per_model
+            .values()
+            .map(|s| s.cached_cost_usd)
+            .sum();
+        eprintln!(
+            "{cached} generations came from the response cache (${saved:.4} not spent; excluded from cost and latency)"
+        );
+    }
+
+    if let Some(budget) = &report.budget {
+        eprintln!(
+            "Spent ${:.4}, {} tokens, {} requests (budget {})",
+            budget.spent.cost_usd,
+            budget.spent.tokens,
+            budget.spent.requests,
+            describe_budget(&budget.limits)
+        );
+        if budget.judge.requests > 0 {
+            eprintln!(
+                "  of which the judge: ${:.4}, {} tokens, {} requests",
+                budget.judge.cost_usd, budget.judge.tokens, budget.judge.requests
+            );
+        }
+        if budget.is_partial() {
+            for stop in &budget.exhausted {
+                eprintln!("PARTIAL REPORT: {stop}");
+            }
+            eprintln!(
+                "PARTIAL REPORT: {} generations skipped; these results are incomplete",
+                budget.skipped
+            );
+        }
+    }
+
+    // Compile failures per model and error category
+    let mut models: Vec<(&String, &ModelStats)> = report
+        .aggregate
+        .per_model
+        .iter()
+        .filter(|(_, stats)| !stats.failure_modes.is_empty())
+        .collect();
+    if !models.is_empty() {
+        models.sort_by_key(|(model, _)| *model);
+        let categories: Vec<ErrorCategory> = ErrorCategory::ALL
+            .into_iter()
+            .filter(|c| models.iter().any(|(_, s)| s.failure_modes.contains_key(c)))
+            .collect();
+        let mut table = Table::new();
+        table.set_header(
+            std::iter::once("Model".to_string()).chain(categories.iter().map(|c| c.to_string())),
+        );
+        for (model, stats) in models {
+            let mut row = vec![Cell::new(model)];
+            for category in &categories {
+                row.push(Cell::new(
+                    stats.failure_modes.get(category).copied().unwrap_or(0),
+                ));
+            }
+            table.add_row(row);
+        }
+        eprintln!("\nAttempts failing to compile, by error category:\n{table}");
+    }
+
+    // Calls into dependency APIs that don't exist
+    let hallucinated: Vec<_> = report
+        .results
+        .iter()
+        .flat_map(|r| r.hallucinated_apis.iter().map(move |api| (r, api)))
+        .collect();
+    if !hallucinated.is_empty() {
+        eprintln!("\nNon-existent dependency APIs:");
+        for (r, api) in hallucinated {
+            match &api.suggestion {
+                Some(suggestion) => eprintln!(
+                    "  {}/{}: {} (did you mean {suggestion}?)",
+                    r.case_id, r.model, api.path
+                ),
+                None => eprintln!("  {}/{}: {}", r.case_id, r.model, api.path),
+            }
+        }
+    }
+
+    // Pass rate per model and tag / difficulty
+    for (title, groups) in [
+        ("tag", &report.aggregate.per_tag),
+        ("difficulty", &report.aggregate.per_difficulty),
+    ] {
+        if groups.is_empty() {
+            continue;
+        }
+        let labels = group_order(groups);
+        let mut models: Vec<&String> = groups.values().flat_map(|m| m.keys()).collect();
+        models.sort();
+        models.dedup();
+
+        let mut table = Table::new();
+        table.set_header(std::iter::once("Model").chain(labels.iter().copied()));
+        for model in models {
+            let mut row = vec![Cell::new(model)];
+            for label in &labels {
+                row.push(Cell::new(match groups[*label].get(model) {
+                    Some(stats) => format!("{:.1}% ({})", stats.pass_rate * 100.0, stats.cases),
+                    None => "-".to_string(),
+                }));
+            }
+            table.add_row(row);
+        }
+        eprintln!("\nPass rate by {title} (cases in parentheses):\n{table}");
+    }
 }
@@ -4,6 +4,11 @@ use std::path::PathBuf;
 use std::process;
 
 use clap::{Parser, Subcommand};
+use forgetest_core::budget::{BudgetConfig, BudgetLimits};
+use forgetest_core::cache::{CacheMode, ResponseCache};
+use forgetest_core::confidence::{IntervalConfig, IntervalMethod};
+use forgetest_core::leaderboard::LeaderboardConfig;
+use forgetest_core::significance::SignificanceConfig;
 
 mod commands;
 
@@ -14,6 +19,8 @@ struct Cli {
     command: Commands,
 }
 
+// Parsed once per process, so the size of `Run` doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Run evaluations
@@ -42,7 +49,12 @@ enum Commands {
         #[arg(long, default_value = "./forgetest-results")]
         output: PathBuf,
 
-        /// Output format: json, html, sarif, all
+        /// Continue the unfinished run in this output directory, skipping
+        /// results already in its journal (pass the run's other options again)
+        #[arg(long, value_name = "RUN_DIR", conflicts_with = "output")]
+        resume: Option<PathBuf>,
+
+        /// Output format: json, html, markdown, sarif, all
         #[arg(long, default_value = "json")]
         format: String,
 
@@ -53,17 +65,127 @@ enum Commands {
         /// Config file path
         #[arg(long)]
         config: Option<PathBuf>,
+
+        /// Grade cases that have a judge rubric with this model (e.g. "anthropic/claude-sonnet-4-20250514")
+        #[arg(long)]
+        judge: Option<String>,
+
+        /// Confidence level for the intervals on aggregate metrics
+        #[arg(long, default_value = "0.95")]
+        confidence: f64,
+
+        /// Bootstrap resamples for the confidence intervals
+        #[arg(long, default_value = "1000")]
+        resamples: u32,
+
+        /// Confidence interval method: bootstrap, wilson
+        #[arg(long, default_value = "bootstrap")]
+        ci_method: IntervalMethod,
+
+        /// Response cache: read (reuse and store), write (refresh), off
+        #[arg(long, default_value = "off")]
+        cache: CacheMode,
+
+        /// Response cache directory
+        #[arg(long, default_value = DEFAULT_CACHE_DIR)]
+        cache_dir: PathBuf,
+
+        /// Record every provider request and response to this cassette file
+        /// (not with --cache read or --resume, whose results never reach a
+        /// provider)
+        #[arg(long, value_name = "CASSETTE", conflicts_with = "replay")]
+        record: Option<PathBuf>,
+
+        /// Replay responses from this cassette file instead of calling
+        /// providers (no network or API keys needed)
+        #[arg(long, value_name = "CASSETTE")]
+        replay: Option<PathBuf>,
+
+        /// Stop generating once the run has cost this many USD
+        #[arg(long, value_name = "USD")]
+        budget_usd: Option<f64>,
+
+        /// Stop generating once the run has used this many tokens
+        #[arg(long, value_name = "TOKENS")]
+        budget_tokens: Option<u64>,
+
+        /// Stop generating once the run has made this many provider requests
+        #[arg(long, value_name = "REQUESTS")]
+        budget_requests: Option<u64>,
+
+        /// Stop generating for a model once it has cost this many USD
+        #[arg(long, value_name = "USD")]
+        model_budget_usd: Option<f64>,
+
+        /// Stop generating for a model once it has used this many tokens
+        #[arg(long, value_name = "TOKENS")]
+        model_budget_tokens: Option<u64>,
+
+        /// Stop generating for a model once it has made this many requests
+        #[arg(long, value_name = "REQUESTS")]
+        model_budget_requests: Option<u64>,
+
+        /// Print the evaluation matrix and estimated cost, and check for
+        /// problems, without generating anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Completion length per generation to assume for --dry-run estimates
+        #[arg(long, default_value = "512", value_name = "TOKENS")]
+        completion_tokens: u32,
     },
 
-    /// Compare two eval reports
+    /// Re-run compilation, tests and scoring on a report's stored
+    /// generations against the current eval set, without regenerating
+    Rescore {
+        /// Report JSON whose generations to rescore
+        #[arg(long)]
+        report: PathBuf,
+
+        /// Path to the .toml eval set, or a directory containing it
+        #[arg(long)]
+        eval_set: PathBuf,
+
+        /// Max concurrent evals
+        #[arg(long, default_value = "4")]
+        parallelism: usize,
+
+        /// Output directory
+        #[arg(long, default_value = "./forgetest-results")]
+        output: PathBuf,
+
+        /// Output format: json, html, markdown, sarif, all
+        #[arg(long, default_value = "json")]
+        format: String,
+
+        /// Config file path
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+
+    /// Inspect or prune the LLM response cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+
+    /// Compare two eval reports, or two models within one report
     Compare {
         /// Baseline report JSON
-        #[arg(long)]
-        baseline: PathBuf,
+        #[arg(long, required_unless_present = "report")]
+        baseline: Option<PathBuf>,
 
         /// Current report JSON
-        #[arg(long)]
-        current: PathBuf,
+        #[arg(long, required_unless_present = "report")]
+        current: Option<PathBuf>,
+
+        /// Report JSON to compare two models within (head-to-head)
+        #[arg(long, conflicts_with_all = ["baseline", "current"], requires = "models")]
+        report: Option<PathBuf>,
+
+        /// The two models to compare head-to-head (e.g. "gpt-4.1,claude-sonnet-4")
+        #[arg(long, requires = "report")]
+        models: Option<String>,
 
         /// Regression threshold
         #[arg(long, default_value = "0.05")]
@@ -73,9 +195,45 @@ enum Commands {
         #[arg(long)]
         fail_on_regression: bool,
 
-        /// Output format: text, json, markdown
+        /// Output format: text, json, markdown (html also for head-to-head)
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// Regression detection: threshold (best-score drops) or significance
+        /// (permutation tests over all attempts)
+        #[arg(long, default_value = "threshold", value_parser = ["threshold", "significance"])]
+        mode: String,
+
+        /// Significance level for --mode significance
+        #[arg(long, default_value = "0.05")]
+        alpha: f64,
+
+        /// Permutations per test for --mode significance
+        #[arg(long, default_value = "10000")]
+        resamples: u32,
+    },
+
+    /// Rank models across many reports with Bradley-Terry ratings
+    Leaderboard {
+        /// Report JSON files, or directories of them
+        #[arg(long, num_args = 1.., value_delimiter = ',', required = true)]
+        reports: Vec<PathBuf>,
+
+        /// Output format: text, json, markdown, html
         #[arg(long, default_value = "text")]
         format: String,
+
+        /// Also write a static HTML page to this path
+        #[arg(long)]
+        html: Option<PathBuf>,
+
+        /// Confidence level for the rating intervals
+        #[arg(long, default_value = "0.95")]
+        confidence: f64,
+
+        /// Bootstrap resamples for the rating intervals
+        #[arg(long, default_value = "1000")]
+        resamples: u32,
     },
 
     /// Validate eval set TOML files
@@ -100,6 +258,38 @@ enum Commands {
     Init,
 }
 
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Show what the cache holds
+    Stats {
+        /// Response cache directory
+        #[arg(long, default_value = DEFAULT_CACHE_DIR)]
+        cache_dir: PathBuf,
+
+        /// Output format: text, json
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Remove cached responses
+    Prune {
+        /// Response cache directory
+        #[arg(long, default_value = DEFAULT_CACHE_DIR)]
+        cache_dir: PathBuf,
+
+        /// Only remove responses cached more than this many days ago
+        #[arg(long, required_unless_present = "all")]
+        older_than_days: Option<u64>,
+
+        /// Remove every cached response
+        #[arg(long, conflicts_with = "older_than_days")]
+        all: bool,
+    },
+}
+
+/// Default location of the LLM response cache.
+const DEFAULT_CACHE_DIR: &str = ".forgetest-cache";
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt()
@@ -119,10 +309,50 @@ async fn main() {
             parallelism,
             temperature,
             output,
+            resume,
             format,
             filter,
             config,
+            judge,
+            confidence,
+            resamples,
+            ci_method,
+            cache,
+            cache_dir,
+            record,
+            replay,
+            budget_usd,
+            budget_tokens,
+            budget_requests,
+            model_budget_usd,
+            model_budget_tokens,
+            model_budget_requests,
+            dry_run,
+            completion_tokens,
         } => {
+            let intervals = IntervalConfig {
+                method: ci_method,
+                level: confidence,
+                resamples,
+                ..IntervalConfig::default()
+            };
+            // Limits given here override those in the config file
+            let budget = BudgetConfig {
+                global: BudgetLimits {
+                    max_cost_usd: budget_usd,
+                    max_tokens: budget_tokens,
+                    max_requests: budget_requests,
+                },
+                per_model: BudgetLimits {
+                    max_cost_usd: model_budget_usd,
+                    max_tokens: model_budget_tokens,
+                    max_requests: model_budget_requests,
+                },
+            };
+            let (output, resume) = match resume {
+                Some(run_dir) => (run_dir, true),
+                None => (output, false),
+            };
             commands::run::execute(
                 eval_set,
                 models,
@@ -133,16 +363,85 @@ async fn main() {
                 format,
                 filter,
                 config,
+                judge,
+                intervals,
+                resume,
+                (cache != CacheMode::Off).then(|| ResponseCache::new(cache_dir, cache)),
+                record
+                    .map(commands::run::CassetteMode::Record)
+                    .or(replay.map(commands::run::CassetteMode::Replay)),
+                budget,
+                dry_run,
+                completion_tokens,
             )
             .await
         }
+        Commands::Rescore {
+            report,
+            eval_set,
+            parallelism,
+            output,
+            format,
+            config,
+        } => {
+            commands::rescore::execute(report, eval_set, parallelism, output, format, config).await
+        }
+        Commands::Compare {
+            report: Some(report),
+            models,
+            format,
+            ..
+        } => commands::compare::head_to_head(report, models.unwrap_or_default(), format),
         Commands::Compare {
             baseline,
             current,
             threshold,
             fail_on_regression,
             format,
-        } => commands::compare::execute(baseline, current, threshold, fail_on_regression, format),
+            mode,
+            alpha,
+            resamples,
+            ..
+        } => {
+            let significance = (mode == "significance").then(|| SignificanceConfig {
+                alpha,
+                resamples,
+                ..SignificanceConfig::default()
+            });
+            commands::compare::execute(
+                baseline.expect("required by clap"),
+                current.expect("required by clap"),
+                threshold,
+                fail_on_regression,
+                format,
+                significance,
+            )
+        }
+        Commands::Leaderboard {
+            reports,
+            format,
+            html,
+            confidence,
+            resamples,
+        } => {
+            let config = LeaderboardConfig {
+                level: confidence,
+                resamples,
+                ..LeaderboardConfig::default()
+            };
+            commands::leaderboard::execute(reports, format, html, config)
+        }
+        Commands::Cache {
+            action: CacheCommand::Stats { cache_dir, format },
+        } => commands::cache::stats(cache_dir, format),
+        Commands::Cache {
+            action:
+                CacheCommand::Prune {
+                    cache_dir,
+                    older_than_days,
+                    ..
+                },
+        } => commands::cache::prune(cache_dir, older_than_days),
         Commands::Validate { eval_set } => commands::validate::execute(eval_set),
         Commands::ListModels { provider, config } => {
             commands::list_models::execute(provider, config)
@@ -103,6 +103,301 @@ fn init_skips_existing() {
         .stdout(predicate::str::contains("already exists"));
 }
 
+#[test]
+fn resume_requires_an_unfinished_run() {
+    let dir = TempDir::new().unwrap();
+
+    forgetest()
+        .arg("run")
+        .arg("--eval-set")
+        .arg("../../eval-sets/rust-basics.toml")
+        .arg("--resume")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no unfinished run"));
+
+    // A fresh run won't clobber an unfinished one
+    std::fs::write(dir.path().join("journal.jsonl"), "").unwrap();
+    forgetest()
+        .arg("run")
+        .arg("--eval-set")
+        .arg("../../eval-sets/rust-basics.toml")
+        .arg("--output")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--resume"));
+}
+
+#[test]
+fn resume_refuses_changed_settings() {
+    let dir = TempDir::new().unwrap();
+    let cassette = dir.path().join("cassette.jsonl");
+    std::fs::write(&cassette, "{\"version\": 2}\n").unwrap();
+    let header = serde_json::json!({"header": {
+        "settings": {
+            "models": ["anthropic/claude-sonnet-4-20250514"],
+            "pass_k": [1],
+            "temperature": 0.5,
+            "max_tokens": 4096
+        },
+        "cases": {}
+    }});
+    std::fs::write(dir.path().join("journal.jsonl"), format!("{header}\n")).unwrap();
+
+    forgetest()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .arg("run")
+        .arg("--eval-set")
+        .arg(std::fs::canonicalize("../../eval-sets/rust-basics.toml").unwrap())
+        .arg("--models")
+        .arg("anthropic/claude-sonnet-4-20250514")
+        .arg("--replay")
+        .arg(&cassette)
+        .arg("--resume")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("used different settings"))
+        .stderr(predicate::str::contains("temperature: 0.5 -> 0"));
+}
+
+#[test]
+fn replay_needs_no_provider_config() {
+    let dir = TempDir::new().unwrap();
+    let cassette = dir.path().join("cassette.jsonl");
+    std::fs::write(&cassette, "{\"version\": 2}\n").unwrap();
+
+    // No provider is configured, yet every request reaches the cassette
+    forgetest()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .env_remove("FORGETEST_ANTHROPIC_KEY")
+        .arg("run")
+        .arg("--eval-set")
+        .arg(std::fs::canonicalize("../../eval-sets/rust-basics.toml").unwrap())
+        .arg("--models")
+        .arg("anthropic/claude-sonnet-4-20250514")
+        .arg("--replay")
+        .arg(&cassette)
+        .arg("--output")
+        .arg(dir.path().join("out"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Replaying 0 recorded interactions",
+        ))
+        .stderr(predicate::str::contains("no recorded response"));
+
+    forgetest()
+        .arg("run")
+        .arg("--eval-set")
+        .arg("../../eval-sets/rust-basics.toml")
+        .arg("--record")
+        .arg(&cassette)
+        .arg("--replay")
+        .arg(&cassette)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    for conflicting in [["--cache", "read"], ["--resume", "run-dir"]] {
+        forgetest()
+            .arg("run")
+            .arg("--eval-set")
+            .arg("../../eval-sets/rust-basics.toml")
+            .arg("--record")
+            .arg(&cassette)
+            .args(conflicting)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("can't be used with"));
+    }
+}
+
+#[test]
+fn dry_run_prints_matrix_and_problems() {
+    let dir = TempDir::new().unwrap();
+    let output = dir.path().join("out");
+
+    forgetest()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .env_remove("FORGETEST_ANTHROPIC_KEY")
+        .arg("run")
+        .arg("--eval-set")
+        .arg(std::fs::canonicalize("../../eval-sets/rust-basics.toml").unwrap())
+        .arg("--models")
+        .arg("anthropic/claude-sonnet-4-20250514,nope/model")
+        .arg("--pass-k")
+        .arg("1,2")
+        .arg("--judge")
+        .arg("grader/judge-model")
+        .arg("--output")
+        .arg(&output)
+        .arg("--dry-run")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Total: 60 generations"))
+        .stdout(predicate::str::contains("nope/model"))
+        .stdout(predicate::str::contains(
+            "provider 'nope' is not configured",
+        ))
+        .stdout(predicate::str::contains(
+            "provider 'grader' is not configured",
+        ))
+        .stderr(predicate::str::contains("dry run found 3 problem(s)"));
+
+    // Nothing was run or written
+    assert!(!output.exists());
+}
+
+#[test]
+fn budget_stops_run_with_partial_report() {
+    let dir = TempDir::new().unwrap();
+    let cassette = dir.path().join("cassette.jsonl");
+    std::fs::write(&cassette, "{\"version\": 2}\n").unwrap();
+
+    forgetest()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .arg("run")
+        .arg("--eval-set")
+        .arg(std::fs::canonicalize("../../eval-sets/rust-basics.toml").unwrap())
+        .arg("--models")
+        .arg("anthropic/claude-sonnet-4-20250514")
+        .arg("--replay")
+        .arg(&cassette)
+        .arg("--budget-requests")
+        .arg("3")
+        .arg("--parallelism")
+        .arg("1")
+        .arg("--output")
+        .arg(dir.path().join("out"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "PARTIAL REPORT: run budget exhausted: request limit 3 reached",
+        ))
+        .stderr(predicate::str::contains(
+            "PARTIAL REPORT: 12 generations skipped",
+        ));
+}
+
+#[test]
+fn rescore_warns_about_changed_prompts() {
+    let dir = TempDir::new().unwrap();
+    let report = dir.path().join("report.json");
+    let write_report = |eval_set: &str| {
+        let json = serde_json::json!({
+            "id": "00000000-0000-0000-0000-000000000001",
+            "created_at": "2025-01-01T00:00:00Z",
+            "eval_set": {"id": eval_set, "name": "Rust Basics", "case_count": 1},
+            "models_evaluated": [],
+            "results": [],
+            "aggregate": {"per_model": {}, "per_case": {}},
+            "cases": {
+                "fibonacci": {
+                    "expectations": {"should_compile": true, "should_pass_tests": true},
+                    "prompt_hash": "stale"
+                }
+            },
+            "duration_ms": 0
+        });
+        std::fs::write(&report, json.to_string()).unwrap();
+    };
+
+    write_report("rust-basics");
+    forgetest()
+        .arg("rescore")
+        .arg("--report")
+        .arg(&report)
+        .arg("--eval-set")
+        .arg("../../eval-sets/rust-basics.toml")
+        .arg("--output")
+        .arg(dir.path().join("out"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "the prompt of 'fibonacci' changed",
+        ))
+        .stderr(predicate::str::contains(
+            "Rescored from report 00000000-0000-0000-0000-000000000001",
+        ));
+
+    write_report("gone");
+    forgetest()
+        .arg("rescore")
+        .arg("--report")
+        .arg(&report)
+        .arg("--eval-set")
+        .arg("../../eval-sets/rust-basics.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("eval set 'gone'"));
+}
+
+#[test]
+fn cache_stats_and_prune() {
+    let dir = TempDir::new().unwrap();
+    let shard = dir.path().join("ab");
+    std::fs::create_dir_all(&shard).unwrap();
+    std::fs::write(
+        shard.join("abcd.json"),
+        r#"{
+            "created_at": "2025-01-01T00:00:00Z",
+            "provider": "openai",
+            "model": "gpt-4.1",
+            "attempt": 1,
+            "response": {
+                "content": "fn f() {}",
+                "extracted_code": "fn f() {}",
+                "model": "gpt-4.1",
+                "token_usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": 5,
+                    "total_tokens": 15,
+                    "estimated_cost_usd": 0.02
+                },
+                "latency_ms": 800
+            }
+        }"#,
+    )
+    .unwrap();
+
+    forgetest()
+        .arg("cache")
+        .arg("stats")
+        .arg("--cache-dir")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 responses"))
+        .stdout(predicate::str::contains("openai/gpt-4.1: 1"));
+
+    // Pruning needs an age or --all
+    forgetest()
+        .arg("cache")
+        .arg("prune")
+        .arg("--cache-dir")
+        .arg(dir.path())
+        .assert()
+        .failure();
+
+    forgetest()
+        .arg("cache")
+        .arg("prune")
+        .arg("--cache-dir")
+        .arg(dir.path())
+        .arg("--older-than-days")
+        .arg("30")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 cached responses"));
+}
+
 #[test]
 fn compare_reports() {
     let dir = TempDir::new().unwrap();
@@ -128,6 +423,77 @@ fn compare_reports() {
         .stdout(predicate::str::contains("regression"));
 }
 
+#[test]
+fn compare_head_to_head() {
+    let dir = TempDir::new().unwrap();
+
+    // One report with both models' results on the same case
+    let mut report: serde_json::Value =
+        serde_json::from_str(&make_test_report("case1", "model1", true, 3, 0)).unwrap();
+    let other: serde_json::Value =
+        serde_json::from_str(&make_test_report("case1", "model2", false, 0, 0)).unwrap();
+    let other_result = other["results"][0].clone();
+    report["results"].as_array_mut().unwrap().push(other_result);
+    let report_path = dir.path().join("report.json");
+    std::fs::write(&report_path, report.to_string()).unwrap();
+
+    forgetest()
+        .arg("compare")
+        .arg("--report")
+        .arg(&report_path)
+        .arg("--models")
+        .arg("model1,model2")
+        .arg("--format")
+        .arg("markdown")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("**model1** wins 1"))
+        .stdout(predicate::str::contains("Only model1 solved: `case1`"));
+
+    forgetest()
+        .arg("compare")
+        .arg("--report")
+        .arg(&report_path)
+        .arg("--models")
+        .arg("model1")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn leaderboard_from_report_directory() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("a.json"),
+        make_test_report("case1", "model1", true, 3, 0),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("b.json"),
+        make_test_report("case1", "model2", false, 0, 0),
+    )
+    .unwrap();
+    let html_path = dir.path().join("leaderboard.html");
+
+    forgetest()
+        .arg("leaderboard")
+        .arg("--reports")
+        .arg(dir.path())
+        .arg("--html")
+        .arg(&html_path)
+        .arg("--resamples")
+        .arg("50")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "2 models over 1 shared cases from 2 reports",
+        ))
+        .stdout(predicate::str::contains("1-0-0"));
+    assert!(std::fs::read_to_string(&html_path)
+        .unwrap()
+        .contains("forgetest leaderboard"));
+}
+
 #[test]
 fn compare_nonexistent_report() {
     forgetest()
@@ -5,7 +5,7 @@
 
 use std::time::Duration;
 
-use forgetest_core::model::{EvalCase, Expectations, Language};
+use forgetest_core::model::{EvalCase, Expectations, Fixture, Language};
 use forgetest_core::results::{Score, TokenUsage};
 use forgetest_runner::{run_eval, LocalRunner};
 use uuid::Uuid;
@@ -33,9 +33,12 @@ fn make_case(id: &str, test_file: &str) -> EvalCase {
             ..Default::default()
         },
         tags: vec![],
+        difficulty: None,
         dependencies: vec![],
         timeout_secs: Some(120),
         max_tokens: None,
+        fixture: None,
+        fim: None,
     }
 }
 
@@ -329,3 +332,69 @@ mod tests {
         score.overall
     );
 }
+
+// --- Tests with fixture crates ---
+
+#[tokio::test]
+async fn e2e_fixture_crate_runs_its_own_tests() {
+    let (_target, runner) = make_runner();
+    let fixture = tempfile::tempdir().unwrap();
+    std::fs::write(
+        fixture.path().join("Cargo.toml"),
+        "[package]\nname = \"word_count\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(fixture.path().join("src")).unwrap();
+    std::fs::create_dir_all(fixture.path().join("tests/data")).unwrap();
+    std::fs::write(
+        fixture.path().join("src/lib.rs"),
+        "//! Word counting.\n\n// forgetest:fill\n",
+    )
+    .unwrap();
+    std::fs::write(fixture.path().join("tests/data/input.txt"), "one two three").unwrap();
+    std::fs::write(
+        fixture.path().join("tests/count.rs"),
+        r#"
+#[test]
+fn counts_words_in_data_file() {
+    let text = std::fs::read_to_string(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/input.txt"),
+    )
+    .unwrap();
+    assert_eq!(word_count::count_words(&text), 3);
+}
+"#,
+    )
+    .unwrap();
+
+    let mut case = make_case("fixture", "");
+    case.expectations.test_file = None;
+    case.fixture = Some(Fixture {
+        path: fixture.path().to_path_buf(),
+        target_file: "src/lib.rs".into(),
+    });
+
+    let code = "pub fn count_words(s: &str) -> usize { s.split_whitespace().count() }";
+
+    let result = run_eval(
+        &runner,
+        &case,
+        code,
+        "mock",
+        "mock",
+        zero_usage(),
+        0,
+        1,
+        Uuid::nil(),
+    )
+    .await
+    .unwrap();
+
+    assert!(result.compilation.success, "fixture should compile");
+    let tests = result
+        .test_execution
+        .as_ref()
+        .expect("fixture tests should run");
+    assert_eq!(tests.passed, 1);
+    assert_eq!(tests.failed, 0);
+}
@@ -48,6 +48,14 @@ fn make_result(
             None
         },
         clippy: None,
+        compile_fail: vec![],
+        hallucinated_apis: vec![],
+        formatting: None,
+        miri: None,
+        performance: None,
+        metrics: None,
+        judge: None,
+        score: None,
         timing: TimingInfo {
             llm_request_ms: 100,
             compilation_ms: 100,
@@ -60,6 +68,7 @@ fn make_result(
             total_tokens: 30,
             estimated_cost_usd: 0.001,
         },
+        cached: false,
         attempt: 1,
         run_id: Uuid::nil(),
     }
@@ -86,7 +95,13 @@ fn make_report(results: Vec<EvalResult>) -> EvalReport {
         aggregate: AggregateStats {
             per_model: HashMap::new(),
             per_case: HashMap::new(),
+            per_tag: HashMap::new(),
+            per_difficulty: HashMap::new(),
         },
+        scoring: None,
+        cases: Default::default(),
+        rescored_from: None,
+        budget: None,
         duration_ms: 1000,
     }
 }
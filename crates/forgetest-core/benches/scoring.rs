@@ -31,7 +31,16 @@ fn make_result(compile_ok: bool, passed: u32, failed: u32, warnings: u32) -> Eva
         clippy: Some(ClippyResult {
             warnings: vec![],
             warning_count: warnings,
+            by_category: Default::default(),
         }),
+        compile_fail: vec![],
+        hallucinated_apis: vec![],
+        formatting: None,
+        miri: None,
+        performance: None,
+        metrics: None,
+        judge: None,
+        score: None,
         timing: TimingInfo {
             llm_request_ms: 0,
             compilation_ms: 0,
@@ -44,6 +53,7 @@ fn make_result(compile_ok: bool, passed: u32, failed: u32, warnings: u32) -> Eva
             total_tokens: 300,
             estimated_cost_usd: 0.01,
         },
+        cached: false,
         attempt: 1,
         run_id: Uuid::nil(),
     }
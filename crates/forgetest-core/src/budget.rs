@@ -0,0 +1,363 @@
+//! Run-level spending limits.
+//!
+//! A [`Budget`] caps the USD cost, total tokens and provider requests of a
+//! run, both across all models and for each model. The engine asks it before
+//! every provider call; once a limit is reached the remaining generations it
+//! covers are skipped and the report is marked partial. Judge requests count
+//! against the run-wide limits only, and are tracked separately.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::results::TokenUsage;
+
+/// Limits on what a run, or one model in it, may spend. `None` is unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetLimits {
+    /// Maximum estimated cost in USD.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    /// Maximum total (prompt + completion) tokens.
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    /// Maximum provider requests, retries included.
+    #[serde(default)]
+    pub max_requests: Option<u64>,
+}
+
+impl BudgetLimits {
+    /// Whether no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_cost_usd.is_none() && self.max_tokens.is_none() && self.max_requests.is_none()
+    }
+
+    /// These limits, with any unset one taken from `base`.
+    pub fn or(self, base: BudgetLimits) -> BudgetLimits {
+        BudgetLimits {
+            max_cost_usd: self.max_cost_usd.or(base.max_cost_usd),
+            max_tokens: self.max_tokens.or(base.max_tokens),
+            max_requests: self.max_requests.or(base.max_requests),
+        }
+    }
+
+    /// The first limit `spent` has reached, described for humans.
+    fn reached(&self, spent: &Spend) -> Option<String> {
+        if let Some(max) = self.max_cost_usd {
+            if spent.cost_usd >= max {
+                return Some(format!(
+                    "cost limit ${max:.2} reached (${:.4} spent)",
+                    spent.cost_usd
+                ));
+            }
+        }
+        if let Some(max) = self.max_tokens {
+            if spent.tokens >= max {
+                return Some(format!("token limit {max} reached ({} used)", spent.tokens));
+            }
+        }
+        if let Some(max) = self.max_requests {
+            if spent.requests >= max {
+                return Some(format!("request limit {max} reached"));
+            }
+        }
+        None
+    }
+}
+
+/// Budget configuration (`[budget]` in `forgetest.toml`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// Limits on the whole run (`[budget.global]`).
+    #[serde(default)]
+    pub global: BudgetLimits,
+    /// Limits applied to each model separately (`[budget.per_model]`).
+    #[serde(default)]
+    pub per_model: BudgetLimits,
+}
+
+impl BudgetConfig {
+    /// Whether no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.global.is_unlimited() && self.per_model.is_unlimited()
+    }
+}
+
+/// What has been spent so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Spend {
+    /// Estimated cost in USD.
+    pub cost_usd: f64,
+    /// Total tokens.
+    pub tokens: u64,
+    /// Provider requests.
+    pub requests: u64,
+}
+
+impl Spend {
+    fn add(&mut self, usage: &TokenUsage) {
+        self.cost_usd += usage.estimated_cost_usd;
+        self.tokens += u64::from(usage.total_tokens);
+    }
+}
+
+/// A generation was not dispatched because a budget limit was reached.
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
+#[error("{} budget exhausted: {reason}", model.as_deref().unwrap_or("run"))]
+pub struct BudgetExceeded {
+    /// Model whose own budget ran out (`None` for the run-wide budget).
+    pub model: Option<String>,
+    /// Which limit was reached.
+    pub reason: String,
+}
+
+/// How a budgeted run went, recorded in its report.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetSummary {
+    /// The limits the run had.
+    pub limits: BudgetConfig,
+    /// What the run spent, judge requests included.
+    pub spent: Spend,
+    /// The part of `spent` that went on judge requests.
+    #[serde(default)]
+    pub judge: Spend,
+    /// Limits that stopped generations, one per run or model.
+    #[serde(default)]
+    pub exhausted: Vec<BudgetExceeded>,
+    /// Generations skipped because a limit was reached. Any at all make the
+    /// report partial.
+    #[serde(default)]
+    pub skipped: usize,
+}
+
+impl BudgetSummary {
+    /// Whether a limit stopped part of the run.
+    pub fn is_partial(&self) -> bool {
+        self.skipped > 0
+    }
+}
+
+/// Tracks spending against a [`BudgetConfig`].
+///
+/// Requests are counted when dispatched and tokens and cost when the
+/// response arrives, so with several requests in flight a run can overshoot
+/// its cost or token limit by up to the parallelism's worth of responses.
+#[derive(Debug, Default)]
+pub struct Budget {
+    config: BudgetConfig,
+    state: Mutex<BudgetState>,
+}
+
+#[derive(Debug, Default)]
+struct BudgetState {
+    total: Spend,
+    per_model: HashMap<String, Spend>,
+    judge: Spend,
+}
+
+impl Budget {
+    /// Track spending against `config`.
+    pub fn new(config: BudgetConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::default(),
+        }
+    }
+
+    /// The configured limits.
+    pub fn config(&self) -> &BudgetConfig {
+        &self.config
+    }
+
+    /// Reserve a request to `model`, unless the run's or the model's budget
+    /// is exhausted.
+    pub fn try_dispatch(&self, model: &str) -> Result<(), BudgetExceeded> {
+        let mut state = self.lock();
+        if let Some(reason) = self.config.global.reached(&state.total) {
+            return Err(BudgetExceeded {
+                model: None,
+                reason,
+            });
+        }
+        let spent = state.per_model.entry(model.to_string()).or_default();
+        if let Some(reason) = self.config.per_model.reached(spent) {
+            return Err(BudgetExceeded {
+                model: Some(model.to_string()),
+                reason,
+            });
+        }
+        spent.requests += 1;
+        state.total.requests += 1;
+        Ok(())
+    }
+
+    /// Charge the tokens and cost of a response to `model`.
+    pub fn record(&self, model: &str, usage: &TokenUsage) {
+        let mut state = self.lock();
+        state.total.add(usage);
+        state
+            .per_model
+            .entry(model.to_string())
+            .or_default()
+            .add(usage);
+    }
+
+    /// Charge a generation made before this budget existed, such as one a
+    /// resumed run already has, as one request plus its usage.
+    pub fn record_completed(&self, model: &str, usage: &TokenUsage) {
+        self.record(model, usage);
+        let mut state = self.lock();
+        state.total.requests += 1;
+        state
+            .per_model
+            .entry(model.to_string())
+            .or_default()
+            .requests += 1;
+    }
+
+    /// Reserve a judge request, unless the run's budget is exhausted. Judge
+    /// requests don't count against any model's own limits.
+    pub fn try_dispatch_judge(&self) -> Result<(), BudgetExceeded> {
+        let mut state = self.lock();
+        if let Some(reason) = self.config.global.reached(&state.total) {
+            return Err(BudgetExceeded {
+                model: None,
+                reason,
+            });
+        }
+        state.total.requests += 1;
+        state.judge.requests += 1;
+        Ok(())
+    }
+
+    /// Charge the tokens and cost of a judge response.
+    pub fn record_judge(&self, usage: &TokenUsage) {
+        let mut state = self.lock();
+        state.total.add(usage);
+        state.judge.add(usage);
+    }
+
+    /// Charge a judge verdict made before this budget existed as one request
+    /// plus its usage.
+    pub fn record_judge_completed(&self, usage: &TokenUsage) {
+        self.record_judge(usage);
+        let mut state = self.lock();
+        state.total.requests += 1;
+        state.judge.requests += 1;
+    }
+
+    /// Spending across all models and the judge so far.
+    pub fn spent(&self) -> Spend {
+        self.lock().total
+    }
+
+    /// Spending on judge requests so far.
+    pub fn judge_spent(&self) -> Spend {
+        self.lock().judge
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BudgetState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(tokens: u32, cost: f64) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: tokens / 2,
+            completion_tokens: tokens - tokens / 2,
+            total_tokens: tokens,
+            estimated_cost_usd: cost,
+        }
+    }
+
+    #[test]
+    fn global_limits_stop_every_model() {
+        let budget = Budget::new(BudgetConfig {
+            global: BudgetLimits {
+                max_cost_usd: Some(1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        budget.try_dispatch("a").unwrap();
+        budget.record("a", &usage(100, 0.6));
+        budget.try_dispatch("b").unwrap();
+        budget.record("b", &usage(100, 0.6));
+
+        let err = budget.try_dispatch("c").unwrap_err();
+        assert_eq!(err.model, None);
+        assert!(err
+            .to_string()
+            .starts_with("run budget exhausted: cost limit $1.00"));
+        assert_eq!(budget.spent().requests, 2);
+        assert_eq!(budget.spent().tokens, 200);
+    }
+
+    #[test]
+    fn per_model_limits_stop_only_that_model() {
+        let budget = Budget::new(BudgetConfig {
+            per_model: BudgetLimits {
+                max_requests: Some(2),
+                max_tokens: Some(1_000),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        budget.try_dispatch("a").unwrap();
+        budget.try_dispatch("a").unwrap();
+        let err = budget.try_dispatch("a").unwrap_err();
+        assert_eq!(err.model.as_deref(), Some("a"));
+        assert_eq!(err.reason, "request limit 2 reached");
+
+        budget.try_dispatch("b").unwrap();
+        budget.record("b", &usage(1_000, 0.0));
+        assert!(budget
+            .try_dispatch("b")
+            .unwrap_err()
+            .reason
+            .contains("token limit"));
+    }
+
+    #[test]
+    fn judge_requests_count_against_the_run_only() {
+        let budget = Budget::new(BudgetConfig {
+            global: BudgetLimits {
+                max_requests: Some(3),
+                ..Default::default()
+            },
+            per_model: BudgetLimits {
+                max_tokens: Some(100),
+                ..Default::default()
+            },
+        });
+        budget.try_dispatch("a").unwrap();
+        budget.record("a", &usage(50, 0.0));
+        budget.try_dispatch_judge().unwrap();
+        budget.record_judge(&usage(500, 0.2));
+
+        // The judge's tokens don't use up the model's own budget
+        budget.try_dispatch("a").unwrap();
+        let err = budget.try_dispatch_judge().unwrap_err();
+        assert_eq!(err.model, None);
+        assert_eq!(budget.spent().requests, 3);
+        assert_eq!(budget.judge_spent().requests, 1);
+        assert_eq!(budget.judge_spent().tokens, 500);
+    }
+
+    #[test]
+    fn parses_from_toml() {
+        let config: BudgetConfig =
+            toml::from_str("[global]\nmax_cost_usd = 25.0\n[per_model]\nmax_requests = 200\n")
+                .unwrap();
+        assert_eq!(config.global.max_cost_usd, Some(25.0));
+        assert_eq!(config.per_model.max_requests, Some(200));
+        assert!(!config.is_unlimited());
+        assert!(BudgetConfig::default().is_unlimited());
+    }
+}
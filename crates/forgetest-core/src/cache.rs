@@ -0,0 +1,377 @@
+//! Content-addressed cache of LLM responses.
+//!
+//! Responses are stored on disk under a SHA-256 of everything that shapes a
+//! generation: provider, model, prompt and context files (or FIM prefix and
+//! suffix), system prompt, sampling settings and the attempt index. Re-running
+//! an eval after fixing a test file then reuses the generations instead of
+//! paying for them again, while each Pass@k attempt keeps its own sample.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::traits::{CompletionRequest, GenerateRequest, GenerateResponse};
+
+/// How the engine uses the response cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheMode {
+    /// Reuse cached responses, and cache new ones.
+    Read,
+    /// Always call the provider, and cache (overwrite) its responses.
+    Write,
+    /// Don't touch the cache.
+    #[default]
+    Off,
+}
+
+impl CacheMode {
+    /// Whether cached responses are reused.
+    pub fn reads(self) -> bool {
+        self == CacheMode::Read
+    }
+
+    /// Whether new responses are stored.
+    pub fn writes(self) -> bool {
+        self != CacheMode::Off
+    }
+}
+
+impl std::fmt::Display for CacheMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheMode::Read => write!(f, "read"),
+            CacheMode::Write => write!(f, "write"),
+            CacheMode::Off => write!(f, "off"),
+        }
+    }
+}
+
+impl std::str::FromStr for CacheMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "read" => Ok(CacheMode::Read),
+            "write" => Ok(CacheMode::Write),
+            "off" => Ok(CacheMode::Off),
+            other => Err(format!("unknown cache mode: {other}")),
+        }
+    }
+}
+
+/// A cached response with what produced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// When the response was cached.
+    pub created_at: DateTime<Utc>,
+    /// Provider name.
+    pub provider: String,
+    /// Model identifier.
+    pub model: String,
+    /// Attempt index the response was generated for.
+    pub attempt: u32,
+    /// The provider's response.
+    pub response: GenerateResponse,
+}
+
+/// Summary of the cache's contents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Number of cached responses.
+    pub entries: usize,
+    /// Total size on disk in bytes.
+    pub bytes: u64,
+    /// Estimated cost of generating the cached responses, in USD.
+    pub cost_usd: f64,
+    /// Responses per `provider/model`.
+    pub per_model: BTreeMap<String, usize>,
+    /// When the oldest response was cached.
+    pub oldest: Option<DateTime<Utc>>,
+    /// When the newest response was cached.
+    pub newest: Option<DateTime<Utc>>,
+}
+
+/// An on-disk response cache.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    mode: CacheMode,
+}
+
+impl ResponseCache {
+    /// A cache stored in `dir`, used according to `mode`.
+    pub fn new(dir: impl Into<PathBuf>, mode: CacheMode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    /// The cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// How the cache is used.
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Key of a generation: a hex SHA-256 over every input that shapes it.
+    /// FIM cases pass their completion request, which replaces the prompt.
+    pub fn key(
+        provider: &str,
+        request: &GenerateRequest,
+        completion: Option<&CompletionRequest>,
+        attempt: u32,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        let mut part = |s: &str| {
+            hasher.update(s.as_bytes());
+            hasher.update([0]);
+        };
+        part(provider);
+        part(&request.model);
+        match completion {
+            Some(fim) => {
+                part("fim");
+                part(&fim.prefix);
+                part(&fim.suffix);
+                part(&fim.stop_sequences.join("\u{1}"));
+            }
+            None => {
+                part("chat");
+                part(&request.prompt);
+                part(&serde_json::to_string(&request.context_files).unwrap_or_default());
+                part(&request.stop_sequences.join("\u{1}"));
+            }
+        }
+        part(request.system_prompt.as_deref().unwrap_or(""));
+        part(&request.temperature.to_string());
+        part(&request.max_tokens.to_string());
+        part(&attempt.to_string());
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{key}.json"))
+    }
+
+    /// The cached response for `key`, if the mode reads and there is one.
+    /// Unreadable entries count as misses.
+    pub fn get(&self, key: &str) -> Option<GenerateResponse> {
+        if !self.mode.reads() {
+            return None;
+        }
+        let contents = std::fs::read_to_string(self.path(key)).ok()?;
+        match serde_json::from_str::<CacheEntry>(&contents) {
+            Ok(entry) => Some(entry.response),
+            Err(e) => {
+                tracing::warn!("ignoring corrupt cache entry {key}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Store a response under `key`, if the mode writes.
+    pub fn put(
+        &self,
+        key: &str,
+        provider: &str,
+        attempt: u32,
+        response: &GenerateResponse,
+    ) -> Result<()> {
+        if !self.mode.writes() {
+            return Ok(());
+        }
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let entry = CacheEntry {
+            created_at: Utc::now(),
+            provider: provider.to_string(),
+            model: response.model.clone(),
+            attempt,
+            response: response.clone(),
+        };
+        // Write then rename, so concurrent readers never see half an entry
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(&entry)?)
+            .with_context(|| format!("failed to write cache entry {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("failed to write cache entry {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Summarize the cache's contents.
+    pub fn stats(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        for (path, bytes) in self.entry_files()? {
+            stats.entries += 1;
+            stats.bytes += bytes;
+            let Some(entry) = read_entry(&path) else {
+                continue;
+            };
+            stats.cost_usd += entry.response.token_usage.estimated_cost_usd;
+            *stats
+                .per_model
+                .entry(format!("{}/{}", entry.provider, entry.model))
+                .or_default() += 1;
+            stats.oldest = Some(
+                stats
+                    .oldest
+                    .map_or(entry.created_at, |t| t.min(entry.created_at)),
+            );
+            stats.newest = Some(
+                stats
+                    .newest
+                    .map_or(entry.created_at, |t| t.max(entry.created_at)),
+            );
+        }
+        Ok(stats)
+    }
+
+    /// Remove entries cached more than `older_than` ago (all entries if
+    /// `None`), and any that can't be read. Returns the number removed.
+    pub fn prune(&self, older_than: Option<Duration>) -> Result<usize> {
+        let cutoff = older_than.map(|age| SystemTime::now() - age);
+        let mut removed = 0;
+        for (path, _) in self.entry_files()? {
+            let expired = match (cutoff, read_entry(&path)) {
+                (_, None) | (None, _) => true,
+                (Some(cutoff), Some(entry)) => SystemTime::from(entry.created_at) < cutoff,
+            };
+            if expired {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("failed to remove {}", path.display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Entry files and their sizes.
+    fn entry_files(&self) -> Result<Vec<(PathBuf, u64)>> {
+        let mut files = Vec::new();
+        if !self.dir.is_dir() {
+            return Ok(files);
+        }
+        for shard in std::fs::read_dir(&self.dir)
+            .with_context(|| format!("failed to read cache {}", self.dir.display()))?
+        {
+            let shard = shard?.path();
+            if !shard.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(&shard)? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.extension().is_some_and(|e| e == "json") {
+                    files.push((path, entry.metadata()?.len()));
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+fn read_entry(path: &Path) -> Option<CacheEntry> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::TokenUsage;
+
+    fn request(prompt: &str) -> GenerateRequest {
+        GenerateRequest {
+            model: "m".into(),
+            prompt: prompt.into(),
+            system_prompt: None,
+            context_files: vec![],
+            max_tokens: 100,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        }
+    }
+
+    fn response(code: &str) -> GenerateResponse {
+        GenerateResponse {
+            content: code.into(),
+            extracted_code: code.into(),
+            model: "m".into(),
+            token_usage: TokenUsage {
+                prompt_tokens: 10,
+                completion_tokens: 5,
+                total_tokens: 15,
+                estimated_cost_usd: 0.25,
+            },
+            latency_ms: 900,
+        }
+    }
+
+    #[test]
+    fn keys_cover_every_input() {
+        let base = ResponseCache::key("p", &request("a"), None, 1);
+        assert_eq!(base, ResponseCache::key("p", &request("a"), None, 1));
+        assert_ne!(base, ResponseCache::key("q", &request("a"), None, 1));
+        assert_ne!(base, ResponseCache::key("p", &request("b"), None, 1));
+        assert_ne!(base, ResponseCache::key("p", &request("a"), None, 2));
+
+        let mut hot = request("a");
+        hot.temperature = 0.8;
+        assert_ne!(base, ResponseCache::key("p", &hot, None, 1));
+        let mut system = request("a");
+        system.system_prompt = Some("be terse".into());
+        assert_ne!(base, ResponseCache::key("p", &system, None, 1));
+    }
+
+    #[test]
+    fn modes_control_reads_and_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = ResponseCache::key("p", &request("a"), None, 1);
+
+        ResponseCache::new(dir.path(), CacheMode::Off)
+            .put(&key, "p", 1, &response("off"))
+            .unwrap();
+        assert!(ResponseCache::new(dir.path(), CacheMode::Read)
+            .get(&key)
+            .is_none());
+
+        // Write mode stores but never reads
+        let write = ResponseCache::new(dir.path(), CacheMode::Write);
+        write.put(&key, "p", 1, &response("fresh")).unwrap();
+        assert!(write.get(&key).is_none());
+
+        let read = ResponseCache::new(dir.path(), CacheMode::Read);
+        assert_eq!(read.get(&key).unwrap().extracted_code, "fresh");
+    }
+
+    #[test]
+    fn stats_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), CacheMode::Read);
+        for (i, prompt) in ["a", "b"].into_iter().enumerate() {
+            let key = ResponseCache::key("p", &request(prompt), None, 1);
+            cache.put(&key, "p", 1, &response(&i.to_string())).unwrap();
+        }
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.per_model["p/m"], 2);
+        assert!((stats.cost_usd - 0.5).abs() < 1e-9);
+
+        // Nothing is a day old yet
+        assert_eq!(cache.prune(Some(Duration::from_secs(86_400))).unwrap(), 0);
+        assert_eq!(cache.prune(None).unwrap(), 2);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+}
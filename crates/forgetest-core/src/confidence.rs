@@ -0,0 +1,293 @@
+//! Confidence intervals for aggregate metrics.
+//!
+//! Eval sets are small, so point estimates alone overstate differences between
+//! models. Intervals are computed over per-case values (each case is one
+//! observation), either with a percentile bootstrap or with the Wilson score
+//! interval. Bootstrap resampling uses a seeded generator so reports are
+//! reproducible.
+
+use serde::{Deserialize, Serialize};
+
+/// How intervals are computed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntervalMethod {
+    /// Percentile bootstrap of the mean over cases.
+    #[default]
+    Bootstrap,
+    /// Wilson score interval, treating the mean over cases as a proportion.
+    Wilson,
+}
+
+impl std::fmt::Display for IntervalMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalMethod::Bootstrap => write!(f, "bootstrap"),
+            IntervalMethod::Wilson => write!(f, "Wilson"),
+        }
+    }
+}
+
+impl std::str::FromStr for IntervalMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bootstrap" => Ok(IntervalMethod::Bootstrap),
+            "wilson" => Ok(IntervalMethod::Wilson),
+            other => Err(format!("unknown interval method: {other}")),
+        }
+    }
+}
+
+/// Settings for confidence intervals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntervalConfig {
+    pub method: IntervalMethod,
+    /// Confidence level, e.g. 0.95.
+    pub level: f64,
+    /// Number of bootstrap resamples.
+    pub resamples: u32,
+    /// Seed for bootstrap resampling.
+    pub seed: u64,
+}
+
+impl Default for IntervalConfig {
+    fn default() -> Self {
+        Self {
+            method: IntervalMethod::Bootstrap,
+            level: 0.95,
+            resamples: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// A two-sided confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    /// Format a 0.0-1.0 interval as percentages, e.g. `62.1-91.3%`.
+    pub fn percent(&self) -> String {
+        format!("{:.1}-{:.1}%", self.lower * 100.0, self.upper * 100.0)
+    }
+
+    /// Whether `value` lies within the interval.
+    pub fn contains(&self, value: f64) -> bool {
+        (self.lower..=self.upper).contains(&value)
+    }
+}
+
+/// Format a 0.0-1.0 value as a percentage, followed by its interval if any,
+/// e.g. `80.0% [62.1-91.3%]`.
+pub fn percent_with_interval(value: f64, interval: Option<&ConfidenceInterval>) -> String {
+    match interval {
+        Some(ci) => format!("{:.1}% [{}]", value * 100.0, ci.percent()),
+        None => format!("{:.1}%", value * 100.0),
+    }
+}
+
+/// Interval for the mean of `values` (one per case) using `config`.
+///
+/// Returns `None` for no values or a confidence level outside (0, 1).
+pub fn interval(values: &[f64], config: &IntervalConfig) -> Option<ConfidenceInterval> {
+    match config.method {
+        IntervalMethod::Bootstrap => {
+            bootstrap_interval(values, config.level, config.resamples, config.seed)
+        }
+        IntervalMethod::Wilson => {
+            let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+            wilson_interval(mean, values.len(), config.level)
+        }
+    }
+}
+
+/// Percentile bootstrap interval for the mean of `values`.
+pub fn bootstrap_interval(
+    values: &[f64],
+    level: f64,
+    resamples: u32,
+    seed: u64,
+) -> Option<ConfidenceInterval> {
+    if values.is_empty() || !(0.0..1.0).contains(&level) || level == 0.0 {
+        return None;
+    }
+    let n = values.len();
+    let mut rng = SplitMix64::new(seed);
+    let mut means: Vec<f64> = (0..resamples.max(1))
+        .map(|_| (0..n).map(|_| values[rng.below(n)]).sum::<f64>() / n as f64)
+        .collect();
+    means.sort_by(f64::total_cmp);
+
+    let alpha = (1.0 - level) / 2.0;
+    let at = |q: f64| means[((q * (means.len() - 1) as f64).round() as usize).min(means.len() - 1)];
+    Some(ConfidenceInterval {
+        lower: at(alpha),
+        upper: at(1.0 - alpha),
+    })
+}
+
+/// Wilson score interval for a proportion `p` observed over `n` trials.
+pub fn wilson_interval(p: f64, n: usize, level: f64) -> Option<ConfidenceInterval> {
+    if n == 0 || !(0.0..1.0).contains(&level) || level == 0.0 {
+        return None;
+    }
+    let p = p.clamp(0.0, 1.0);
+    let n = n as f64;
+    let z = normal_quantile(1.0 - (1.0 - level) / 2.0);
+    let z2 = z * z;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let margin = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    Some(ConfidenceInterval {
+        lower: (center - margin).max(0.0),
+        upper: (center + margin).min(1.0),
+    })
+}
+
+/// Inverse of the standard normal CDF (Acklam's rational approximation,
+/// relative error below 1.2e-9).
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    if p < LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -normal_quantile(1.0 - p)
+    }
+}
+
+/// Small deterministic generator for resampling.
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in `0..n` (Lemire's multiply-shift reduction).
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        ((self.next() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_quantile_known_values() {
+        assert!((normal_quantile(0.975) - 1.959_964).abs() < 1e-5);
+        assert!((normal_quantile(0.5)).abs() < 1e-9);
+        assert!((normal_quantile(0.005) + 2.575_829).abs() < 1e-5);
+    }
+
+    #[test]
+    fn wilson_matches_reference() {
+        // 8 of 10 at 95%: [0.4902, 0.9433]
+        let ci = wilson_interval(0.8, 10, 0.95).unwrap();
+        assert!((ci.lower - 0.4902).abs() < 1e-4);
+        assert!((ci.upper - 0.9433).abs() < 1e-4);
+
+        // Stays within [0, 1] at the extremes
+        let ci = wilson_interval(1.0, 5, 0.95).unwrap();
+        assert!(ci.upper <= 1.0 && ci.lower > 0.5);
+        assert!(wilson_interval(0.5, 0, 0.95).is_none());
+    }
+
+    #[test]
+    fn bootstrap_is_deterministic_and_brackets_the_mean() {
+        let values = [1.0, 0.0, 1.0, 1.0, 0.5, 1.0, 0.0, 1.0, 1.0, 0.75];
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let a = bootstrap_interval(&values, 0.95, 2000, 7).unwrap();
+        let b = bootstrap_interval(&values, 0.95, 2000, 7).unwrap();
+        assert_eq!(a, b);
+        assert!(a.contains(mean));
+        assert!(a.lower > 0.3 && a.upper <= 1.0);
+
+        // A narrower level gives a narrower interval
+        let narrow = bootstrap_interval(&values, 0.5, 2000, 7).unwrap();
+        assert!(narrow.upper - narrow.lower < a.upper - a.lower);
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        let single = bootstrap_interval(&[0.4], 0.95, 100, 0).unwrap();
+        assert_eq!((single.lower, single.upper), (0.4, 0.4));
+        assert!(bootstrap_interval(&[], 0.95, 100, 0).is_none());
+        assert!(bootstrap_interval(&[0.4], 1.0, 100, 0).is_none());
+        assert!(interval(&[], &IntervalConfig::default()).is_none());
+    }
+
+    #[test]
+    fn format_with_interval() {
+        let ci = ConfidenceInterval {
+            lower: 0.621,
+            upper: 0.9133,
+        };
+        assert_eq!(percent_with_interval(0.8, Some(&ci)), "80.0% [62.1-91.3%]");
+        assert_eq!(percent_with_interval(0.8, None), "80.0%");
+    }
+
+    #[test]
+    fn parse_method() {
+        assert_eq!(
+            "Wilson".parse::<IntervalMethod>().unwrap(),
+            IntervalMethod::Wilson
+        );
+        assert!("bayes".parse::<IntervalMethod>().is_err());
+    }
+}
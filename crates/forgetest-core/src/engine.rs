@@ -3,7 +3,7 @@
 //! Coordinates multiple eval cases across multiple models with parallelism,
 //! retries, and Pass@k support.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -12,13 +12,25 @@ use futures::stream::{FuturesUnordered, StreamExt};
 use tokio::sync::Semaphore;
 use uuid::Uuid;
 
+use crate::budget::{Budget, BudgetExceeded, BudgetSummary};
+use crate::cache::ResponseCache;
+use crate::confidence::IntervalConfig;
 use crate::error::ProviderError;
-use crate::model::{EvalSet, Language};
-use crate::report::{EvalReport, EvalSetSummary};
-use crate::results::{EvalResult, TimingInfo};
-use crate::statistics::compute_aggregate_stats;
+use crate::hallucination::HallucinatedApi;
+use crate::journal::Journal;
+use crate::judge::Judge;
+use crate::metrics::CodeMetrics;
+use crate::model::{EvalCase, EvalSet, Language};
+use crate::report::{CaseSnapshot, EvalReport, EvalSetSummary};
+use crate::results::{
+    ClippyResult, CompilationResult, CompileFailResult, EvalResult, FormattingResult, JudgeResult,
+    MiriResult, PerformanceResult, TestResult, TimingInfo,
+};
+use crate::scoring::{DefaultScorer, Scorer};
+use crate::statistics::compute_aggregate_stats_with;
 use crate::traits::{
-    ClippyRequest, CodeRunner, CompileRequest, GenerateRequest, LlmProvider, TestRequest,
+    ApiCheckRequest, BenchmarkRequest, ClippyRequest, CodeRunner, CompileFailRequest,
+    CompileRequest, CompletionRequest, FormatRequest, GenerateRequest, LlmProvider, TestRequest,
 };
 
 /// Configuration for the eval engine.
@@ -38,6 +50,8 @@ pub struct EvalEngineConfig {
     pub retry_delay: Duration,
     /// Optional system prompt override.
     pub system_prompt_override: Option<String>,
+    /// Confidence interval settings for the aggregate statistics.
+    pub intervals: IntervalConfig,
 }
 
 impl Default for EvalEngineConfig {
@@ -50,6 +64,7 @@ impl Default for EvalEngineConfig {
             max_retries_per_case: 3,
             retry_delay: Duration::from_secs(1),
             system_prompt_override: None,
+            intervals: IntervalConfig::default(),
         }
     }
 }
@@ -86,6 +101,11 @@ pub struct EvalEngine {
     providers: HashMap<String, Arc<dyn LlmProvider>>,
     runner: Arc<dyn CodeRunner>,
     config: EvalEngineConfig,
+    scorer: Arc<dyn Scorer>,
+    judge: Option<Arc<Judge>>,
+    journal: Option<Arc<Journal>>,
+    cache: Option<Arc<ResponseCache>>,
+    budget: Option<Arc<Budget>>,
 }
 
 impl EvalEngine {
@@ -98,21 +118,102 @@ impl EvalEngine {
             providers,
             runner,
             config,
+            scorer: Arc::new(DefaultScorer::default()),
+            judge: None,
+            journal: None,
+            cache: None,
+            budget: None,
         }
     }
 
+    /// Use a custom scorer for result scores and aggregate statistics.
+    pub fn with_scorer(mut self, scorer: Arc<dyn Scorer>) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// Grade cases that have a rubric with an LLM judge.
+    pub fn with_judge(mut self, judge: Arc<Judge>) -> Self {
+        self.judge = Some(judge);
+        self
+    }
+
+    /// Append each result to `journal` as soon as it is scored.
+    pub fn with_journal(mut self, journal: Arc<Journal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Look generations up in, and store them to, a response cache.
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Stop dispatching generations once `budget` is exhausted. Share one
+    /// budget between engines to limit a run over several eval sets.
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// The scorer used for this engine's results.
+    pub fn scorer(&self) -> &Arc<dyn Scorer> {
+        &self.scorer
+    }
+
     /// Run evaluations for an eval set against specified models.
     pub async fn run(
         &self,
         eval_set: &EvalSet,
         models: &[ModelSpec],
         progress: &dyn ProgressReporter,
+    ) -> Result<EvalReport> {
+        self.resume(eval_set, models, progress, Vec::new()).await
+    }
+
+    /// Continue a run from the results it already has (usually loaded from
+    /// its journal). Only the (case, model, attempt) triples missing from
+    /// `completed` are evaluated; the report includes both and keeps the
+    /// original run ID.
+    pub async fn resume(
+        &self,
+        eval_set: &EvalSet,
+        models: &[ModelSpec],
+        progress: &dyn ProgressReporter,
+        completed: Vec<EvalResult>,
     ) -> Result<EvalReport> {
         let start = Instant::now();
-        let run_id = Uuid::new_v4();
+        let run_id = completed.first().map_or_else(Uuid::new_v4, |r| r.run_id);
         let semaphore = Arc::new(Semaphore::new(self.config.parallelism));
         let max_k = self.config.pass_k.iter().copied().max().unwrap_or(1);
 
+        // Keep earlier results that are still part of the plan
+        let planned_models: HashSet<&str> = models.iter().map(|m| m.model.as_str()).collect();
+        let mut results: Vec<EvalResult> = completed
+            .into_iter()
+            .filter(|r| {
+                planned_models.contains(r.model.as_str())
+                    && r.attempt <= max_k
+                    && eval_set.cases.iter().any(|c| c.id == r.case_id)
+            })
+            .collect();
+        let done: HashSet<(String, String, u32)> = results
+            .iter()
+            .map(|r| (r.case_id.clone(), r.model.clone(), r.attempt))
+            .collect();
+        // What the earlier results cost counts against the budget
+        if let Some(budget) = &self.budget {
+            for result in results.iter().filter(|r| !r.cached) {
+                budget.record_completed(&result.model, &result.token_usage);
+            }
+            for verdict in results.iter().filter_map(|r| r.judge.as_ref()) {
+                if !verdict.cached {
+                    budget.record_judge_completed(&verdict.token_usage);
+                }
+            }
+        }
+
         let mut futures = FuturesUnordered::new();
 
         for model_spec in models {
@@ -123,6 +224,9 @@ impl EvalEngine {
 
             for case in &eval_set.cases {
                 for attempt in 1..=max_k {
+                    if done.contains(&(case.id.clone(), model_spec.model.clone(), attempt)) {
+                        continue;
+                    }
                     progress.on_eval_start(&case.id, &model_spec.model, attempt);
                     let provider = Arc::clone(provider);
                     let runner = Arc::clone(&self.runner);
@@ -131,6 +235,9 @@ impl EvalEngine {
                     let model = model_spec.model.clone();
                     let provider_name = model_spec.provider.clone();
                     let config = self.config.clone();
+                    let judge = self.judge.clone();
+                    let cache = self.cache.clone();
+                    let budget = self.budget.clone();
 
                     futures.push(async move {
                         let ctx_case_id = case.id.clone();
@@ -150,8 +257,36 @@ impl EvalEngine {
                                 max_tokens: case.max_tokens.unwrap_or(config.max_tokens),
                                 temperature: config.temperature,
                                 stop_sequences: vec![],
+                                attempt,
                             };
 
+                            // FIM cases use the completion endpoint instead of chat
+                            let completion_request =
+                                case.fim.as_ref().map(|fim| CompletionRequest {
+                                    model: model.clone(),
+                                    prefix: fim.prefix.clone(),
+                                    suffix: fim.suffix.clone(),
+                                    max_tokens: request.max_tokens,
+                                    temperature: config.temperature,
+                                    stop_sequences: vec![],
+                                    attempt,
+                                });
+
+                            // Reuse the generation from the cache if there is one
+                            let cache_key = cache.as_ref().map(|_| {
+                                ResponseCache::key(
+                                    &provider_name,
+                                    &request,
+                                    completion_request.as_ref(),
+                                    attempt,
+                                )
+                            });
+                            let mut cached_response = cache
+                                .as_ref()
+                                .zip(cache_key.as_deref())
+                                .and_then(|(cache, key)| cache.get(key));
+                            let cached = cached_response.is_some();
+
                             let gen_start = Instant::now();
 
                             // Retry on transient provider errors with exponential backoff
@@ -162,69 +297,55 @@ impl EvalEngine {
                                     tokio::time::sleep(retry_delay).await;
                                     retry_delay = (retry_delay * 2).min(Duration::from_secs(60));
                                 }
-                                match provider.generate(&request).await {
+                                let outcome = match cached_response.take() {
+                                    Some(response) => Ok(response),
+                                    None => {
+                                        if let Some(budget) = &budget {
+                                            budget.try_dispatch(&model)?;
+                                        }
+                                        match &completion_request {
+                                            Some(completion) => provider.complete(completion).await,
+                                            None => provider.generate(&request).await,
+                                        }
+                                    }
+                                };
+                                match outcome {
                                     Ok(response) => {
-                                        let llm_ms = gen_start.elapsed().as_millis() as u64;
-                                        let generated_code = response.extracted_code.clone();
-                                        let language = case.language.unwrap_or(Language::Rust);
-                                        let timeout_secs = case.timeout_secs.unwrap_or(60);
-
-                                        let deps = case.dependencies.clone();
-
-                                        // Compile the generated code
-                                        let compile_result = runner
-                                            .compile(&CompileRequest {
-                                                code: generated_code.clone(),
-                                                language,
-                                                dependencies: deps.clone(),
-                                                timeout_secs,
-                                            })
-                                            .await?;
-                                        let compilation_ms = compile_result.duration_ms;
-
-                                        // Run tests if compilation succeeded and test_file is provided
-                                        let test_execution = if compile_result.success
-                                            && case.expectations.should_pass_tests
+                                        if let (Some(budget), false) = (&budget, cached) {
+                                            budget.record(&model, &response.token_usage);
+                                        }
+                                        if let (Some(cache), Some(key), false) =
+                                            (&cache, &cache_key, cached)
                                         {
-                                            if let Some(test_file) = &case.expectations.test_file {
-                                                Some(
-                                                    runner
-                                                        .run_tests(&TestRequest {
-                                                            code: generated_code.clone(),
-                                                            test_code: test_file.clone(),
-                                                            language,
-                                                            dependencies: deps.clone(),
-                                                            timeout_secs,
-                                                        })
-                                                        .await?,
-                                                )
-                                            } else {
-                                                None
+                                            if let Err(e) =
+                                                cache.put(key, &provider_name, attempt, &response)
+                                            {
+                                                tracing::warn!("{e:#}");
                                             }
-                                        } else {
-                                            None
+                                        }
+                                        // A replayed or cached response reports the latency
+                                        // of the original request
+                                        let llm_ms = (gen_start.elapsed().as_millis() as u64)
+                                            .max(response.latency_ms);
+                                        // Splice FIM completions between prefix and suffix
+                                        let generated_code = match &case.fim {
+                                            Some(fim) => fim.splice(&response.extracted_code),
+                                            None => response.extracted_code.clone(),
                                         };
-                                        let test_execution_ms = test_execution
+                                        let execution = execute(
+                                            runner.as_ref(),
+                                            judge.as_deref(),
+                                            budget.as_deref(),
+                                            &case,
+                                            &model,
+                                            &generated_code,
+                                        )
+                                        .await?;
+                                        let compilation_ms = execution.compilation.duration_ms;
+                                        let test_execution_ms = execution
+                                            .test_execution
                                             .as_ref()
-                                            .map(|t| t.duration_ms)
-                                            .unwrap_or(0);
-
-                                        // Run clippy if compilation succeeded
-                                        let clippy = if compile_result.success {
-                                            Some(
-                                                runner
-                                                    .run_clippy(&ClippyRequest {
-                                                        code: generated_code.clone(),
-                                                        language,
-                                                        dependencies: deps,
-                                                        timeout_secs,
-                                                    })
-                                                    .await?,
-                                            )
-                                        } else {
-                                            None
-                                        };
-
+                                            .map_or(0, |t| t.duration_ms);
                                         let total_ms = llm_ms + compilation_ms + test_execution_ms;
 
                                         return Ok(EvalResult {
@@ -232,9 +353,17 @@ impl EvalEngine {
                                             model: model.clone(),
                                             provider: provider_name.clone(),
                                             generated_code,
-                                            compilation: compile_result,
-                                            test_execution,
-                                            clippy,
+                                            compilation: execution.compilation,
+                                            test_execution: execution.test_execution,
+                                            clippy: execution.clippy,
+                                            compile_fail: execution.compile_fail,
+                                            hallucinated_apis: execution.hallucinated_apis,
+                                            formatting: execution.formatting,
+                                            miri: execution.miri,
+                                            performance: execution.performance,
+                                            metrics: execution.metrics,
+                                            judge: execution.judge,
+                                            score: None,
                                             timing: TimingInfo {
                                                 llm_request_ms: llm_ms,
                                                 compilation_ms,
@@ -242,6 +371,7 @@ impl EvalEngine {
                                                 total_ms,
                                             },
                                             token_usage: response.token_usage,
+                                            cached,
                                             attempt,
                                             run_id,
                                         });
@@ -271,18 +401,37 @@ impl EvalEngine {
             }
         }
 
-        let mut results = Vec::new();
         let mut completed = 0usize;
         let mut failed = 0usize;
+        let mut skipped = 0usize;
+        let mut exhausted: Vec<BudgetExceeded> = Vec::new();
         let total = futures.len();
 
         while let Some((case_id, model, result)) = futures.next().await {
             match result {
-                Ok(eval_result) => {
+                Ok(mut eval_result) => {
+                    if let Some(case) = eval_set.cases.iter().find(|c| c.id == case_id) {
+                        eval_result.score =
+                            Some(self.scorer.score(&eval_result, &case.expectations));
+                    }
+                    if let Some(journal) = &self.journal {
+                        if let Err(e) = journal.append(&eval_set.id, &eval_result) {
+                            tracing::warn!("{e:#}");
+                        }
+                    }
                     progress.on_eval_complete(&eval_result);
                     results.push(eval_result);
                     completed += 1;
                 }
+                Err(e) if e.is::<BudgetExceeded>() => {
+                    // Skipped, not failed: the budget stopped it being generated
+                    let stop = e.downcast::<BudgetExceeded>()?;
+                    if !exhausted.contains(&stop) {
+                        tracing::warn!("{stop}; skipping the generations it covers");
+                        exhausted.push(stop);
+                    }
+                    skipped += 1;
+                }
                 Err(e) => {
                     tracing::error!("eval failed for {case_id}/{model}: {e:#}");
                     progress.on_eval_error(&case_id, &model, &e.to_string());
@@ -294,11 +443,148 @@ impl EvalEngine {
         let elapsed = start.elapsed();
         progress.on_set_complete(total, completed, failed, elapsed);
 
-        let aggregate = compute_aggregate_stats(&results, eval_set, &self.config.pass_k);
-
         let models_evaluated: Vec<String> = models.iter().map(|m| m.model.clone()).collect();
+        let mut report = self.build_report(eval_set, models_evaluated, results, run_id, elapsed);
+        report.budget = self.budget.as_ref().map(|budget| BudgetSummary {
+            limits: *budget.config(),
+            spent: budget.spent(),
+            judge: budget.judge_spent(),
+            exhausted,
+            skipped,
+        });
+        Ok(report)
+    }
 
-        Ok(EvalReport {
+    /// Re-run execution and scoring on the generations stored in `report`
+    /// against the current definitions in `eval_set`, without calling any
+    /// provider. Results for cases no longer in the set are dropped. The new
+    /// report gets its own ID and links back with `rescored_from`.
+    pub async fn rescore(
+        &self,
+        eval_set: &EvalSet,
+        report: &EvalReport,
+        progress: &dyn ProgressReporter,
+    ) -> Result<EvalReport> {
+        let start = Instant::now();
+        let run_id = Uuid::new_v4();
+        let semaphore = Arc::new(Semaphore::new(self.config.parallelism));
+
+        let mut futures = FuturesUnordered::new();
+        for original in &report.results {
+            let Some(case) = eval_set.cases.iter().find(|c| c.id == original.case_id) else {
+                continue;
+            };
+            progress.on_eval_start(&case.id, &original.model, original.attempt);
+            let semaphore = Arc::clone(&semaphore);
+            let runner = Arc::clone(&self.runner);
+            let judge = self.judge.clone();
+            let budget = self.budget.clone();
+            futures.push(async move {
+                let inner = async {
+                    let _permit = semaphore
+                        .acquire()
+                        .await
+                        .map_err(|_| anyhow::anyhow!("semaphore closed"))?;
+                    let execution = execute(
+                        runner.as_ref(),
+                        judge.as_deref(),
+                        budget.as_deref(),
+                        case,
+                        &original.model,
+                        &original.generated_code,
+                    )
+                    .await?;
+
+                    let mut result = original.clone();
+                    result.timing.compilation_ms = execution.compilation.duration_ms;
+                    result.timing.test_execution_ms = execution
+                        .test_execution
+                        .as_ref()
+                        .map_or(0, |t| t.duration_ms);
+                    result.timing.total_ms = result.timing.llm_request_ms
+                        + result.timing.compilation_ms
+                        + result.timing.test_execution_ms;
+                    result.compilation = execution.compilation;
+                    result.test_execution = execution.test_execution;
+                    result.clippy = execution.clippy;
+                    result.compile_fail = execution.compile_fail;
+                    result.hallucinated_apis = execution.hallucinated_apis;
+                    result.formatting = execution.formatting;
+                    result.miri = execution.miri;
+                    result.performance = execution.performance;
+                    result.metrics = execution.metrics;
+                    // Without a judge, keep the verdict the generation already has
+                    if judge.is_some() {
+                        result.judge = execution.judge;
+                    }
+                    result.score = Some(self.scorer.score(&result, &case.expectations));
+                    result.run_id = run_id;
+                    Ok::<_, anyhow::Error>(result)
+                };
+                (original, inner.await)
+            });
+        }
+
+        let mut results = Vec::new();
+        let (total, mut failed) = (futures.len(), 0usize);
+        while let Some((original, result)) = futures.next().await {
+            match result {
+                Ok(result) => {
+                    progress.on_eval_complete(&result);
+                    results.push(result);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "rescoring failed for {}/{}: {e:#}",
+                        original.case_id,
+                        original.model
+                    );
+                    progress.on_eval_error(&original.case_id, &original.model, &e.to_string());
+                    failed += 1;
+                }
+            }
+        }
+        let elapsed = start.elapsed();
+        progress.on_set_complete(total, results.len(), failed, elapsed);
+
+        // Only the cases the report has generations for
+        let rescored_set = EvalSet {
+            cases: eval_set
+                .cases
+                .iter()
+                .filter(|c| report.results.iter().any(|r| r.case_id == c.id))
+                .cloned()
+                .collect(),
+            ..eval_set.clone()
+        };
+        let mut rescored = self.build_report(
+            &rescored_set,
+            report.models_evaluated.clone(),
+            results,
+            run_id,
+            elapsed,
+        );
+        rescored.rescored_from = Some(report.id);
+        Ok(rescored)
+    }
+
+    fn build_report(
+        &self,
+        eval_set: &EvalSet,
+        models_evaluated: Vec<String>,
+        results: Vec<EvalResult>,
+        run_id: Uuid,
+        elapsed: Duration,
+    ) -> EvalReport {
+        let aggregate = compute_aggregate_stats_with(
+            &results,
+            eval_set,
+            &self.config.pass_k,
+            self.scorer.as_ref(),
+            &self.config.intervals,
+        );
+
+        EvalReport {
             id: run_id,
             created_at: chrono::Utc::now(),
             eval_set: EvalSetSummary {
@@ -309,14 +595,674 @@ impl EvalEngine {
             models_evaluated,
             results,
             aggregate,
+            scoring: self.scorer.profile().cloned(),
+            cases: eval_set
+                .cases
+                .iter()
+                .map(|case| (case.id.clone(), CaseSnapshot::from_case(case)))
+                .collect(),
+            rescored_from: None,
+            budget: None,
             duration_ms: elapsed.as_millis() as u64,
-        })
+        }
     }
 }
 
+/// What running generated code through the pipeline produced.
+struct Execution {
+    compilation: CompilationResult,
+    test_execution: Option<TestResult>,
+    clippy: Option<ClippyResult>,
+    compile_fail: Vec<CompileFailResult>,
+    hallucinated_apis: Vec<HallucinatedApi>,
+    formatting: Option<FormattingResult>,
+    miri: Option<MiriResult>,
+    performance: Option<PerformanceResult>,
+    metrics: Option<CodeMetrics>,
+    judge: Option<JudgeResult>,
+}
+
+/// Compile, test, benchmark, lint and judge generated code for a case.
+async fn execute(
+    runner: &dyn CodeRunner,
+    judge: Option<&Judge>,
+    budget: Option<&Budget>,
+    case: &EvalCase,
+    model: &str,
+    generated_code: &str,
+) -> Result<Execution> {
+    let language = case.language.unwrap_or(Language::Rust);
+    let timeout_secs = case.timeout_secs.unwrap_or(60);
+
+    let deps = case.dependencies.clone();
+
+    let fixture = case.fixture.clone();
+
+    // Compile the generated code
+    let compile_result = runner
+        .compile(&CompileRequest {
+            code: generated_code.to_string(),
+            language,
+            dependencies: deps.clone(),
+            timeout_secs,
+            fixture: fixture.clone(),
+        })
+        .await?;
+
+    // Run tests if compilation succeeded and there is a
+    // test_file or a fixture with its own test suite
+    let test_execution = if compile_result.success
+        && case.expectations.should_pass_tests
+        && (case.expectations.test_file.is_some() || fixture.is_some())
+    {
+        Some(
+            runner
+                .run_tests(&TestRequest {
+                    code: generated_code.to_string(),
+                    test_code: case.expectations.test_file.clone().unwrap_or_default(),
+                    language,
+                    dependencies: deps.clone(),
+                    timeout_secs,
+                    fixture: fixture.clone(),
+                })
+                .await?,
+        )
+    } else {
+        None
+    };
+    // Benchmark against the performance budget
+    let performance = match &case.expectations.performance {
+        Some(perf) if compile_result.success => Some(
+            runner
+                .run_benchmark(&BenchmarkRequest {
+                    code: generated_code.to_string(),
+                    performance: perf.clone(),
+                    reference_solution: case.expectations.reference_solution.clone(),
+                    language,
+                    dependencies: deps.clone(),
+                    timeout_secs,
+                    fixture: fixture.clone(),
+                })
+                .await?,
+        ),
+        _ => None,
+    };
+
+    // Look for undefined behaviour if the case opts in
+    let miri = if compile_result.success && case.expectations.run_miri {
+        Some(
+            runner
+                .run_miri(&TestRequest {
+                    code: generated_code.to_string(),
+                    test_code: case.expectations.test_file.clone().unwrap_or_default(),
+                    language,
+                    dependencies: deps.clone(),
+                    timeout_secs,
+                    fixture: fixture.clone(),
+                })
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    // Check that misuse snippets are rejected
+    let compile_fail =
+        if compile_result.success && !case.expectations.expected_compile_failures.is_empty() {
+            runner
+                .check_compile_failures(&CompileFailRequest {
+                    code: generated_code.to_string(),
+                    snippets: case.expectations.expected_compile_failures.clone(),
+                    language,
+                    dependencies: deps.clone(),
+                    timeout_secs,
+                    fixture: fixture.clone(),
+                })
+                .await?
+        } else {
+            Vec::new()
+        };
+
+    // Look for calls into APIs the dependencies don't have
+    let hallucinated_apis = if !compile_result.success && language == Language::Rust {
+        runner
+            .check_api_usage(&ApiCheckRequest {
+                code: generated_code.to_string(),
+                errors: compile_result.errors.clone(),
+                dependencies: deps.clone(),
+            })
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    // Run clippy if compilation succeeded
+    let clippy = if compile_result.success {
+        Some(
+            runner
+                .run_clippy(&ClippyRequest {
+                    code: generated_code.to_string(),
+                    language,
+                    dependencies: deps,
+                    timeout_secs,
+                    fixture,
+                    config: case.expectations.clippy.clone(),
+                })
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    // Check formatting if compilation succeeded
+    let formatting = if compile_result.success {
+        runner
+            .check_formatting(&FormatRequest {
+                code: generated_code.to_string(),
+                language,
+                config: case.expectations.formatting.clone(),
+                timeout_secs,
+            })
+            .await?
+    } else {
+        None
+    };
+
+    let metrics = if language == Language::Rust {
+        crate::metrics::analyze(generated_code)
+    } else {
+        None
+    };
+
+    // Grade untestable qualities with the LLM judge
+    let judge_result = match (judge, &case.expectations.judge) {
+        (Some(judge), Some(rubric)) if compile_result.success && !rubric.criteria.is_empty() => {
+            judge
+                .grade(&case.prompt, generated_code, language, rubric, budget)
+                .await
+                .map_err(|e| tracing::warn!("judge failed for {}/{model}: {e:#}", case.id))
+                .ok()
+        }
+        _ => None,
+    };
+
+    Ok(Execution {
+        compilation: compile_result,
+        test_execution,
+        clippy,
+        compile_fail,
+        hallucinated_apis,
+        formatting,
+        miri,
+        performance,
+        metrics,
+        judge: judge_result,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{EvalCase, Expectations, FimSpec};
+    use crate::results::{ClippyResult, CompilationResult, Score, TestResult, TokenUsage};
+    use crate::traits::{GenerateResponse, ModelInfo};
+    use std::sync::Mutex;
+
+    /// Provider stub that answers every request with a fixed completion.
+    struct StubProvider {
+        response: String,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for StubProvider {
+        fn name(&self) -> &str {
+            "stub"
+        }
+
+        async fn generate(&self, request: &GenerateRequest) -> Result<GenerateResponse> {
+            self.complete(&CompletionRequest {
+                model: request.model.clone(),
+                prefix: request.prompt.clone(),
+                suffix: String::new(),
+                max_tokens: request.max_tokens,
+                temperature: request.temperature,
+                stop_sequences: vec![],
+                attempt: request.attempt,
+            })
+            .await
+        }
+
+        async fn complete(&self, request: &CompletionRequest) -> Result<GenerateResponse> {
+            Ok(GenerateResponse {
+                content: self.response.clone(),
+                extracted_code: self.response.clone(),
+                model: request.model.clone(),
+                token_usage: TokenUsage {
+                    prompt_tokens: 10,
+                    completion_tokens: 5,
+                    total_tokens: 15,
+                    estimated_cost_usd: 0.0,
+                },
+                latency_ms: 250,
+            })
+        }
+
+        fn available_models(&self) -> Vec<ModelInfo> {
+            vec![]
+        }
+    }
+
+    /// Runner stub that records the code it was asked to compile.
+    #[derive(Default)]
+    struct RecordingRunner {
+        compiled: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CodeRunner for RecordingRunner {
+        async fn compile(&self, request: &CompileRequest) -> Result<CompilationResult> {
+            self.compiled.lock().unwrap().push(request.code.clone());
+            Ok(CompilationResult {
+                success: true,
+                errors: vec![],
+                warnings: vec![],
+                duration_ms: 0,
+            })
+        }
+
+        async fn run_tests(&self, _: &TestRequest) -> Result<TestResult> {
+            Ok(TestResult {
+                passed: 1,
+                failed: 0,
+                ignored: 0,
+                duration_ms: 0,
+                failures: vec![],
+            })
+        }
+
+        async fn run_clippy(&self, _: &ClippyRequest) -> Result<ClippyResult> {
+            Ok(ClippyResult {
+                warnings: vec![],
+                warning_count: 0,
+                by_category: Default::default(),
+            })
+        }
+    }
+
+    fn make_case(id: &str) -> EvalCase {
+        EvalCase {
+            id: id.into(),
+            name: id.into(),
+            description: String::new(),
+            prompt: String::new(),
+            language: Some(Language::Rust),
+            context: vec![],
+            expectations: Expectations::default(),
+            tags: vec![],
+            difficulty: None,
+            dependencies: vec![],
+            timeout_secs: None,
+            max_tokens: None,
+            fixture: None,
+            fim: None,
+        }
+    }
+
+    fn make_set(cases: Vec<EvalCase>) -> EvalSet {
+        EvalSet {
+            id: "set".into(),
+            name: "Set".into(),
+            description: String::new(),
+            cases,
+            default_language: Language::Rust,
+            default_timeout_secs: 60,
+            scoring: None,
+        }
+    }
+
+    fn make_engine(
+        response: &str,
+        runner: Arc<RecordingRunner>,
+        config: EvalEngineConfig,
+    ) -> EvalEngine {
+        let mut providers: HashMap<String, Arc<dyn LlmProvider>> = HashMap::new();
+        providers.insert(
+            "stub".into(),
+            Arc::new(StubProvider {
+                response: response.into(),
+            }),
+        );
+        EvalEngine::new(providers, runner, config)
+    }
+
+    fn stub_model() -> Vec<ModelSpec> {
+        vec![ModelSpec {
+            provider: "stub".into(),
+            model: "stub-model".into(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn fim_completion_is_spliced_before_compiling() {
+        let mut case = make_case("fim");
+        case.fim = Some(FimSpec {
+            prefix: "pub fn add(a: i32, b: i32) -> i32 {\n".into(),
+            suffix: "\n}\n".into(),
+        });
+        let runner = Arc::new(RecordingRunner::default());
+        let engine = make_engine("    a + b", runner.clone(), EvalEngineConfig::default());
+
+        let report = engine
+            .run(&make_set(vec![case]), &stub_model(), &NoopReporter)
+            .await
+            .unwrap();
+
+        let expected = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+        assert_eq!(report.results[0].generated_code, expected);
+        assert_eq!(runner.compiled.lock().unwrap()[0], expected);
+        assert!(report.cases.contains_key("fim"));
+    }
+
+    #[tokio::test]
+    async fn provider_latency_is_reported() {
+        let runner = Arc::new(RecordingRunner::default());
+        let engine = make_engine("pub fn f() {}", runner, EvalEngineConfig::default());
+
+        let report = engine
+            .run(
+                &make_set(vec![make_case("a")]),
+                &stub_model(),
+                &NoopReporter,
+            )
+            .await
+            .unwrap();
+
+        // The stub answers at once but reports 250 ms, as a replay would
+        let timing = &report.results[0].timing;
+        assert!(timing.llm_request_ms >= 250);
+        assert!(timing.total_ms >= timing.llm_request_ms);
+    }
+
+    /// Scores everything as a flat 0.5 so its use is visible in results and stats.
+    struct HalfScorer;
+
+    impl Scorer for HalfScorer {
+        fn id(&self) -> &str {
+            "half"
+        }
+
+        fn score(&self, result: &EvalResult, expectations: &Expectations) -> Score {
+            let mut score = DefaultScorer::default().score(result, expectations);
+            score.clippy = 0.5;
+            score.overall = 0.5;
+            score.scorer = self.id().into();
+            score
+        }
+
+        fn is_correct(&self, _: &EvalResult, _: &Expectations) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_scorer_is_recorded_and_used_for_stats() {
+        let runner = Arc::new(RecordingRunner::default());
+        let engine = make_engine("pub fn f() {}", runner, EvalEngineConfig::default())
+            .with_scorer(Arc::new(HalfScorer));
+        assert_eq!(engine.scorer().id(), "half");
+
+        let report = engine
+            .run(
+                &make_set(vec![make_case("c")]),
+                &stub_model(),
+                &NoopReporter,
+            )
+            .await
+            .unwrap();
+
+        let score = report.results[0].score.as_ref().unwrap();
+        assert_eq!(score.scorer, "half");
+        assert_eq!(score.overall, 0.5);
+
+        let stats = &report.aggregate.per_model["stub-model"];
+        assert_eq!(stats.avg_clippy_score, 0.5);
+        assert_eq!(stats.pass_at_k[&1], 0.0);
+    }
+
+    #[tokio::test]
+    async fn resume_runs_only_what_the_journal_lacks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let set = make_set(vec![make_case("a"), make_case("b")]);
+
+        // First run only gets through case "a"
+        let runner = Arc::new(RecordingRunner::default());
+        let journal = Arc::new(Journal::open(&path).unwrap());
+        let first = make_engine("pub fn f() {}", runner, EvalEngineConfig::default())
+            .with_journal(journal)
+            .run(
+                &make_set(vec![make_case("a")]),
+                &stub_model(),
+                &NoopReporter,
+            )
+            .await
+            .unwrap();
+
+        let completed: Vec<EvalResult> = Journal::load(&path)
+            .unwrap()
+            .entries
+            .into_iter()
+            .map(|e| e.result)
+            .collect();
+        assert_eq!(completed.len(), 1);
+
+        let runner = Arc::new(RecordingRunner::default());
+        let journal = Arc::new(Journal::open(&path).unwrap());
+        let resumed = make_engine("pub fn f() {}", runner.clone(), EvalEngineConfig::default())
+            .with_journal(journal)
+            .resume(&set, &stub_model(), &NoopReporter, completed)
+            .await
+            .unwrap();
+
+        assert_eq!(runner.compiled.lock().unwrap().len(), 1);
+        assert_eq!(resumed.id, first.id);
+        let mut cases: Vec<&str> = resumed.results.iter().map(|r| r.case_id.as_str()).collect();
+        cases.sort();
+        assert_eq!(cases, ["a", "b"]);
+        assert!(resumed.results.iter().all(|r| r.run_id == first.id));
+        assert_eq!(Journal::load(&path).unwrap().entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn budget_stops_dispatch_and_marks_the_report_partial() {
+        use crate::budget::{BudgetConfig, BudgetLimits};
+
+        let budget = Arc::new(Budget::new(BudgetConfig {
+            global: BudgetLimits {
+                max_requests: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        }));
+        let config = EvalEngineConfig {
+            parallelism: 1,
+            ..EvalEngineConfig::default()
+        };
+        let runner = Arc::new(RecordingRunner::default());
+        let report = make_engine("pub fn f() {}", runner.clone(), config)
+            .with_budget(Arc::clone(&budget))
+            .run(
+                &make_set(vec![make_case("a"), make_case("b"), make_case("c")]),
+                &stub_model(),
+                &NoopReporter,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(runner.compiled.lock().unwrap().len(), 2);
+        let summary = report.budget.unwrap();
+        assert!(summary.is_partial());
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.spent.requests, 2);
+        assert_eq!(summary.spent.tokens, 30);
+        assert_eq!(summary.exhausted.len(), 1);
+        assert_eq!(summary.exhausted[0].model, None);
+    }
+
+    #[tokio::test]
+    async fn rescore_reexecutes_stored_generations() {
+        let runner = Arc::new(RecordingRunner::default());
+        let original = make_engine("pub fn f() {}", runner, EvalEngineConfig::default())
+            .run(
+                &make_set(vec![make_case("a"), make_case("b")]),
+                &stub_model(),
+                &NoopReporter,
+            )
+            .await
+            .unwrap();
+        assert!(original.results.iter().all(|r| r.test_execution.is_none()));
+
+        // "a" gains a test file and a reworded prompt; "b" is gone
+        let mut fixed = make_case("a");
+        fixed.prompt = "Write f, carefully".into();
+        fixed.expectations.test_file = Some("#[test] fn t() {}".into());
+        let set = make_set(vec![fixed]);
+        assert_eq!(original.changed_prompts(&set), ["a"]);
+
+        let runner = Arc::new(RecordingRunner::default());
+        let rescored = make_engine("pub fn g() {}", runner.clone(), EvalEngineConfig::default())
+            .rescore(&set, &original, &NoopReporter)
+            .await
+            .unwrap();
+
+        // The stored generation is compiled, not a new one
+        assert_eq!(*runner.compiled.lock().unwrap(), ["pub fn f() {}"]);
+        assert_eq!(rescored.results.len(), 1);
+        let result = &rescored.results[0];
+        assert!(result.test_execution.is_some());
+        assert_eq!(result.token_usage.total_tokens, 15);
+        assert_eq!(result.run_id, rescored.id);
+        assert_ne!(rescored.id, original.id);
+        assert_eq!(rescored.rescored_from, Some(original.id));
+        assert_eq!(rescored.eval_set.case_count, 1);
+    }
+
+    #[tokio::test]
+    async fn cached_generations_are_reused_and_marked() {
+        use crate::cache::CacheMode;
+
+        let dir = tempfile::tempdir().unwrap();
+        let set = make_set(vec![make_case("a")]);
+        let run = |mode, response: &str| {
+            let cache = Arc::new(ResponseCache::new(dir.path(), mode));
+            let engine = make_engine(
+                response,
+                Arc::new(RecordingRunner::default()),
+                EvalEngineConfig::default(),
+            )
+            .with_cache(cache);
+            let set = set.clone();
+            async move {
+                engine
+                    .run(&set, &stub_model(), &NoopReporter)
+                    .await
+                    .unwrap()
+                    .results
+                    .remove(0)
+            }
+        };
+
+        let first = run(CacheMode::Read, "pub fn one() {}").await;
+        assert!(!first.cached);
+        // The provider now answers differently, but the cache wins
+        let second = run(CacheMode::Read, "pub fn two() {}").await;
+        assert!(second.cached);
+        assert_eq!(second.generated_code, "pub fn one() {}");
+        // Write mode always asks the provider and refreshes the entry
+        let third = run(CacheMode::Write, "pub fn three() {}").await;
+        assert!(!third.cached);
+        assert_eq!(third.generated_code, "pub fn three() {}");
+        let fourth = run(CacheMode::Read, "pub fn four() {}").await;
+        assert_eq!(fourth.generated_code, "pub fn three() {}");
+    }
+
+    #[tokio::test]
+    async fn judge_grades_rubric_cases_and_reuses_verdicts() {
+        use crate::model::{JudgeCriterion, JudgeRubric};
+
+        let mut judged = make_case("judged");
+        judged.expectations.judge = Some(JudgeRubric {
+            criteria: vec![JudgeCriterion {
+                name: "naming".into(),
+                description: "Names are descriptive".into(),
+                weight: 1.0,
+            }],
+            instructions: None,
+            weight: 0.5,
+        });
+        let runner = Arc::new(RecordingRunner::default());
+        let config = EvalEngineConfig {
+            pass_k: vec![1, 2],
+            parallelism: 1,
+            ..EvalEngineConfig::default()
+        };
+        let judge = Judge::new(
+            Arc::new(StubProvider {
+                response: r#"{"scores": {"naming": 4}, "rationale": "terse names"}"#.into(),
+            }),
+            "judge-model",
+        );
+        let engine = make_engine("pub fn f() {}", runner, config).with_judge(Arc::new(judge));
+
+        let report = engine
+            .run(
+                &make_set(vec![judged, make_case("plain")]),
+                &stub_model(),
+                &NoopReporter,
+            )
+            .await
+            .unwrap();
+
+        let judged: Vec<_> = report
+            .results
+            .iter()
+            .filter(|r| r.case_id == "judged")
+            .collect();
+        assert_eq!(judged.len(), 2);
+        for result in &judged {
+            let verdict = result.judge.as_ref().unwrap();
+            assert_eq!(verdict.model, "judge-model");
+            assert_eq!(verdict.criteria["naming"], 0.4);
+            let score = result.score.as_ref().unwrap();
+            assert_eq!(score.judge, Some(0.4));
+            // Half of the overall score comes from the judge (no tests ran,
+            // so the base score is 0.3 + 0.15 + 0.1)
+            assert!((score.overall - (0.55 * 0.5 + 0.4 * 0.5)).abs() < 1e-9);
+        }
+        // Identical code is graded once
+        assert_eq!(
+            judged
+                .iter()
+                .filter(|r| r.judge.as_ref().unwrap().cached)
+                .count(),
+            1
+        );
+
+        let plain = report
+            .results
+            .iter()
+            .find(|r| r.case_id == "plain")
+            .unwrap();
+        assert!(plain.judge.is_none());
+        assert_eq!(plain.score.as_ref().unwrap().judge, None);
+
+        // Judge usage is tracked apart from the model under test
+        let stats = &report.aggregate.per_model["stub-model"];
+        assert_eq!(stats.judge_tokens, 15);
+        assert_eq!(stats.total_tokens, 4 * 15);
+        assert_eq!(stats.avg_judge_score, Some(0.4));
+    }
 
     #[test]
     fn provider_error_classification() {
@@ -335,5 +1281,11 @@ mod tests {
 
         let timeout = ProviderError::Timeout(120);
         assert!(!timeout.is_permanent());
+
+        let unsupported = ProviderError::Unsupported("no FIM".into());
+        assert!(unsupported.is_permanent());
+
+        let not_recorded = ProviderError::NotRecorded("abc123".into());
+        assert!(not_recorded.is_permanent());
     }
 }
@@ -32,6 +32,14 @@ pub enum ProviderError {
     /// A network error occurred.
     #[error("network error: {0}")]
     NetworkError(String),
+
+    /// The provider does not support the requested operation.
+    #[error("unsupported by provider: {0}")]
+    Unsupported(String),
+
+    /// A replayed cassette has no recorded response for the request.
+    #[error("no recorded response: {0}")]
+    NotRecorded(String),
 }
 
 impl ProviderError {
@@ -39,7 +47,10 @@ impl ProviderError {
     pub fn is_permanent(&self) -> bool {
         matches!(
             self,
-            ProviderError::AuthenticationFailed(_) | ProviderError::ModelNotFound(_)
+            ProviderError::AuthenticationFailed(_)
+                | ProviderError::ModelNotFound(_)
+                | ProviderError::Unsupported(_)
+                | ProviderError::NotRecorded(_)
         )
     }
 
@@ -0,0 +1,528 @@
+//! Hallucinated-API detection.
+//!
+//! Models often call functions, types and methods that don't exist in the
+//! crates they depend on (`tokio::task::spawn_async`, `Runtime::run`, ...).
+//! This module picks the "cannot find" family of rustc errors out of a
+//! failed compile, attributes them to the case's declared dependencies and,
+//! given an index of a crate's real items, drops errors about items that do
+//! exist (typically behind a disabled feature) and suggests the closest real
+//! item for the rest.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::results::CompilerDiagnostic;
+use crate::traits::Dependency;
+
+/// A use of an item that doesn't exist in a dependency crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HallucinatedApi {
+    /// The dependency the item was looked up in (underscored, e.g. `serde_json`).
+    pub crate_name: String,
+    /// The path as written, e.g. `tokio::task::spawn_async` or `Runtime::run`.
+    pub path: String,
+    /// The missing item's name.
+    pub item: String,
+    /// The rustc error code that reported it.
+    pub code: String,
+    /// The closest real item, e.g. `tokio::task::spawn_blocking`.
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+/// The public items of a dependency crate, as found in its sources.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateApi {
+    /// Crate name (underscored).
+    pub name: String,
+    /// Item name to the module paths (relative to the crate root, `""` for
+    /// the root itself) it's declared or re-exported in. Methods count as
+    /// items of the module their `impl` block lives in.
+    pub items: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CrateApi {
+    /// An empty index for `name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: crate_ident(name),
+            items: BTreeMap::new(),
+        }
+    }
+
+    /// Record `item` as declared in `module`.
+    pub fn insert(&mut self, module: &str, item: &str) {
+        self.items
+            .entry(item.to_string())
+            .or_default()
+            .insert(module.to_string());
+    }
+
+    /// Whether `item` is declared or re-exported in `module`.
+    pub fn contains(&self, module: &str, item: &str) -> bool {
+        self.items.get(item).is_some_and(|m| m.contains(module))
+    }
+
+    /// Full path of the item in `preferred` if it's declared there, else of
+    /// its shallowest declaration, e.g. `tokio::time::sleep`.
+    fn full_path(&self, item: &str, preferred: &str) -> Option<String> {
+        let modules = self.items.get(item)?;
+        let module = if modules.contains(preferred) {
+            preferred
+        } else {
+            modules
+                .iter()
+                .min_by_key(|m| (m.split("::").filter(|s| !s.is_empty()).count(), *m))?
+        };
+        Some(if module.is_empty() {
+            format!("{}::{item}", self.name)
+        } else {
+            format!("{}::{module}::{item}", self.name)
+        })
+    }
+
+    /// The item whose name is closest to `item`, if any is close enough to
+    /// be a plausible intent.
+    fn closest(&self, item: &str) -> Option<&str> {
+        let limit = (item.chars().count() / 3).max(1);
+        self.items
+            .keys()
+            .map(|name| (levenshtein(item, name), name))
+            .filter(|(distance, _)| *distance <= limit)
+            .min_by_key(|(distance, name)| (*distance, name.len(), name.as_str()))
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+/// A "cannot find" error, parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MissingItem {
+    code: String,
+    /// Path segments the item was looked up in (a module path for path
+    /// errors, a type path for method errors).
+    scope: Vec<String>,
+    item: String,
+    /// Whether the item is a method or associated item of `scope`.
+    member: bool,
+}
+
+/// Find hallucinated dependency APIs among a failed compile's errors.
+///
+/// `code` is the generated source; its `use` declarations resolve imported
+/// names like `task::spawn_async` or `Runtime` back to their crate. `apis`
+/// holds indexes of the dependencies' real items; dependencies without one
+/// are trusted to rustc, and get no suggestions.
+pub fn find_hallucinated_apis(
+    errors: &[CompilerDiagnostic],
+    code: &str,
+    dependencies: &[Dependency],
+    apis: &[CrateApi],
+) -> Vec<HallucinatedApi> {
+    let crates: HashSet<String> = dependencies.iter().map(|d| crate_ident(&d.name)).collect();
+    let apis: HashMap<&str, &CrateApi> = apis.iter().map(|a| (a.name.as_str(), a)).collect();
+    let imports = imports(code);
+
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for missing in errors.iter().filter_map(parse_missing) {
+        let Some(scope) = resolve(&missing.scope, &imports) else {
+            continue;
+        };
+        let Some(crate_name) = scope.first().filter(|c| crates.contains(*c)) else {
+            continue;
+        };
+        let api = apis.get(crate_name.as_str());
+        let module = scope[1..].join("::");
+        if let Some(api) = api {
+            let exists = if missing.member {
+                api.items.contains_key(&missing.item)
+            } else {
+                api.contains(&module, &missing.item)
+            };
+            if exists {
+                continue;
+            }
+        }
+        let path = format!("{}::{}", missing.scope.join("::"), missing.item);
+        if !seen.insert((crate_name.clone(), path.clone())) {
+            continue;
+        }
+        let suggestion = api.and_then(|api| {
+            if missing.member {
+                let name = api.closest(&missing.item)?;
+                Some(format!("{}::{name}", missing.scope.join("::")))
+            } else if api.items.contains_key(&missing.item) {
+                // Real item, wrong module
+                api.full_path(&missing.item, &module)
+            } else {
+                api.full_path(api.closest(&missing.item)?, &module)
+            }
+        });
+        found.push(HallucinatedApi {
+            crate_name: crate_name.clone(),
+            path,
+            item: missing.item,
+            code: missing.code,
+            suggestion,
+        });
+    }
+    found
+}
+
+/// Parse a "cannot find" error into the item and where it was looked for.
+fn parse_missing(diagnostic: &CompilerDiagnostic) -> Option<MissingItem> {
+    let code = diagnostic.code.as_deref()?;
+    let message = diagnostic.message.trim();
+    let quoted = backticked(message);
+    let (scope, item, member) = match code {
+        // cannot find function `x` in crate `tokio` / module `tokio::task`
+        "E0405" | "E0412" | "E0422" | "E0423" | "E0425" | "E0531" => {
+            if !message.contains(" in crate `") && !message.contains(" in module `") {
+                return None;
+            }
+            (
+                split_path(quoted.get(1)?),
+                quoted.first()?.to_string(),
+                false,
+            )
+        }
+        // failed to resolve: could not find `x` in `tokio`
+        "E0433" => {
+            if !message.contains("could not find `") {
+                return None;
+            }
+            (
+                split_path(quoted.get(1)?),
+                quoted.first()?.to_string(),
+                false,
+            )
+        }
+        // unresolved import `tokio::task::spawn_async`
+        "E0432" => {
+            let mut path = split_path(quoted.first()?);
+            let item = path.pop()?;
+            (path, item, false)
+        }
+        // no method named `x` found for struct `Runtime` in the current scope
+        "E0599" => {
+            if !message.starts_with("no ") {
+                return None;
+            }
+            (
+                split_path(&base_type(quoted.get(1)?)),
+                quoted.first()?.to_string(),
+                true,
+            )
+        }
+        _ => return None,
+    };
+    if scope.is_empty() || item.is_empty() {
+        return None;
+    }
+    Some(MissingItem {
+        code: code.to_string(),
+        scope,
+        item,
+        member,
+    })
+}
+
+/// The backtick-quoted spans of a message.
+fn backticked(message: &str) -> Vec<&str> {
+    message.split('`').skip(1).step_by(2).collect()
+}
+
+/// Split a path into segments, dropping a leading `::` or `crate`.
+fn split_path(path: &str) -> Vec<String> {
+    path.trim_start_matches("::")
+        .split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Strip references and generic arguments: `&mut Foo<T>` → `Foo`.
+fn base_type(ty: &str) -> String {
+    let ty = ty
+        .trim_start_matches('&')
+        .trim_start_matches("mut ")
+        .trim_start_matches("dyn ")
+        .trim();
+    ty.split('<').next().unwrap_or(ty).trim().to_string()
+}
+
+/// Resolve the first segment of `path` through the code's imports.
+fn resolve(path: &[String], imports: &HashMap<String, Vec<String>>) -> Option<Vec<String>> {
+    let first = path.first()?;
+    match imports.get(first) {
+        Some(full) => Some(full.iter().chain(&path[1..]).cloned().collect()),
+        None => Some(path.to_vec()),
+    }
+}
+
+/// Names brought into scope by the code's `use` declarations, mapped to the
+/// full paths they stand for.
+fn imports(code: &str) -> HashMap<String, Vec<String>> {
+    let mut imports = HashMap::new();
+    let mut rest = code;
+    while let Some(start) = find_use(rest) {
+        let tail = &rest[start..];
+        let end = tail.find(';').unwrap_or(tail.len());
+        collect_use_tree(&[], tail[..end].trim(), &mut imports);
+        rest = &tail[end..];
+    }
+    imports
+}
+
+/// Byte offset just past the next `use` keyword that starts a declaration.
+fn find_use(code: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(pos) = code[offset..].find("use ") {
+        let at = offset + pos;
+        let before = code[..at].trim_end_matches(' ');
+        let boundary = before.is_empty()
+            || before.ends_with(['\n', ';', '{', '}'])
+            || before.ends_with("pub")
+            || before.ends_with(')');
+        if boundary {
+            return Some(at + 4);
+        }
+        offset = at + 4;
+    }
+    None
+}
+
+/// Add the leaves of a use tree such as `tokio::{task, time::sleep as nap}`.
+fn collect_use_tree(prefix: &[String], tree: &str, out: &mut HashMap<String, Vec<String>>) {
+    let tree = tree.trim();
+    if let Some(open) = tree.find('{') {
+        let close = tree.rfind('}').unwrap_or(tree.len());
+        let mut base = prefix.to_vec();
+        base.extend(split_path(tree[..open].trim_end_matches("::")));
+        for part in split_top_level(&tree[open + 1..close]) {
+            collect_use_tree(&base, part, out);
+        }
+        return;
+    }
+    let (path, alias) = match tree.split_once(" as ") {
+        Some((path, alias)) => (path, Some(alias.trim())),
+        None => (tree, None),
+    };
+    let mut full = prefix.to_vec();
+    full.extend(split_path(path));
+    if full.last().is_some_and(|s| s == "self") {
+        full.pop();
+    }
+    let Some(last) = full.last().cloned() else {
+        return;
+    };
+    if last == "*" || alias == Some("_") {
+        return;
+    }
+    out.insert(alias.map_or(last, str::to_string), full);
+}
+
+/// Split on commas that aren't nested inside braces.
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&list[start..]);
+    parts.into_iter().filter(|p| !p.trim().is_empty()).collect()
+}
+
+/// A crate name as it appears in paths (`serde-json` → `serde_json`).
+pub fn crate_ident(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Edit distance between two strings.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::DiagnosticLevel;
+
+    fn error(code: &str, message: &str) -> CompilerDiagnostic {
+        CompilerDiagnostic {
+            level: DiagnosticLevel::Error,
+            message: message.into(),
+            code: Some(code.into()),
+            spans: vec![],
+        }
+    }
+
+    fn deps(names: &[&str]) -> Vec<Dependency> {
+        names
+            .iter()
+            .map(|name| Dependency {
+                name: name.to_string(),
+                version: "1".into(),
+                features: vec![],
+            })
+            .collect()
+    }
+
+    fn tokio_api() -> CrateApi {
+        let mut api = CrateApi::new("tokio");
+        api.insert("", "spawn");
+        api.insert("task", "spawn");
+        api.insert("task", "spawn_blocking");
+        api.insert("blocking", "spawn_blocking");
+        api.insert("time", "sleep");
+        api.insert("runtime", "Runtime");
+        api.insert("runtime", "block_on");
+        api.insert("sync", "Mutex");
+        api
+    }
+
+    #[test]
+    fn flags_missing_items_in_dependency_paths() {
+        let errors = [
+            error(
+                "E0425",
+                "cannot find function `spawn_blockin` in module `tokio::task`",
+            ),
+            error("E0425", "cannot find value `x` in this scope"),
+            error("E0308", "mismatched types"),
+        ];
+        let found = find_hallucinated_apis(&errors, "", &deps(&["tokio"]), &[tokio_api()]);
+        assert_eq!(
+            found,
+            vec![HallucinatedApi {
+                crate_name: "tokio".into(),
+                path: "tokio::task::spawn_blockin".into(),
+                item: "spawn_blockin".into(),
+                code: "E0425".into(),
+                suggestion: Some("tokio::task::spawn_blocking".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_crates_that_are_not_dependencies() {
+        let errors = [error(
+            "E0433",
+            "failed to resolve: could not find `foo` in `rand`",
+        )];
+        assert!(find_hallucinated_apis(&errors, "", &deps(&["tokio"]), &[]).is_empty());
+    }
+
+    #[test]
+    fn real_items_in_disabled_modules_are_not_flagged() {
+        // `tokio::time::sleep` exists but the `time` feature is off
+        let errors = [error(
+            "E0425",
+            "cannot find function `sleep` in module `tokio::time`",
+        )];
+        let found = find_hallucinated_apis(&errors, "", &deps(&["tokio"]), &[tokio_api()]);
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn real_items_in_the_wrong_module_point_at_the_right_one() {
+        let errors = [error("E0432", "unresolved import `tokio::sync::sleep`")];
+        let found = find_hallucinated_apis(&errors, "", &deps(&["tokio"]), &[tokio_api()]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "tokio::sync::sleep");
+        assert_eq!(found[0].suggestion.as_deref(), Some("tokio::time::sleep"));
+    }
+
+    #[test]
+    fn methods_on_imported_types_are_attributed_through_use() {
+        let code = "use tokio::runtime::{Builder, Runtime as Rt};\nfn main() {}";
+        let errors = [
+            error(
+                "E0599",
+                "no method named `block_onn` found for struct `Rt` in the current scope",
+            ),
+            error(
+                "E0599",
+                "no method named `len` found for struct `Vec<u8>` in the current scope",
+            ),
+        ];
+        let found = find_hallucinated_apis(&errors, code, &deps(&["tokio"]), &[tokio_api()]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "Rt::block_onn");
+        assert_eq!(found[0].suggestion.as_deref(), Some("Rt::block_on"));
+    }
+
+    #[test]
+    fn imported_modules_resolve_to_their_crate() {
+        let code = "use tokio::{self, task};";
+        let errors = [error(
+            "E0425",
+            "cannot find function `spawn_async` in module `task`",
+        )];
+        let found = find_hallucinated_apis(&errors, code, &deps(&["tokio"]), &[]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].crate_name, "tokio");
+        assert_eq!(found[0].path, "task::spawn_async");
+        // No index, so no suggestion
+        assert_eq!(found[0].suggestion, None);
+    }
+
+    #[test]
+    fn dashed_crate_names_and_duplicates() {
+        let errors = [
+            error(
+                "E0425",
+                "cannot find function `to_strin` in crate `serde_json`",
+            ),
+            error(
+                "E0425",
+                "cannot find function `to_strin` in crate `serde_json`",
+            ),
+        ];
+        let found = find_hallucinated_apis(&errors, "", &deps(&["serde-json"]), &[]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].crate_name, "serde_json");
+    }
+
+    #[test]
+    fn distant_names_get_no_suggestion() {
+        let errors = [error(
+            "E0425",
+            "cannot find function `frobnicate_everything` in crate `tokio`",
+        )];
+        let found = find_hallucinated_apis(&errors, "", &deps(&["tokio"]), &[tokio_api()]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].suggestion, None);
+    }
+
+    #[test]
+    fn levenshtein_distances() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("spawn", "spawn"), 0);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,421 @@
+//! Head-to-head comparison of two models within one report.
+//!
+//! Only cases both models attempted are compared. A case is *solved* by a
+//! model if any of its attempts is correct; the winner of a case is the model
+//! with the higher mean score over its attempts.
+
+use std::collections::BTreeMap;
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::report::EvalReport;
+use crate::results::EvalResult;
+use crate::scoring::{DefaultScorer, Scorer};
+use crate::significance::{mcnemar_exact, paired_permutation_test};
+
+/// Permutations for the paired metric tests.
+const RESAMPLES: u32 = 10_000;
+
+/// Which model won a case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Winner {
+    A,
+    B,
+    Tie,
+}
+
+/// Both models' results on one case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseOutcome {
+    pub case_id: String,
+    /// Mean overall score of model A's attempts.
+    pub score_a: f64,
+    /// Mean overall score of model B's attempts.
+    pub score_b: f64,
+    /// Any of model A's attempts is correct.
+    pub solved_a: bool,
+    /// Any of model B's attempts is correct.
+    pub solved_b: bool,
+    pub winner: Winner,
+}
+
+/// The paired difference in one metric, averaged over shared cases.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub a: f64,
+    pub b: f64,
+    /// `b - a`.
+    pub delta: f64,
+    /// Paired sign-flip permutation test over cases.
+    pub p_value: f64,
+}
+
+/// Head-to-head comparison of model A against model B.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadToHead {
+    pub model_a: String,
+    pub model_b: String,
+    /// Per-case outcomes, sorted by case ID.
+    pub cases: Vec<CaseOutcome>,
+    pub wins_a: usize,
+    pub wins_b: usize,
+    pub ties: usize,
+    pub both_solved: usize,
+    pub neither_solved: usize,
+    /// Cases only model A solved.
+    pub only_a_solved: Vec<String>,
+    /// Cases only model B solved.
+    pub only_b_solved: Vec<String>,
+    /// Exact McNemar test on the solved/unsolved discordant cases.
+    pub mcnemar_p: f64,
+    /// Paired metric deltas.
+    pub deltas: Vec<MetricDelta>,
+}
+
+impl EvalReport {
+    /// Compare two models of this report, scoring with its scoring profile
+    /// (or the default one).
+    pub fn head_to_head(&self, model_a: &str, model_b: &str) -> Result<HeadToHead> {
+        let scorer = DefaultScorer::from_profile(self.scoring.clone().unwrap_or_default());
+        self.head_to_head_with(model_a, model_b, &scorer)
+    }
+
+    /// Compare two models of this report, scoring with `scorer`.
+    pub fn head_to_head_with(
+        &self,
+        model_a: &str,
+        model_b: &str,
+        scorer: &dyn Scorer,
+    ) -> Result<HeadToHead> {
+        ensure!(
+            model_a != model_b,
+            "cannot compare model '{model_a}' with itself"
+        );
+        let by_case = |model: &str| -> BTreeMap<&str, Vec<&EvalResult>> {
+            let mut map: BTreeMap<&str, Vec<&EvalResult>> = BTreeMap::new();
+            for r in self.results.iter().filter(|r| r.model == model) {
+                map.entry(r.case_id.as_str()).or_default().push(r);
+            }
+            map
+        };
+        let (results_a, results_b) = (by_case(model_a), by_case(model_b));
+        ensure!(
+            !results_a.is_empty(),
+            "report has no results for '{model_a}'"
+        );
+        ensure!(
+            !results_b.is_empty(),
+            "report has no results for '{model_b}'"
+        );
+
+        let mut h2h = HeadToHead {
+            model_a: model_a.to_string(),
+            model_b: model_b.to_string(),
+            cases: vec![],
+            wins_a: 0,
+            wins_b: 0,
+            ties: 0,
+            both_solved: 0,
+            neither_solved: 0,
+            only_a_solved: vec![],
+            only_b_solved: vec![],
+            mcnemar_p: 1.0,
+            deltas: vec![],
+        };
+
+        // Per-case means of each metric, for model A and model B
+        let mut metrics: Vec<(&str, Vec<f64>, Vec<f64>)> = METRICS
+            .iter()
+            .map(|(name, _)| (*name, vec![], vec![]))
+            .collect();
+
+        for (case_id, attempts_a) in &results_a {
+            let Some(attempts_b) = results_b.get(case_id) else {
+                continue;
+            };
+            let expectations = self.case_expectations(case_id);
+            let summarize = |attempts: &[&EvalResult]| {
+                let score = mean(
+                    attempts
+                        .iter()
+                        .map(|r| self.score_result(r, scorer).overall),
+                );
+                let solved = attempts.iter().any(|r| scorer.is_correct(r, &expectations));
+                (score, solved)
+            };
+            let (score_a, solved_a) = summarize(attempts_a);
+            let (score_b, solved_b) = summarize(attempts_b);
+
+            let winner = if (score_a - score_b).abs() < 1e-9 {
+                h2h.ties += 1;
+                Winner::Tie
+            } else if score_a > score_b {
+                h2h.wins_a += 1;
+                Winner::A
+            } else {
+                h2h.wins_b += 1;
+                Winner::B
+            };
+            match (solved_a, solved_b) {
+                (true, true) => h2h.both_solved += 1,
+                (false, false) => h2h.neither_solved += 1,
+                (true, false) => h2h.only_a_solved.push(case_id.to_string()),
+                (false, true) => h2h.only_b_solved.push(case_id.to_string()),
+            }
+            h2h.cases.push(CaseOutcome {
+                case_id: case_id.to_string(),
+                score_a,
+                score_b,
+                solved_a,
+                solved_b,
+                winner,
+            });
+
+            for ((_, metric), (_, a, b)) in METRICS.iter().zip(metrics.iter_mut()) {
+                a.push(mean(attempts_a.iter().map(|r| metric(self, r, scorer))));
+                b.push(mean(attempts_b.iter().map(|r| metric(self, r, scorer))));
+            }
+        }
+        ensure!(
+            !h2h.cases.is_empty(),
+            "'{model_a}' and '{model_b}' have no cases in common"
+        );
+
+        h2h.mcnemar_p = mcnemar_exact(h2h.only_a_solved.len(), h2h.only_b_solved.len());
+        h2h.deltas = metrics
+            .into_iter()
+            .map(|(metric, a, b)| {
+                let diffs: Vec<f64> = a.iter().zip(&b).map(|(a, b)| b - a).collect();
+                let (a, b) = (mean(a.into_iter()), mean(b.into_iter()));
+                MetricDelta {
+                    metric: metric.to_string(),
+                    a,
+                    b,
+                    delta: b - a,
+                    p_value: paired_permutation_test(&diffs, RESAMPLES, 0),
+                }
+            })
+            .collect();
+        Ok(h2h)
+    }
+}
+
+type Metric = fn(&EvalReport, &EvalResult, &dyn Scorer) -> f64;
+
+/// Metrics compared per case, by name.
+const METRICS: [(&str, Metric); 7] = [
+    ("score", |report, r, scorer| {
+        report.score_result(r, scorer).overall
+    }),
+    ("compilation", |report, r, scorer| {
+        report.score_result(r, scorer).compilation
+    }),
+    ("tests", |report, r, scorer| {
+        report.score_result(r, scorer).tests
+    }),
+    ("clippy", |report, r, scorer| {
+        report.score_result(r, scorer).clippy
+    }),
+    ("tokens", |_, r, _| r.token_usage.total_tokens as f64),
+    ("cost_usd", |_, r, _| r.token_usage.estimated_cost_usd),
+    ("latency_ms", |_, r, _| r.timing.llm_request_ms as f64),
+];
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = values.fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+    sum / n.max(1) as f64
+}
+
+impl HeadToHead {
+    /// Format the comparison as markdown.
+    pub fn to_markdown(&self) -> String {
+        let (a, b) = (&self.model_a, &self.model_b);
+        let mut md = format!("## {a} vs {b}\n\n");
+
+        md.push_str(&format!(
+            "**{a}** wins {} | **{b}** wins {} | ties {} (over {} shared cases)\n\n",
+            self.wins_a,
+            self.wins_b,
+            self.ties,
+            self.cases.len()
+        ));
+
+        md.push_str("### Solved\n\n");
+        md.push_str(&format!("| | {b} solved | {b} unsolved |\n|---|---|---|\n"));
+        md.push_str(&format!(
+            "| **{a} solved** | {} | {} |\n| **{a} unsolved** | {} | {} |\n\n",
+            self.both_solved,
+            self.only_a_solved.len(),
+            self.only_b_solved.len(),
+            self.neither_solved
+        ));
+        md.push_str(&format!(
+            "McNemar exact test: p = {:.4}\n\n",
+            self.mcnemar_p
+        ));
+        for (model, cases) in [(a, &self.only_a_solved), (b, &self.only_b_solved)] {
+            if !cases.is_empty() {
+                let list: Vec<String> = cases.iter().map(|c| format!("`{c}`")).collect();
+                md.push_str(&format!("Only {model} solved: {}\n\n", list.join(", ")));
+            }
+        }
+
+        md.push_str("### Metrics\n\n");
+        md.push_str(&format!("| Metric | {a} | {b} | Delta | p |\n"));
+        md.push_str("|--------|---|---|-------|---|\n");
+        for d in &self.deltas {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {:.4} |\n",
+                d.metric,
+                format_metric(&d.metric, d.a),
+                format_metric(&d.metric, d.b),
+                format_delta(&d.metric, d.delta),
+                d.p_value
+            ));
+        }
+
+        md.push_str("\n### Cases\n\n");
+        md.push_str(&format!("| Case | {a} | {b} | Winner |\n"));
+        md.push_str("|------|---|---|--------|\n");
+        for c in &self.cases {
+            md.push_str(&format!(
+                "| {} | {:.1}%{} | {:.1}%{} | {} |\n",
+                c.case_id,
+                c.score_a * 100.0,
+                if c.solved_a { " ✓" } else { "" },
+                c.score_b * 100.0,
+                if c.solved_b { " ✓" } else { "" },
+                self.winner_name(c.winner)
+            ));
+        }
+
+        md
+    }
+
+    /// Display name of a case winner.
+    pub fn winner_name(&self, winner: Winner) -> &str {
+        match winner {
+            Winner::A => &self.model_a,
+            Winner::B => &self.model_b,
+            Winner::Tie => "tie",
+        }
+    }
+}
+
+/// Format a metric value: percentages for scores, plain numbers otherwise.
+pub fn format_metric(metric: &str, value: f64) -> String {
+    match metric {
+        "tokens" => format!("{value:.0}"),
+        "cost_usd" => format!("${value:.4}"),
+        "latency_ms" => format!("{value:.0}ms"),
+        _ => format!("{:.1}%", value * 100.0),
+    }
+}
+
+/// Format a metric delta with its sign.
+pub fn format_delta(metric: &str, delta: f64) -> String {
+    match metric {
+        "tokens" => format!("{delta:+.0}"),
+        "cost_usd" => format!("{}${:.4}", if delta < 0.0 { "-" } else { "+" }, delta.abs()),
+        "latency_ms" => format!("{delta:+.0}ms"),
+        _ => format!("{:+.1}%", delta * 100.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::EvalSetSummary;
+    use crate::results::*;
+    use crate::statistics::AggregateStats;
+    use std::collections::HashMap;
+
+    fn result(case_id: &str, model: &str, passed: bool, tokens: u32) -> EvalResult {
+        let mut result = EvalResult::stub(case_id, model);
+        result.test_execution = Some(TestResult {
+            passed: u32::from(passed),
+            failed: u32::from(!passed),
+            ignored: 0,
+            duration_ms: 0,
+            failures: vec![],
+        });
+        result.timing.llm_request_ms = 100;
+        result.timing.total_ms = 100;
+        result.token_usage.completion_tokens = tokens;
+        result.token_usage.total_tokens = tokens;
+        result
+    }
+
+    fn report(results: Vec<EvalResult>) -> EvalReport {
+        EvalReport {
+            id: uuid::Uuid::nil(),
+            created_at: chrono::Utc::now(),
+            eval_set: EvalSetSummary {
+                id: "test".into(),
+                name: "Test".into(),
+                case_count: 0,
+            },
+            models_evaluated: vec!["a".into(), "b".into()],
+            results,
+            aggregate: AggregateStats {
+                per_model: HashMap::new(),
+                per_case: HashMap::new(),
+                per_tag: HashMap::new(),
+                per_difficulty: HashMap::new(),
+            },
+            scoring: None,
+            cases: Default::default(),
+            rescored_from: None,
+            budget: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn head_to_head_counts_wins_and_discordant_cases() {
+        let mut results = vec![];
+        // b solves 9 cases a doesn't; a solves one b doesn't; both solve one
+        for i in 0..9 {
+            results.push(result(&format!("c{i}"), "a", false, 100));
+            results.push(result(&format!("c{i}"), "b", true, 50));
+        }
+        results.push(result("c9", "a", true, 100));
+        results.push(result("c9", "b", false, 50));
+        results.push(result("c10", "a", true, 100));
+        results.push(result("c10", "b", true, 50));
+        // Only a attempted this one
+        results.push(result("solo", "a", true, 100));
+
+        let h2h = report(results).head_to_head("a", "b").unwrap();
+        assert_eq!(h2h.cases.len(), 11);
+        assert_eq!((h2h.wins_a, h2h.wins_b, h2h.ties), (1, 9, 1));
+        assert_eq!(h2h.only_a_solved, vec!["c9"]);
+        assert_eq!(h2h.only_b_solved.len(), 9);
+        assert_eq!((h2h.both_solved, h2h.neither_solved), (1, 0));
+        assert!((h2h.mcnemar_p - 22.0 / 1024.0).abs() < 1e-12);
+
+        let tokens = h2h.deltas.iter().find(|d| d.metric == "tokens").unwrap();
+        assert_eq!((tokens.a, tokens.b, tokens.delta), (100.0, 50.0, -50.0));
+        assert!(tokens.p_value < 0.01);
+        let score = h2h.deltas.iter().find(|d| d.metric == "score").unwrap();
+        assert!(score.delta > 0.0);
+
+        let md = h2h.to_markdown();
+        assert!(md.contains("**a** wins 1 | **b** wins 9 | ties 1 (over 11 shared cases)"));
+        assert!(md.contains("| **a solved** | 1 | 1 |"));
+        assert!(md.contains("Only a solved: `c9`"));
+        assert!(md.contains("| tokens | 100 | 50 | -50 |"));
+    }
+
+    #[test]
+    fn head_to_head_rejects_unknown_models() {
+        let r = report(vec![result("c", "a", true, 1), result("d", "b", true, 1)]);
+        assert!(r.head_to_head("a", "missing").is_err());
+        assert!(r.head_to_head("a", "a").is_err());
+        let err = r.head_to_head("a", "b").unwrap_err();
+        assert!(err.to_string().contains("no cases in common"));
+    }
+}
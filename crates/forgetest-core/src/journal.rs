@@ -0,0 +1,381 @@
+//! Append-only results journal.
+//!
+//! The engine appends each result to the journal as soon as it is scored, one
+//! JSON object per line, so a run that dies part-way keeps everything it
+//! finished. Resuming loads the journal and only runs the (case, model,
+//! attempt) triples it doesn't have yet.
+//!
+//! The first line is a [`JournalHeader`] with the run's settings and case
+//! definitions, so a resume can refuse different settings and drop results
+//! for cases that changed since.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{EvalEngineConfig, ModelSpec};
+use crate::model::EvalSet;
+use crate::report::CaseSnapshot;
+use crate::results::EvalResult;
+
+/// The run options results depend on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSettings {
+    /// Models as `provider/model`.
+    pub models: Vec<String>,
+    /// Pass@k values, sorted.
+    pub pass_k: Vec<u32>,
+    /// Sampling temperature.
+    pub temperature: f64,
+    /// Generation token limit for cases that don't set one.
+    pub max_tokens: u32,
+    /// System prompt override.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+}
+
+impl RunSettings {
+    /// The settings of a run of `models` with `config`.
+    pub fn new(config: &EvalEngineConfig, models: &[ModelSpec]) -> Self {
+        let mut pass_k = config.pass_k.clone();
+        pass_k.sort_unstable();
+        pass_k.dedup();
+        Self {
+            models: models
+                .iter()
+                .map(|m| format!("{}/{}", m.provider, m.model))
+                .collect(),
+            pass_k,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            system_prompt: config.system_prompt_override.clone(),
+        }
+    }
+
+    /// How `self` differs from `other`, one description per setting.
+    pub fn differences(&self, other: &RunSettings) -> Vec<String> {
+        let mut differences = Vec::new();
+        let mut check = |name: &str, before: String, after: String| {
+            if before != after {
+                differences.push(format!("{name}: {before} -> {after}"));
+            }
+        };
+        check("models", other.models.join(","), self.models.join(","));
+        check(
+            "pass@k",
+            format!("{:?}", other.pass_k),
+            format!("{:?}", self.pass_k),
+        );
+        check(
+            "temperature",
+            other.temperature.to_string(),
+            self.temperature.to_string(),
+        );
+        check(
+            "max tokens",
+            other.max_tokens.to_string(),
+            self.max_tokens.to_string(),
+        );
+        check(
+            "system prompt",
+            format!("{:?}", other.system_prompt),
+            format!("{:?}", self.system_prompt),
+        );
+        differences
+    }
+}
+
+/// First line of a journal: what its results were produced with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalHeader {
+    /// The run's settings.
+    pub settings: RunSettings,
+    /// Case definitions by eval set ID, then case ID.
+    pub cases: BTreeMap<String, BTreeMap<String, CaseSnapshot>>,
+}
+
+impl JournalHeader {
+    /// The header of a run of `eval_sets` with `settings`.
+    pub fn new(settings: RunSettings, eval_sets: &[EvalSet]) -> Self {
+        let cases = eval_sets
+            .iter()
+            .map(|set| {
+                let snapshots = set
+                    .cases
+                    .iter()
+                    .map(|case| (case.id.clone(), CaseSnapshot::from_case(case)))
+                    .collect();
+                (set.id.clone(), snapshots)
+            })
+            .collect();
+        Self { settings, cases }
+    }
+
+    /// Whether the journal recorded a different definition of a case than
+    /// `current` has. Cases it didn't record haven't changed.
+    pub fn case_changed(&self, eval_set: &str, case_id: &str, current: &CaseSnapshot) -> bool {
+        self.cases
+            .get(eval_set)
+            .and_then(|cases| cases.get(case_id))
+            .is_some_and(|before| !before.same_definition(current))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HeaderLine {
+    header: JournalHeader,
+}
+
+/// What a journal holds.
+#[derive(Debug, Clone, Default)]
+pub struct JournalContents {
+    /// The run's header (`None` for journals written before headers).
+    pub header: Option<JournalHeader>,
+    /// The journaled results, in the order they finished.
+    pub entries: Vec<JournalEntry>,
+}
+
+/// One journal line: a result and the eval set it belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// ID of the eval set the case is from.
+    pub eval_set: String,
+    /// The scored result.
+    pub result: EvalResult,
+}
+
+/// An open journal that results are appended to.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Journal {
+    /// Start a journal at `path` with `header`, replacing any existing one.
+    pub fn create(path: &Path, header: &JournalHeader) -> Result<Self> {
+        Self::write(path, header, &[])?;
+        Self::open(path)
+    }
+
+    /// Atomically replace the journal at `path` with `header` and `entries`.
+    pub fn write(path: &Path, header: &JournalHeader, entries: &[JournalEntry]) -> Result<()> {
+        let mut contents = serde_json::to_string(&HeaderLine {
+            header: header.clone(),
+        })?;
+        contents.push('\n');
+        for entry in entries {
+            contents.push_str(&serde_json::to_string(entry)?);
+            contents.push('\n');
+        }
+        let tmp = path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, contents)
+            .with_context(|| format!("failed to write journal {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("failed to write journal {}", path.display()))
+    }
+
+    /// Open `path` for appending, creating it if needed. A torn last line
+    /// left by a crash is cut off, so new entries start on a line of their own.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open journal {}", path.display()))?;
+        let contents = std::fs::read(path)?;
+        if !contents.is_empty() && !contents.ends_with(b"\n") {
+            let keep = contents
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            file.set_len(keep as u64)
+                .with_context(|| format!("failed to repair journal {}", path.display()))?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a result and flush it to disk.
+    pub fn append(&self, eval_set: &str, result: &EvalResult) -> Result<()> {
+        let entry = JournalEntry {
+            eval_set: eval_set.to_string(),
+            result: result.clone(),
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        // One write per line, so a crash can only tear the last one
+        file.write_all(line.as_bytes())
+            .and_then(|()| file.sync_data())
+            .with_context(|| format!("failed to append to journal {}", self.path.display()))
+    }
+
+    /// Load the header and entries of a journal.
+    ///
+    /// A line that doesn't parse is skipped with a warning if it is the last
+    /// one (a write the crash interrupted) and an error anywhere else.
+    pub fn load(path: &Path) -> Result<JournalContents> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read journal {}", path.display()))?;
+        // Keep each line's number for error messages
+        let mut lines: Vec<(usize, &str)> = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .collect();
+        let header = lines
+            .first()
+            .and_then(|(_, line)| serde_json::from_str::<HeaderLine>(line).ok())
+            .map(|line| line.header);
+        if header.is_some() {
+            lines.remove(0);
+        }
+        let mut entries = Vec::with_capacity(lines.len());
+        for (i, &(number, line)) in lines.iter().enumerate() {
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) if i + 1 == lines.len() => {
+                    tracing::warn!("skipping truncated last line of {}: {e}", path.display());
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!(
+                            "invalid journal entry on line {} of {}",
+                            number + 1,
+                            path.display()
+                        )
+                    })
+                }
+            }
+        }
+        Ok(JournalContents { header, entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_result(case_id: &str, attempt: u32) -> EvalResult {
+        EvalResult {
+            provider: "p".into(),
+            generated_code: "fn f() {}".into(),
+            attempt,
+            ..EvalResult::stub(case_id, "m")
+        }
+    }
+
+    #[test]
+    fn appended_results_load_back_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let journal = Journal::open(&path).unwrap();
+        journal.append("set", &make_result("a", 1)).unwrap();
+        journal.append("set", &make_result("a", 2)).unwrap();
+        drop(journal);
+        // Reopening appends rather than truncating
+        Journal::open(&path)
+            .unwrap()
+            .append("other", &make_result("b", 1))
+            .unwrap();
+
+        let entries = Journal::load(&path).unwrap().entries;
+        let keys: Vec<_> = entries
+            .iter()
+            .map(|e| {
+                (
+                    e.eval_set.as_str(),
+                    e.result.case_id.as_str(),
+                    e.result.attempt,
+                )
+            })
+            .collect();
+        assert_eq!(keys, [("set", "a", 1), ("set", "a", 2), ("other", "b", 1)]);
+    }
+
+    #[test]
+    fn torn_last_line_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        Journal::open(&path)
+            .unwrap()
+            .append("set", &make_result("a", 1))
+            .unwrap();
+        let mut contents = std::fs::read_to_string(&path).unwrap();
+        contents.push_str("{\"eval_set\":\"set\",\"res");
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(Journal::load(&path).unwrap().entries.len(), 1);
+
+        // Resuming cuts the torn line off before appending
+        Journal::open(&path)
+            .unwrap()
+            .append("set", &make_result("a", 2))
+            .unwrap();
+        assert_eq!(Journal::load(&path).unwrap().entries.len(), 2);
+
+        // Corruption before the last line is an error
+        contents.push_str("\n{\"eval_set\":\"set\"}\n");
+        std::fs::write(&path, format!("\n{contents}")).unwrap();
+        let err = Journal::load(&path).unwrap_err();
+        // Line numbers count blank lines
+        assert!(err.to_string().contains("line 3 of"), "{err}");
+    }
+
+    #[test]
+    fn header_records_settings_and_cases() {
+        let set = crate::parser::parse_eval_set_str(
+            "[eval_set]\nid = \"set\"\nname = \"s\"\n[[cases]]\nid = \"a\"\nname = \"a\"\nprompt = \"p\"\n",
+            Path::new("s.toml"),
+        )
+        .unwrap();
+        let models = [ModelSpec {
+            provider: "p".into(),
+            model: "m".into(),
+        }];
+        let config = EvalEngineConfig {
+            pass_k: vec![5, 1],
+            ..EvalEngineConfig::default()
+        };
+        let settings = RunSettings::new(&config, &models);
+        assert_eq!(settings.models, ["p/m"]);
+        assert_eq!(settings.pass_k, [1, 5]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let header = JournalHeader::new(settings.clone(), std::slice::from_ref(&set));
+        Journal::create(&path, &header)
+            .unwrap()
+            .append("set", &make_result("a", 1))
+            .unwrap();
+
+        let loaded = Journal::load(&path).unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        let loaded = loaded.header.unwrap();
+        assert_eq!(loaded.settings, settings);
+
+        let hotter = RunSettings {
+            temperature: 0.8,
+            ..settings.clone()
+        };
+        assert_eq!(hotter.differences(&settings), ["temperature: 0 -> 0.8"]);
+
+        let mut edited = set.cases[0].clone();
+        assert!(!loaded.case_changed("set", "a", &CaseSnapshot::from_case(&edited)));
+        edited.expectations.test_file = Some("#[test] fn t() {}".into());
+        assert!(loaded.case_changed("set", "a", &CaseSnapshot::from_case(&edited)));
+        assert!(!loaded.case_changed("set", "new", &CaseSnapshot::from_case(&edited)));
+    }
+}
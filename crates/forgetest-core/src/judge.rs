@@ -0,0 +1,369 @@
+//! LLM-as-judge grading.
+//!
+//! A [`Judge`] asks any [`LlmProvider`] to grade generated code against a
+//! case's [`JudgeRubric`], for qualities that tests can't check. The judge
+//! answers with a JSON verdict (a 0-10 score per criterion plus a rationale).
+//! Verdicts are cached by a hash of the code, task and rubric, so identical
+//! samples are only graded once.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::budget::Budget;
+use crate::model::{JudgeRubric, Language};
+use crate::results::{JudgeResult, TokenUsage};
+use crate::traits::{GenerateRequest, LlmProvider};
+
+pub(crate) const SYSTEM_PROMPT: &str = "You are a meticulous senior code reviewer. \
+Grade code strictly against the given criteria and answer with JSON only.";
+
+/// Token limit of a judge answer.
+pub const JUDGE_MAX_TOKENS: u32 = 1024;
+
+/// Grades generated code with an LLM.
+pub struct Judge {
+    provider: Arc<dyn LlmProvider>,
+    model: String,
+    max_tokens: u32,
+    cache: Mutex<HashMap<String, JudgeResult>>,
+}
+
+impl Judge {
+    /// A judge that uses `model` on `provider`.
+    pub fn new(provider: Arc<dyn LlmProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            max_tokens: JUDGE_MAX_TOKENS,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The judge model.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Grade `code` written for `task` against `rubric`, charging the request
+    /// to `budget` if there is one.
+    ///
+    /// Cached verdicts are returned with `cached` set and zero token usage.
+    /// Fails without calling the provider if the budget is exhausted.
+    pub async fn grade(
+        &self,
+        task: &str,
+        code: &str,
+        language: Language,
+        rubric: &JudgeRubric,
+        budget: Option<&Budget>,
+    ) -> Result<JudgeResult> {
+        anyhow::ensure!(!rubric.criteria.is_empty(), "judge rubric has no criteria");
+
+        let key = cache_key(&self.model, task, code, rubric);
+        if let Some(hit) = self
+            .cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            return Ok(JudgeResult {
+                cached: true,
+                token_usage: TokenUsage {
+                    prompt_tokens: 0,
+                    completion_tokens: 0,
+                    total_tokens: 0,
+                    estimated_cost_usd: 0.0,
+                },
+                ..hit.clone()
+            });
+        }
+
+        let request = GenerateRequest {
+            model: self.model.clone(),
+            prompt: build_prompt(task, code, language, rubric),
+            system_prompt: Some(SYSTEM_PROMPT.to_string()),
+            context_files: vec![],
+            max_tokens: self.max_tokens,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        };
+        if let Some(budget) = budget {
+            budget.try_dispatch_judge()?;
+        }
+        let response = self
+            .provider
+            .generate(&request)
+            .await
+            .with_context(|| format!("judge request to {} failed", self.model))?;
+        if let Some(budget) = budget {
+            budget.record_judge(&response.token_usage);
+        }
+        let verdict = parse_verdict(&response.content, rubric)?;
+
+        let result = JudgeResult {
+            model: self.model.clone(),
+            score: weighted_score(&verdict.criteria, rubric),
+            criteria: verdict.criteria,
+            rationale: verdict.rationale,
+            token_usage: response.token_usage,
+            cached: false,
+        };
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, result.clone());
+        Ok(result)
+    }
+}
+
+/// A parsed judge answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    /// Score per criterion, normalized to 0.0-1.0.
+    pub criteria: BTreeMap<String, f64>,
+    pub rationale: String,
+}
+
+#[derive(Deserialize)]
+struct RawVerdict {
+    scores: BTreeMap<String, f64>,
+    #[serde(default)]
+    rationale: String,
+}
+
+/// Build the grading prompt for a piece of code.
+pub fn build_prompt(task: &str, code: &str, language: Language, rubric: &JudgeRubric) -> String {
+    let mut prompt = String::from(
+        "Review the code below, written for the given task. Grade it against each \
+         criterion on a scale from 0 (fails the criterion completely) to 10 (exemplary). \
+         Correctness is checked separately by tests; grade only the listed criteria.\n\n",
+    );
+    if !task.trim().is_empty() {
+        let _ = write!(prompt, "## Task\n\n{}\n\n", task.trim());
+    }
+    let _ = write!(
+        prompt,
+        "## Code\n\n```{language}\n{}\n```\n\n",
+        code.trim_end()
+    );
+
+    prompt.push_str("## Criteria\n\n");
+    for criterion in &rubric.criteria {
+        let _ = writeln!(prompt, "- `{}`: {}", criterion.name, criterion.description);
+    }
+    if let Some(instructions) = &rubric.instructions {
+        let _ = write!(prompt, "\n{}\n", instructions.trim());
+    }
+
+    let example = rubric
+        .criteria
+        .iter()
+        .map(|c| format!("\"{}\": <0-10>", c.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let _ = write!(
+        prompt,
+        "\nRespond with only a JSON object of the form:\n\
+         {{\"scores\": {{{example}}}, \"rationale\": \"<a short paragraph explaining the grades>\"}}\n"
+    );
+    prompt
+}
+
+/// Parse a judge answer, tolerating surrounding prose or code fences.
+///
+/// Every rubric criterion must be scored; scores are clamped to 0-10 and
+/// normalized to 0.0-1.0.
+pub fn parse_verdict(content: &str, rubric: &JudgeRubric) -> Result<Verdict> {
+    let start = content
+        .find('{')
+        .context("judge response contains no JSON object")?;
+    let end = content
+        .rfind('}')
+        .filter(|&end| end > start)
+        .context("judge response contains no JSON object")?;
+    let raw: RawVerdict = serde_json::from_str(&content[start..=end])
+        .context("judge response is not a valid verdict")?;
+
+    let mut criteria = BTreeMap::new();
+    for criterion in &rubric.criteria {
+        let score = raw
+            .scores
+            .get(&criterion.name)
+            .with_context(|| format!("judge verdict has no score for '{}'", criterion.name))?;
+        criteria.insert(criterion.name.clone(), score.clamp(0.0, 10.0) / 10.0);
+    }
+
+    Ok(Verdict {
+        criteria,
+        rationale: raw.rationale,
+    })
+}
+
+/// Weighted mean of the criterion scores (plain mean if all weights are 0).
+pub fn weighted_score(scores: &BTreeMap<String, f64>, rubric: &JudgeRubric) -> f64 {
+    let weighted: Vec<(f64, f64)> = rubric
+        .criteria
+        .iter()
+        .filter_map(|c| scores.get(&c.name).map(|&s| (s, c.weight.max(0.0))))
+        .collect();
+    if weighted.is_empty() {
+        return 0.0;
+    }
+    let total_weight: f64 = weighted.iter().map(|(_, w)| w).sum();
+    if total_weight == 0.0 {
+        return weighted.iter().map(|(s, _)| s).sum::<f64>() / weighted.len() as f64;
+    }
+    weighted.iter().map(|(s, w)| s * w).sum::<f64>() / total_weight
+}
+
+/// Hex SHA-256 over everything that can change a verdict.
+fn cache_key(model: &str, task: &str, code: &str, rubric: &JudgeRubric) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        model,
+        task,
+        code,
+        &serde_json::to_string(rubric).unwrap_or_default(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::JudgeCriterion;
+    use crate::traits::{GenerateResponse, ModelInfo};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn rubric() -> JudgeRubric {
+        JudgeRubric {
+            criteria: vec![
+                JudgeCriterion {
+                    name: "errors".into(),
+                    description: "Errors use Result".into(),
+                    weight: 3.0,
+                },
+                JudgeCriterion {
+                    name: "naming".into(),
+                    description: "Names are descriptive".into(),
+                    weight: 1.0,
+                },
+            ],
+            instructions: Some("Be strict.".into()),
+            weight: 0.0,
+        }
+    }
+
+    struct CannedJudge {
+        answer: String,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for CannedJudge {
+        fn name(&self) -> &str {
+            "canned"
+        }
+
+        async fn generate(&self, request: &GenerateRequest) -> Result<GenerateResponse> {
+            assert!(request.prompt.contains("`errors`: Errors use Result"));
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(GenerateResponse {
+                content: self.answer.clone(),
+                extracted_code: String::new(),
+                model: request.model.clone(),
+                token_usage: TokenUsage {
+                    prompt_tokens: 300,
+                    completion_tokens: 40,
+                    total_tokens: 340,
+                    estimated_cost_usd: 0.002,
+                },
+                latency_ms: 5,
+            })
+        }
+
+        fn available_models(&self) -> Vec<ModelInfo> {
+            vec![]
+        }
+    }
+
+    #[test]
+    fn prompt_lists_criteria_and_format() {
+        let prompt = build_prompt("Parse a config", "fn parse() {}", Language::Rust, &rubric());
+        assert!(prompt.contains("## Task\n\nParse a config"));
+        assert!(prompt.contains("```rust\nfn parse() {}\n```"));
+        assert!(prompt.contains("- `naming`: Names are descriptive"));
+        assert!(prompt.contains("Be strict."));
+        assert!(prompt.contains(r#"{"scores": {"errors": <0-10>, "naming": <0-10>}"#));
+    }
+
+    #[test]
+    fn parse_verdict_with_fences_and_prose() {
+        let content = "Here is my review:\n```json\n{\"scores\": {\"errors\": 8, \"naming\": 14, \"extra\": 1}, \"rationale\": \"Good.\"}\n```";
+        let verdict = parse_verdict(content, &rubric()).unwrap();
+        assert_eq!(verdict.criteria["errors"], 0.8);
+        // Out-of-range scores are clamped; unknown criteria are dropped
+        assert_eq!(verdict.criteria["naming"], 1.0);
+        assert_eq!(verdict.criteria.len(), 2);
+        assert_eq!(verdict.rationale, "Good.");
+    }
+
+    #[test]
+    fn parse_verdict_requires_every_criterion() {
+        let err = parse_verdict(r#"{"scores": {"errors": 5}}"#, &rubric()).unwrap_err();
+        assert!(err.to_string().contains("naming"));
+        assert!(parse_verdict("no json here", &rubric()).is_err());
+    }
+
+    #[test]
+    fn weighted_score_uses_criterion_weights() {
+        let scores = BTreeMap::from([("errors".to_string(), 1.0), ("naming".to_string(), 0.0)]);
+        assert!((weighted_score(&scores, &rubric()) - 0.75).abs() < 1e-9);
+
+        let mut unweighted = rubric();
+        unweighted.criteria.iter_mut().for_each(|c| c.weight = 0.0);
+        assert!((weighted_score(&scores, &unweighted) - 0.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn grade_caches_by_code_hash() {
+        let provider = Arc::new(CannedJudge {
+            answer: r#"{"scores": {"errors": 6, "naming": 10}, "rationale": "ok"}"#.into(),
+            calls: AtomicUsize::new(0),
+        });
+        let judge = Judge::new(provider.clone(), "judge-model");
+
+        let first = judge
+            .grade("task", "fn a() {}", Language::Rust, &rubric(), None)
+            .await
+            .unwrap();
+        assert!(!first.cached);
+        assert_eq!(first.model, "judge-model");
+        assert!((first.score - 0.7).abs() < 1e-9);
+        assert_eq!(first.token_usage.total_tokens, 340);
+
+        let second = judge
+            .grade("task", "fn a() {}", Language::Rust, &rubric(), None)
+            .await
+            .unwrap();
+        assert!(second.cached);
+        assert_eq!(second.token_usage.total_tokens, 0);
+        assert_eq!(second.criteria, first.criteria);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        judge
+            .grade("task", "fn b() {}", Language::Rust, &rubric(), None)
+            .await
+            .unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,482 @@
+//! Bradley-Terry leaderboard across many reports.
+//!
+//! Every case two models both attempted is one game between them: the model
+//! with the higher mean score wins, equal scores are a tie (half a win each).
+//! Cases are matched across reports by eval set, case ID and case snapshot, so
+//! a model run last month and a model run today meet on the same cases as
+//! long as those cases haven't changed.
+//!
+//! Ratings are fitted with Hunter's MM algorithm and shown on the Elo scale
+//! (400 points per tenfold odds). Each model also plays one virtual tie
+//! against a fixed 1000-rated anchor, which keeps undefeated and winless
+//! models finite and pulls models with few games towards 1000. Intervals come
+//! from a seeded bootstrap over cases.
+//!
+//! Models that never shared a case with each other have no games to compare
+//! them by, so the comparison graph can split into groups. Ranks are only
+//! given within a group; models with no games at all are listed as unrated.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::confidence::{ConfidenceInterval, SplitMix64};
+use crate::report::EvalReport;
+use crate::scoring::DefaultScorer;
+
+/// Virtual games each model plays against the 1000-rated anchor.
+const PRIOR_GAMES: f64 = 1.0;
+
+/// Scores closer than this are a tie.
+const TIE_EPSILON: f64 = 1e-9;
+
+/// Settings for fitting a leaderboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardConfig {
+    /// Confidence level of the rating intervals, e.g. 0.95.
+    pub level: f64,
+    /// Number of bootstrap resamples over cases.
+    pub resamples: u32,
+    /// Seed for bootstrap resampling.
+    pub seed: u64,
+}
+
+impl Default for LeaderboardConfig {
+    fn default() -> Self {
+        Self {
+            level: 0.95,
+            resamples: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// One model's rating.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// Rank within the model's group, starting at 1.
+    pub rank: usize,
+    pub model: String,
+    /// Group of models connected by shared cases (0 is the largest).
+    pub group: usize,
+    /// Bradley-Terry rating on the Elo scale.
+    pub rating: f64,
+    /// Bootstrap interval of the rating.
+    pub interval: ConfidenceInterval,
+    /// Cases the model shared with at least one other model.
+    pub cases: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+}
+
+/// Ranked Bradley-Terry ratings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leaderboard {
+    /// Rated models, by group and then by rating.
+    pub entries: Vec<LeaderboardEntry>,
+    /// Models that never shared a case with another model.
+    pub unrated: Vec<String>,
+    /// Number of groups of rated models.
+    pub groups: usize,
+    /// Reports the leaderboard was built from.
+    pub reports: usize,
+    /// Distinct cases with at least one game.
+    pub cases: usize,
+    /// Confidence level of the intervals.
+    pub level: f64,
+}
+
+/// One game: `(model i, model j, i's result)`, where the result is 1.0 for a
+/// win, 0.5 for a tie and 0.0 for a loss.
+type Game = (usize, usize, f64);
+
+impl Leaderboard {
+    /// Fit a leaderboard from `reports`, scoring each report with its own
+    /// scoring profile (or the default one).
+    pub fn from_reports(reports: &[EvalReport], config: &LeaderboardConfig) -> Result<Self> {
+        ensure!(
+            config.level > 0.0 && config.level < 1.0,
+            "confidence level must be between 0 and 1"
+        );
+
+        // Mean score of each model on each distinct case
+        let mut scores: BTreeMap<String, BTreeMap<String, (f64, usize)>> = BTreeMap::new();
+        let mut models: BTreeSet<String> = BTreeSet::new();
+        for report in reports {
+            let scorer = DefaultScorer::from_profile(report.scoring.clone().unwrap_or_default());
+            for r in &report.results {
+                let definition = report
+                    .cases
+                    .get(&r.case_id)
+                    .and_then(|case| serde_json::to_value(case).ok())
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+                let key = format!("{}\0{}\0{}", report.eval_set.id, r.case_id, definition);
+                let entry = scores
+                    .entry(key)
+                    .or_default()
+                    .entry(r.model.clone())
+                    .or_default();
+                entry.0 += report.score_result(r, &scorer).overall;
+                entry.1 += 1;
+                models.insert(r.model.clone());
+            }
+        }
+        let models: Vec<String> = models.into_iter().collect();
+        let index: BTreeMap<&str, usize> = models
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m.as_str(), i))
+            .collect();
+
+        // Games per case, for cases with at least two models
+        let cases: Vec<Vec<Game>> = scores
+            .values()
+            .filter(|per_model| per_model.len() > 1)
+            .map(|per_model| {
+                let means: Vec<(usize, f64)> = per_model
+                    .iter()
+                    .map(|(m, (sum, n))| (index[m.as_str()], sum / *n as f64))
+                    .collect();
+                let mut games = vec![];
+                for (k, &(i, a)) in means.iter().enumerate() {
+                    for &(j, b) in &means[k + 1..] {
+                        let result = if (a - b).abs() < TIE_EPSILON {
+                            0.5
+                        } else if a > b {
+                            1.0
+                        } else {
+                            0.0
+                        };
+                        games.push((i, j, result));
+                    }
+                }
+                games
+            })
+            .collect();
+
+        let n = models.len();
+        let mut record = vec![(0usize, 0usize, 0usize, 0usize); n];
+        let mut links = UnionFind::new(n);
+        for games in &cases {
+            let mut played = BTreeSet::new();
+            for &(i, j, result) in games {
+                links.union(i, j);
+                played.extend([i, j]);
+                if result == 0.5 {
+                    record[i].3 += 1;
+                    record[j].3 += 1;
+                } else if result == 1.0 {
+                    record[i].1 += 1;
+                    record[j].2 += 1;
+                } else {
+                    record[i].2 += 1;
+                    record[j].1 += 1;
+                }
+            }
+            for i in played {
+                record[i].0 += 1;
+            }
+        }
+
+        let ratings = fit(n, cases.iter().flatten());
+
+        // Bootstrap over cases
+        let mut samples: Vec<Vec<f64>> = vec![Vec::with_capacity(config.resamples as usize); n];
+        if !cases.is_empty() {
+            let mut rng = SplitMix64::new(config.seed);
+            for _ in 0..config.resamples.max(1) {
+                let resampled = (0..cases.len()).flat_map(|_| &cases[rng.below(cases.len())]);
+                for (i, rating) in fit(n, resampled).into_iter().enumerate() {
+                    samples[i].push(rating);
+                }
+            }
+        }
+
+        // Group rated models by connected component, largest first
+        let mut components: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        let mut unrated = vec![];
+        for i in 0..n {
+            if record[i].0 == 0 {
+                unrated.push(models[i].clone());
+            } else {
+                components.entry(links.find(i)).or_default().push(i);
+            }
+        }
+        let mut components: Vec<Vec<usize>> = components.into_values().collect();
+        components.sort_by_key(|members| std::cmp::Reverse(members.len()));
+
+        let alpha = (1.0 - config.level) / 2.0;
+        let mut entries = vec![];
+        for (group, mut members) in components.into_iter().enumerate() {
+            members.sort_by(|&a, &b| ratings[b].total_cmp(&ratings[a]));
+            for (rank, i) in members.into_iter().enumerate() {
+                let sample = &mut samples[i];
+                sample.sort_by(f64::total_cmp);
+                let at = |q: f64| {
+                    sample[((q * (sample.len() - 1) as f64).round() as usize).min(sample.len() - 1)]
+                };
+                let (wins, losses, ties) = (record[i].1, record[i].2, record[i].3);
+                entries.push(LeaderboardEntry {
+                    rank: rank + 1,
+                    model: models[i].clone(),
+                    group,
+                    rating: ratings[i],
+                    interval: ConfidenceInterval {
+                        lower: at(alpha),
+                        upper: at(1.0 - alpha),
+                    },
+                    cases: record[i].0,
+                    wins,
+                    losses,
+                    ties,
+                });
+            }
+        }
+
+        Ok(Self {
+            groups: entries.last().map_or(0, |e| e.group + 1),
+            entries,
+            unrated,
+            reports: reports.len(),
+            cases: cases.len(),
+            level: config.level,
+        })
+    }
+
+    /// Format the leaderboard as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("## Leaderboard\n\n");
+        md.push_str(&format!(
+            "{} models over {} shared cases from {} reports. Ratings are Bradley-Terry on the Elo scale with {:.0}% bootstrap intervals.\n\n",
+            self.entries.len(),
+            self.cases,
+            self.reports,
+            self.level * 100.0
+        ));
+        if self.groups > 1 {
+            md.push_str(
+                "Some models never ran on the same cases, so they are ranked in separate groups. Ratings are not comparable across groups.\n\n",
+            );
+        }
+
+        for group in 0..self.groups {
+            if self.groups > 1 {
+                md.push_str(&format!("### Group {}\n\n", group + 1));
+            }
+            md.push_str("| Rank | Model | Rating | Interval | Cases | W-L-T |\n");
+            md.push_str("|------|-------|--------|----------|-------|-------|\n");
+            for e in self.entries.iter().filter(|e| e.group == group) {
+                md.push_str(&format!(
+                    "| {} | {} | {:.0} | {:.0}-{:.0} | {} | {}-{}-{} |\n",
+                    e.rank,
+                    e.model,
+                    e.rating,
+                    e.interval.lower,
+                    e.interval.upper,
+                    e.cases,
+                    e.wins,
+                    e.losses,
+                    e.ties
+                ));
+            }
+            md.push('\n');
+        }
+
+        if !self.unrated.is_empty() {
+            md.push_str(&format!(
+                "Unrated (no cases shared with another model): {}\n",
+                self.unrated.join(", ")
+            ));
+        }
+
+        md
+    }
+}
+
+/// Fit Bradley-Terry ratings (Elo scale) for `n` models from `games`.
+fn fit<'a>(n: usize, games: impl Iterator<Item = &'a Game>) -> Vec<f64> {
+    // Points scored and games played for each pair
+    let mut points = vec![vec![0.0; n]; n];
+    let mut played = vec![vec![0.0; n]; n];
+    for &(i, j, result) in games {
+        points[i][j] += result;
+        points[j][i] += 1.0 - result;
+        played[i][j] += 1.0;
+        played[j][i] += 1.0;
+    }
+    let wins: Vec<f64> = points
+        .iter()
+        .map(|row| row.iter().sum::<f64>() + PRIOR_GAMES / 2.0)
+        .collect();
+
+    // MM iterations; the anchor has strength 1
+    let mut strength = vec![1.0; n];
+    for _ in 0..10_000 {
+        let mut change: f64 = 0.0;
+        for i in 0..n {
+            let denom = PRIOR_GAMES / (strength[i] + 1.0)
+                + (0..n)
+                    .filter(|&j| played[i][j] > 0.0)
+                    .map(|j| played[i][j] / (strength[i] + strength[j]))
+                    .sum::<f64>();
+            let next = wins[i] / denom;
+            change = change.max((next / strength[i]).ln().abs());
+            strength[i] = next;
+        }
+        if change < 1e-10 {
+            break;
+        }
+    }
+
+    strength
+        .into_iter()
+        .map(|s| 1000.0 + 400.0 * s.log10())
+        .collect()
+}
+
+/// Disjoint sets of models linked by shared cases.
+struct UnionFind(Vec<usize>);
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self((0..n).collect())
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        if self.0[i] != i {
+            let root = self.find(self.0[i]);
+            self.0[i] = root;
+        }
+        self.0[i]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.0[a] = b;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::EvalSetSummary;
+    use crate::results::*;
+    use crate::statistics::AggregateStats;
+    use std::collections::HashMap;
+
+    fn result(case_id: &str, model: &str, passed: u32, failed: u32) -> EvalResult {
+        EvalResult {
+            test_execution: Some(TestResult {
+                passed,
+                failed,
+                ignored: 0,
+                duration_ms: 0,
+                failures: vec![],
+            }),
+            ..EvalResult::stub(case_id, model)
+        }
+    }
+
+    fn report(eval_set: &str, results: Vec<EvalResult>) -> EvalReport {
+        EvalReport {
+            id: uuid::Uuid::nil(),
+            created_at: chrono::Utc::now(),
+            eval_set: EvalSetSummary {
+                id: eval_set.into(),
+                name: eval_set.into(),
+                case_count: 0,
+            },
+            models_evaluated: vec![],
+            results,
+            aggregate: AggregateStats {
+                per_model: HashMap::new(),
+                per_case: HashMap::new(),
+                per_tag: HashMap::new(),
+                per_difficulty: HashMap::new(),
+            },
+            scoring: None,
+            cases: Default::default(),
+            rescored_from: None,
+            budget: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn ranks_models_across_reports() {
+        // "strong" passes every case, "mid" half its tests, "weak" none; the
+        // second report only runs "weak" and "strong"
+        let mut first = vec![];
+        let mut second = vec![];
+        for i in 0..10 {
+            let case = format!("c{i}");
+            first.push(result(&case, "strong", 4, 0));
+            first.push(result(&case, "mid", 2, 2));
+            second.push(result(&case, "weak", 0, 4));
+        }
+        let reports = [report("set", first), report("set", second)];
+        let board = Leaderboard::from_reports(&reports, &LeaderboardConfig::default()).unwrap();
+
+        let order: Vec<&str> = board.entries.iter().map(|e| e.model.as_str()).collect();
+        assert_eq!(order, vec!["strong", "mid", "weak"]);
+        assert_eq!(board.groups, 1);
+        assert_eq!(board.cases, 10);
+        assert!(board.unrated.is_empty());
+
+        let strong = &board.entries[0];
+        assert_eq!((strong.rank, strong.cases), (1, 10));
+        assert_eq!((strong.wins, strong.losses, strong.ties), (20, 0, 0));
+        assert!(strong.interval.contains(strong.rating));
+        // Undefeated ratings stay finite
+        assert!(strong.rating.is_finite() && strong.rating > 1000.0);
+        assert!(board.entries[2].rating < 1000.0);
+
+        let md = board.to_markdown();
+        assert!(md.contains("| 1 | strong |"));
+        assert!(md.contains("| 10 | 20-0-0 |"));
+    }
+
+    #[test]
+    fn disconnected_models_are_grouped_not_compared() {
+        let reports = [
+            report("a", vec![result("x", "m1", 4, 0), result("x", "m2", 0, 4)]),
+            report("b", vec![result("x", "m3", 4, 0), result("x", "m4", 4, 0)]),
+            report("c", vec![result("x", "loner", 4, 0)]),
+        ];
+        let board = Leaderboard::from_reports(&reports, &LeaderboardConfig::default()).unwrap();
+
+        assert_eq!(board.groups, 2);
+        assert_eq!(board.unrated, vec!["loner"]);
+        let group_of = |m: &str| board.entries.iter().find(|e| e.model == m).unwrap().group;
+        assert_eq!(group_of("m1"), group_of("m2"));
+        assert_ne!(group_of("m1"), group_of("m3"));
+        assert!(board.entries.iter().all(|e| e.rank <= 2));
+        assert!(board.to_markdown().contains("not comparable across groups"));
+    }
+
+    #[test]
+    fn fewer_games_widen_the_interval() {
+        let mut few = vec![];
+        let mut many = vec![];
+        for i in 0..40 {
+            let case = format!("c{i}");
+            let (a, b) = if i % 4 == 0 { (0, 4) } else { (4, 0) };
+            if i < 8 {
+                few.push(result(&case, "a", a, b));
+                few.push(result(&case, "b", b, a));
+            }
+            many.push(result(&case, "a", a, b));
+            many.push(result(&case, "b", b, a));
+        }
+        let config = LeaderboardConfig::default();
+        let width = |results| {
+            let board = Leaderboard::from_reports(&[report("s", results)], &config).unwrap();
+            let a = board.entries.iter().find(|e| e.model == "a").unwrap();
+            a.interval.upper - a.interval.lower
+        };
+        assert!(width(few) > width(many));
+    }
+}
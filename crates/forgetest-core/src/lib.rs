@@ -3,11 +3,24 @@
 //! This crate defines the fundamental data model, traits, and scoring logic
 //! that the entire forgetest system builds on.
 
+pub mod budget;
+pub mod cache;
+pub mod confidence;
 pub mod engine;
 pub mod error;
+pub mod hallucination;
+pub mod head_to_head;
+pub mod journal;
+pub mod judge;
+pub mod leaderboard;
+pub mod metrics;
 pub mod model;
 pub mod parser;
+pub mod plan;
 pub mod report;
 pub mod results;
+pub mod scoring;
+pub mod significance;
 pub mod statistics;
+pub mod taxonomy;
 pub mod traits;
@@ -0,0 +1,328 @@
+//! Static code metrics for generated Rust code.
+//!
+//! Parses the code with `syn` and counts size, complexity and risk markers,
+//! so that solutions which all pass can still be compared on simplicity and
+//! safety.
+
+use serde::{Deserialize, Serialize};
+use syn::visit::{self, Visit};
+
+/// Static metrics for one generated solution.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CodeMetrics {
+    /// Non-blank, non-comment lines.
+    pub lines_of_code: u32,
+    /// Functions and methods (including trait default methods).
+    pub functions: u32,
+    /// Cyclomatic complexity of each function.
+    pub complexity: Vec<FunctionComplexity>,
+    /// `unsafe { .. }` blocks.
+    pub unsafe_blocks: u32,
+    /// `.unwrap()` and `.expect(..)` calls.
+    pub unwrap_calls: u32,
+    /// `.clone()` calls.
+    pub clone_calls: u32,
+    /// Heap allocation sites: `Box::new`, `Vec::new`, `Vec::with_capacity`
+    /// and `vec![..]`.
+    pub heap_allocations: u32,
+}
+
+/// Cyclomatic complexity of a single function.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionComplexity {
+    /// Function name (methods are qualified as `Type::method`).
+    pub name: String,
+    /// 1 + number of decision points.
+    pub complexity: u32,
+}
+
+impl CodeMetrics {
+    /// Highest per-function complexity (0 if there are no functions).
+    pub fn max_complexity(&self) -> u32 {
+        self.complexity
+            .iter()
+            .map(|f| f.complexity)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Mean per-function complexity (0.0 if there are no functions).
+    pub fn mean_complexity(&self) -> f64 {
+        if self.complexity.is_empty() {
+            return 0.0;
+        }
+        self.complexity
+            .iter()
+            .map(|f| f.complexity as f64)
+            .sum::<f64>()
+            / self.complexity.len() as f64
+    }
+}
+
+/// Compute metrics for Rust source code. Returns `None` if it doesn't parse.
+///
+/// Code inside macro invocations other than `vec!` is not inspected.
+pub fn analyze(code: &str) -> Option<CodeMetrics> {
+    let file = syn::parse_file(code).ok()?;
+
+    let mut counter = Counter::default();
+    counter.visit_file(&file);
+
+    Some(CodeMetrics {
+        lines_of_code: count_lines(code),
+        functions: counter.functions.len() as u32,
+        complexity: counter.functions,
+        unsafe_blocks: counter.unsafe_blocks,
+        unwrap_calls: counter.unwrap_calls,
+        clone_calls: counter.clone_calls,
+        heap_allocations: counter.heap_allocations,
+    })
+}
+
+/// Count lines that are neither blank nor line comments.
+fn count_lines(code: &str) -> u32 {
+    code.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("//"))
+        .count() as u32
+}
+
+/// Walks the whole file, counting markers and measuring each function.
+#[derive(Default)]
+struct Counter {
+    functions: Vec<FunctionComplexity>,
+    /// Type of the enclosing `impl` block, used to qualify method names.
+    impl_type: Option<String>,
+    unsafe_blocks: u32,
+    unwrap_calls: u32,
+    clone_calls: u32,
+    heap_allocations: u32,
+}
+
+impl Counter {
+    fn record_function(&mut self, name: String, block: &syn::Block) {
+        let mut complexity = Complexity { decisions: 0 };
+        complexity.visit_block(block);
+        self.functions.push(FunctionComplexity {
+            name,
+            complexity: 1 + complexity.decisions,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for Counter {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.record_function(node.sig.ident.to_string(), &node.block);
+        visit::visit_item_fn(self, node);
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        let outer = self.impl_type.replace(type_name(&node.self_ty));
+        visit::visit_item_impl(self, node);
+        self.impl_type = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        let name = match &self.impl_type {
+            Some(ty) => format!("{ty}::{}", node.sig.ident),
+            None => node.sig.ident.to_string(),
+        };
+        self.record_function(name, &node.block);
+        visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        if let Some(block) = &node.default {
+            self.record_function(node.sig.ident.to_string(), block);
+        }
+        visit::visit_trait_item_fn(self, node);
+    }
+
+    fn visit_expr_unsafe(&mut self, node: &'ast syn::ExprUnsafe) {
+        self.unsafe_blocks += 1;
+        visit::visit_expr_unsafe(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        match node.method.to_string().as_str() {
+            "unwrap" | "expect" => self.unwrap_calls += 1,
+            "clone" => self.clone_calls += 1,
+            _ => {}
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = &*node.func {
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect();
+            if let [.., ty, func] = segments.as_slice() {
+                let allocates = matches!(
+                    (ty.as_str(), func.as_str()),
+                    ("Box", "new") | ("Vec", "new") | ("Vec", "with_capacity")
+                );
+                if allocates {
+                    self.heap_allocations += 1;
+                }
+            }
+        }
+        visit::visit_expr_call(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if node.path.is_ident("vec") {
+            self.heap_allocations += 1;
+        }
+        visit::visit_macro(self, node);
+    }
+}
+
+/// Counts decision points in one function body.
+///
+/// Each `if`, `while`, `for`, `?`, `&&`, `||`, match guard and match arm
+/// beyond the first adds one. Nested function items are measured separately.
+struct Complexity {
+    decisions: u32,
+}
+
+impl<'ast> Visit<'ast> for Complexity {
+    fn visit_item_fn(&mut self, _: &'ast syn::ItemFn) {}
+
+    fn visit_item_impl(&mut self, _: &'ast syn::ItemImpl) {}
+
+    fn visit_expr_if(&mut self, node: &'ast syn::ExprIf) {
+        self.decisions += 1;
+        visit::visit_expr_if(self, node);
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast syn::ExprWhile) {
+        self.decisions += 1;
+        visit::visit_expr_while(self, node);
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast syn::ExprForLoop) {
+        self.decisions += 1;
+        visit::visit_expr_for_loop(self, node);
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast syn::ExprTry) {
+        self.decisions += 1;
+        visit::visit_expr_try(self, node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast syn::ExprMatch) {
+        self.decisions += node.arms.len().saturating_sub(1) as u32;
+        self.decisions += node.arms.iter().filter(|a| a.guard.is_some()).count() as u32;
+        visit::visit_expr_match(self, node);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if matches!(node.op, syn::BinOp::And(_) | syn::BinOp::Or(_)) {
+            self.decisions += 1;
+        }
+        visit::visit_expr_binary(self, node);
+    }
+}
+
+/// Render the last path segment of an impl's self type (e.g. `Stack`).
+fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident.to_string())
+            .unwrap_or_default(),
+        syn::Type::Reference(reference) => type_name(&reference.elem),
+        _ => "_".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_markers() {
+        let code = r#"
+// A small stack
+pub struct Stack { items: Vec<i32> }
+
+impl Stack {
+    pub fn new() -> Self {
+        Stack { items: Vec::new() }
+    }
+
+    pub fn peek(&self) -> i32 {
+        *self.items.last().unwrap()
+    }
+
+    pub fn snapshot(&self) -> Vec<i32> {
+        self.items.clone()
+    }
+}
+
+pub fn boxed(x: i32) -> Box<i32> {
+    let _scratch = vec![x; 4];
+    Box::new(x)
+}
+
+pub fn first(v: &[i32]) -> i32 {
+    unsafe { *v.get_unchecked(0) }
+}
+"#;
+        let metrics = analyze(code).unwrap();
+        assert_eq!(metrics.functions, 5);
+        assert_eq!(metrics.unsafe_blocks, 1);
+        assert_eq!(metrics.unwrap_calls, 1);
+        assert_eq!(metrics.clone_calls, 1);
+        assert_eq!(metrics.heap_allocations, 3);
+        assert_eq!(metrics.complexity[0].name, "Stack::new");
+        assert_eq!(metrics.lines_of_code, 19);
+    }
+
+    #[test]
+    fn cyclomatic_complexity() {
+        let code = r#"
+fn classify(n: i32, flag: bool) -> Result<&'static str, String> {
+    if n < 0 && flag {
+        return Err("negative".into());
+    }
+    for _ in 0..n {}
+    let s = match n {
+        0 => "zero",
+        x if x % 2 == 0 => "even",
+        _ => "odd",
+    };
+    let _: i32 = "1".parse::<i32>().map_err(|e| e.to_string())?;
+    fn helper() -> bool { true || false }
+    Ok(s)
+}
+"#;
+        let metrics = analyze(code).unwrap();
+        let classify = metrics
+            .complexity
+            .iter()
+            .find(|f| f.name == "classify")
+            .unwrap();
+        // 1 + if + && + for + 2 extra arms + guard + ?
+        assert_eq!(classify.complexity, 8);
+        let helper = metrics
+            .complexity
+            .iter()
+            .find(|f| f.name == "helper")
+            .unwrap();
+        assert_eq!(helper.complexity, 2);
+        assert_eq!(metrics.max_complexity(), 8);
+        assert!((metrics.mean_complexity() - 5.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn unparseable_code() {
+        assert!(analyze("fn broken( {").is_none());
+    }
+}
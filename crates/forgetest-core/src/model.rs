@@ -4,9 +4,12 @@
 //! to represent eval cases, expectations, and eval sets.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
 
+use crate::scoring::ScoringProfile;
 use crate::traits::Dependency;
 
 /// A single evaluation task sent to an LLM.
@@ -30,9 +33,13 @@ pub struct EvalCase {
     /// What we check about the LLM's output.
     #[serde(default)]
     pub expectations: Expectations,
-    /// Tags for filtering eval cases.
+    /// Tags for filtering eval cases and grouping aggregate statistics.
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Difficulty label (e.g. "easy", "hard") for grouping aggregate
+    /// statistics.
+    #[serde(default)]
+    pub difficulty: Option<String>,
     /// External crate dependencies needed to compile this case.
     #[serde(default)]
     pub dependencies: Vec<Dependency>,
@@ -42,6 +49,53 @@ pub struct EvalCase {
     /// Per-case max tokens override.
     #[serde(default)]
     pub max_tokens: Option<u32>,
+    /// Existing crate the generated code is dropped into, instead of the
+    /// empty sandbox template.
+    #[serde(default)]
+    pub fixture: Option<Fixture>,
+    /// Fill-in-the-middle completion: the model sees `prefix`/`suffix`
+    /// instead of a chat prompt.
+    #[serde(default)]
+    pub fim: Option<FimSpec>,
+}
+
+/// Prefix and suffix for a fill-in-the-middle (FIM) completion case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FimSpec {
+    /// Code before the cursor.
+    pub prefix: String,
+    /// Code after the cursor.
+    #[serde(default)]
+    pub suffix: String,
+}
+
+impl FimSpec {
+    /// Splice a completion between the prefix and suffix to form the full source.
+    pub fn splice(&self, completion: &str) -> String {
+        format!("{}{}{}", self.prefix, completion, self.suffix)
+    }
+}
+
+/// Marker line in a fixture's target file that is replaced by the generated code.
+pub const FIXTURE_FILL_MARKER: &str = "// forgetest:fill";
+
+/// A fixture crate directory that an eval case starts from.
+///
+/// The whole directory (Cargo.toml, sources, tests, data files) is copied into
+/// the sandbox. The generated code is written to `target_file`: if that file
+/// contains [`FIXTURE_FILL_MARKER`] the marker line is replaced, otherwise the
+/// file is overwritten. The fixture's own test suite is the oracle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Path to the fixture directory (resolved relative to the eval set file).
+    pub path: PathBuf,
+    /// File inside the fixture that receives the generated code.
+    #[serde(default = "default_fixture_target")]
+    pub target_file: String,
+}
+
+pub(crate) fn default_fixture_target() -> String {
+    "src/lib.rs".to_string()
 }
 
 /// A file provided as context to the LLM alongside the prompt.
@@ -77,6 +131,180 @@ pub struct Expectations {
     /// Shell command that receives generated code on stdin; exits 0 for pass.
     #[serde(default)]
     pub custom_check: Option<String>,
+    /// Snippets that must be rejected by the compiler when built against the
+    /// generated crate (trybuild-style negative tests).
+    #[serde(default)]
+    pub expected_compile_failures: Vec<CompileFailExpectation>,
+    /// A known-good solution, used as the baseline for relative checks such as
+    /// `performance.max_ratio`.
+    #[serde(default)]
+    pub reference_solution: Option<String>,
+    /// Performance gate measured with an in-sandbox benchmark.
+    #[serde(default)]
+    pub performance: Option<PerformanceExpectation>,
+    /// Run the tests under Miri (requires the nightly `miri` component) and
+    /// fail the case on any undefined behaviour report.
+    #[serde(default)]
+    pub run_miri: bool,
+    /// Clippy lint profile and category weights.
+    #[serde(default)]
+    pub clippy: ClippyConfig,
+    /// rustfmt configuration and the weight of formatting in the score.
+    #[serde(default)]
+    pub formatting: FormattingConfig,
+    /// Rubric for the optional LLM judge stage.
+    #[serde(default)]
+    pub judge: Option<JudgeRubric>,
+}
+
+/// How generated code is checked with rustfmt and how much it counts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormattingConfig {
+    /// Contents written to `rustfmt.toml` before checking.
+    #[serde(default)]
+    pub config: Option<String>,
+    /// Share of the overall score given to formatting (0.0-1.0). The other
+    /// components are scaled by `1 - weight`; 0.0 leaves the score unchanged.
+    #[serde(default)]
+    pub weight: f64,
+}
+
+/// Criteria an LLM judge grades generated code against.
+///
+/// Used for qualities that tests can't check, such as idiomatic error
+/// handling, naming and documentation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JudgeRubric {
+    /// The graded criteria.
+    #[serde(default)]
+    pub criteria: Vec<JudgeCriterion>,
+    /// Extra guidance for the judge, added to the prompt.
+    #[serde(default)]
+    pub instructions: Option<String>,
+    /// Share of the overall score given to the judge (0.0-1.0), applied like
+    /// the formatting weight; 0.0 reports the judge score without using it.
+    #[serde(default)]
+    pub weight: f64,
+}
+
+/// A single rubric criterion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgeCriterion {
+    /// Short identifier (e.g. "error_handling").
+    pub name: String,
+    /// What the judge should look for.
+    pub description: String,
+    /// Relative weight within the judge score.
+    #[serde(default = "default_criterion_weight")]
+    pub weight: f64,
+}
+
+fn default_criterion_weight() -> f64 {
+    1.0
+}
+
+/// A benchmark and the budget the generated code must meet.
+///
+/// The `bench` snippet defines `fn setup(n: usize) -> Input` (untimed) and
+/// `fn run(input: Input) -> Output` (timed). It is compiled in release mode
+/// against the generated crate and run for each input size, with warmup
+/// iterations followed by measured repetitions. Budgets apply to the largest
+/// input size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceExpectation {
+    /// Benchmark source defining `setup` and `run`.
+    pub bench: String,
+    /// Input sizes passed to `setup`.
+    #[serde(default = "default_bench_sizes")]
+    pub sizes: Vec<usize>,
+    /// Untimed iterations per size before measuring.
+    #[serde(default = "default_bench_warmup")]
+    pub warmup: u32,
+    /// Measured iterations per size; the median is used.
+    #[serde(default = "default_bench_repetitions")]
+    pub repetitions: u32,
+    /// Maximum median time of one `run` call, in milliseconds.
+    #[serde(default)]
+    pub max_time_ms: Option<f64>,
+    /// Maximum heap allocations during one `run` call.
+    #[serde(default)]
+    pub max_allocations: Option<u64>,
+    /// Maximum median time relative to `reference_solution` (e.g. 2.0 = at
+    /// most twice as slow).
+    #[serde(default)]
+    pub max_ratio: Option<f64>,
+}
+
+pub(crate) fn default_bench_sizes() -> Vec<usize> {
+    vec![1000]
+}
+
+pub(crate) fn default_bench_warmup() -> u32 {
+    3
+}
+
+pub(crate) fn default_bench_repetitions() -> u32 {
+    10
+}
+
+/// Penalty per clippy warning for categories without an explicit weight.
+pub const DEFAULT_CLIPPY_WEIGHT: f64 = 0.1;
+
+/// Which lints clippy runs with and how much each warning costs.
+///
+/// The default groups (correctness, suspicious, style, complexity, perf) are
+/// always enabled. Explicit lint lists are applied after the groups, in the
+/// order allow, warn, deny, so they override them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClippyConfig {
+    /// Enable `clippy::pedantic`.
+    #[serde(default)]
+    pub pedantic: bool,
+    /// Enable `clippy::nursery`.
+    #[serde(default)]
+    pub nursery: bool,
+    /// Lints to allow (e.g. "clippy::module_name_repetitions").
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Lints to warn on.
+    #[serde(default)]
+    pub warn: Vec<String>,
+    /// Lints to deny.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Contents written to `clippy.toml` in the sandbox.
+    #[serde(default)]
+    pub config: Option<String>,
+    /// Score penalty per warning, by lint category (e.g. "correctness" = 0.5).
+    /// Categories not listed cost [`DEFAULT_CLIPPY_WEIGHT`].
+    #[serde(default)]
+    pub category_weights: BTreeMap<String, f64>,
+}
+
+impl ClippyConfig {
+    /// Penalty for a single warning in the given lint category.
+    pub fn weight(&self, category: &str) -> f64 {
+        self.category_weights
+            .get(category)
+            .copied()
+            .unwrap_or(DEFAULT_CLIPPY_WEIGHT)
+    }
+}
+
+/// A snippet of misuse that must fail to compile against the generated crate.
+///
+/// The snippet is built as a separate example target with the generated crate
+/// glob-imported, so only its public API is visible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompileFailExpectation {
+    /// Optional label used in reports (defaults to `compile_fail_<n>`).
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The snippet source.
+    pub code: String,
+    /// Expected rustc error code (e.g. "E0382"); any error passes if unset.
+    #[serde(default)]
+    pub error_code: Option<String>,
 }
 
 impl Default for Expectations {
@@ -89,6 +317,13 @@ impl Default for Expectations {
             expected_types: Vec::new(),
             max_clippy_warnings: None,
             custom_check: None,
+            expected_compile_failures: Vec::new(),
+            reference_solution: None,
+            performance: None,
+            run_miri: false,
+            clippy: ClippyConfig::default(),
+            formatting: FormattingConfig::default(),
+            judge: None,
         }
     }
 }
@@ -151,6 +386,9 @@ pub struct EvalSet {
     /// Default timeout in seconds for cases that don't specify one.
     #[serde(default = "default_timeout")]
     pub default_timeout_secs: u64,
+    /// Scoring profile for this set, overriding the one in `forgetest.toml`.
+    #[serde(default)]
+    pub scoring: Option<ScoringProfile>,
 }
 
 fn default_language() -> Language {
@@ -199,13 +437,28 @@ mod tests {
             context: vec![],
             expectations: Expectations::default(),
             tags: vec!["basics".into()],
+            difficulty: None,
             dependencies: vec![],
             timeout_secs: Some(30),
             max_tokens: None,
+            fixture: None,
+            fim: None,
         };
         let json = serde_json::to_string(&case).unwrap();
         let deserialized: EvalCase = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.id, "test-1");
         assert_eq!(deserialized.language, Some(Language::Rust));
     }
+
+    #[test]
+    fn fim_splice() {
+        let fim = FimSpec {
+            prefix: "fn add(a: i32, b: i32) -> i32 {\n".into(),
+            suffix: "\n}\n".into(),
+        };
+        assert_eq!(
+            fim.splice("    a + b"),
+            "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n"
+        );
+    }
 }
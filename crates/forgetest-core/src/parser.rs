@@ -2,12 +2,18 @@
 //!
 //! Loads eval sets from TOML files and directories, and validates them.
 
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
-use crate::model::{EvalCase, EvalSet, Expectations, Language};
+use crate::model::{
+    default_bench_repetitions, default_bench_sizes, default_bench_warmup, default_fixture_target,
+    ClippyConfig, CompileFailExpectation, EvalCase, EvalSet, Expectations, FimSpec, Fixture,
+    FormattingConfig, JudgeCriterion, JudgeRubric, Language, PerformanceExpectation,
+};
+use crate::scoring::ScoringProfile;
 use crate::traits::Dependency;
 
 /// Intermediate TOML structure for parsing eval set files.
@@ -28,6 +34,18 @@ struct TomlEvalSetHeader {
     default_language: String,
     #[serde(default = "default_timeout")]
     default_timeout_secs: u64,
+    /// Lint profile applied to every case; cases can extend or override it.
+    #[serde(default)]
+    clippy: Option<TomlClippy>,
+    /// rustfmt settings applied to every case.
+    #[serde(default)]
+    formatting: Option<TomlFormatting>,
+    /// Scoring profile for the whole set.
+    #[serde(default)]
+    scoring: Option<ScoringProfile>,
+    /// LLM judge rubric applied to every case.
+    #[serde(default)]
+    judge: Option<TomlJudge>,
 }
 
 fn default_language_str() -> String {
@@ -44,12 +62,16 @@ struct TomlEvalCase {
     name: String,
     #[serde(default)]
     description: String,
+    /// Optional for FIM cases, which use `fim.prefix`/`fim.suffix` instead.
+    #[serde(default)]
     prompt: String,
     #[serde(default)]
     language: Option<String>,
     #[serde(default)]
     tags: Vec<String>,
     #[serde(default)]
+    difficulty: Option<String>,
+    #[serde(default)]
     dependencies: Vec<TomlDependency>,
     #[serde(default)]
     timeout_secs: Option<u64>,
@@ -57,6 +79,29 @@ struct TomlEvalCase {
     max_tokens: Option<u32>,
     #[serde(default)]
     expectations: Option<TomlExpectations>,
+    #[serde(default)]
+    fixture: Option<TomlFixture>,
+    #[serde(default)]
+    fim: Option<TomlFim>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TomlFim {
+    prefix: String,
+    #[serde(default)]
+    suffix: String,
+}
+
+/// A fixture is either a bare directory path or a table with a target file.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TomlFixture {
+    Path(String),
+    Table {
+        path: String,
+        #[serde(default)]
+        target_file: Option<String>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,6 +128,134 @@ struct TomlExpectations {
     max_clippy_warnings: Option<u32>,
     #[serde(default)]
     custom_check: Option<String>,
+    #[serde(default)]
+    expected_compile_failures: Vec<TomlCompileFail>,
+    #[serde(default)]
+    reference_solution: Option<String>,
+    #[serde(default)]
+    performance: Option<TomlPerformance>,
+    #[serde(default)]
+    run_miri: bool,
+    #[serde(default)]
+    clippy: Option<TomlClippy>,
+    #[serde(default)]
+    formatting: Option<TomlFormatting>,
+    #[serde(default)]
+    judge: Option<TomlJudge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TomlPerformance {
+    bench: String,
+    #[serde(default)]
+    sizes: Option<Vec<usize>>,
+    #[serde(default)]
+    warmup: Option<u32>,
+    #[serde(default)]
+    repetitions: Option<u32>,
+    #[serde(default)]
+    max_time_ms: Option<f64>,
+    #[serde(default)]
+    max_allocations: Option<u64>,
+    #[serde(default)]
+    max_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TomlFormatting {
+    #[serde(default)]
+    config: Option<String>,
+    #[serde(default)]
+    weight: Option<f64>,
+}
+
+/// Merge the set-level and case-level rustfmt settings; case values win.
+fn merge_formatting(
+    set: Option<&TomlFormatting>,
+    case: Option<TomlFormatting>,
+) -> FormattingConfig {
+    let set = set.cloned().unwrap_or_default();
+    let case = case.unwrap_or_default();
+    FormattingConfig {
+        config: case.config.or(set.config),
+        weight: case.weight.or(set.weight).unwrap_or(0.0),
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TomlJudge {
+    #[serde(default)]
+    criteria: Option<Vec<JudgeCriterion>>,
+    #[serde(default)]
+    instructions: Option<String>,
+    #[serde(default)]
+    weight: Option<f64>,
+}
+
+/// Merge the set-level and case-level judge rubrics; case values win.
+///
+/// Returns `None` unless the set or the case defines a rubric.
+fn merge_judge(set: Option<&TomlJudge>, case: Option<TomlJudge>) -> Option<JudgeRubric> {
+    if set.is_none() && case.is_none() {
+        return None;
+    }
+    let set = set.cloned().unwrap_or_default();
+    let case = case.unwrap_or_default();
+    Some(JudgeRubric {
+        criteria: case.criteria.or(set.criteria).unwrap_or_default(),
+        instructions: case.instructions.or(set.instructions),
+        weight: case.weight.or(set.weight).unwrap_or(0.0),
+    })
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TomlClippy {
+    #[serde(default)]
+    pedantic: Option<bool>,
+    #[serde(default)]
+    nursery: Option<bool>,
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    warn: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+    #[serde(default)]
+    config: Option<String>,
+    #[serde(default)]
+    category_weights: BTreeMap<String, f64>,
+}
+
+/// Merge the set-level and case-level lint profiles.
+///
+/// Case flags and `clippy.toml` contents win; lint lists are concatenated with
+/// the case's entries last so they take precedence, and weights are merged.
+fn merge_clippy(set: Option<&TomlClippy>, case: Option<TomlClippy>) -> ClippyConfig {
+    let set = set.cloned().unwrap_or_default();
+    let case = case.unwrap_or_default();
+    let concat = |a: Vec<String>, b: Vec<String>| a.into_iter().chain(b).collect();
+
+    let mut category_weights = set.category_weights;
+    category_weights.extend(case.category_weights);
+
+    ClippyConfig {
+        pedantic: case.pedantic.or(set.pedantic).unwrap_or(false),
+        nursery: case.nursery.or(set.nursery).unwrap_or(false),
+        allow: concat(set.allow, case.allow),
+        warn: concat(set.warn, case.warn),
+        deny: concat(set.deny, case.deny),
+        config: case.config.or(set.config),
+        category_weights,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TomlCompileFail {
+    #[serde(default)]
+    name: Option<String>,
+    code: String,
+    #[serde(default)]
+    error_code: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -108,16 +281,21 @@ pub fn parse_eval_set_str(content: &str, source_path: &Path) -> Result<EvalSet>
         .parse()
         .map_err(|e: String| anyhow::anyhow!("{}", e))?;
 
+    let base_dir = source_path.parent().unwrap_or_else(|| Path::new(""));
+
     let cases = parsed
         .cases
         .into_iter()
-        .map(|c| {
+        .map(|mut c| {
             let language = c
                 .language
                 .map(|l| l.parse().map_err(|e: String| anyhow::anyhow!("{}", e)))
                 .transpose()?;
 
-            let expectations = match c.expectations {
+            let case_clippy = c.expectations.as_mut().and_then(|e| e.clippy.take());
+            let case_formatting = c.expectations.as_mut().and_then(|e| e.formatting.take());
+            let case_judge = c.expectations.as_mut().and_then(|e| e.judge.take());
+            let mut expectations = match c.expectations {
                 Some(exp) => Expectations {
                     should_compile: exp.should_compile,
                     should_pass_tests: exp.should_pass_tests,
@@ -126,9 +304,36 @@ pub fn parse_eval_set_str(content: &str, source_path: &Path) -> Result<EvalSet>
                     expected_types: exp.expected_types,
                     max_clippy_warnings: exp.max_clippy_warnings,
                     custom_check: exp.custom_check,
+                    expected_compile_failures: exp
+                        .expected_compile_failures
+                        .into_iter()
+                        .map(|f| CompileFailExpectation {
+                            name: f.name,
+                            code: f.code,
+                            error_code: f.error_code,
+                        })
+                        .collect(),
+                    reference_solution: exp.reference_solution,
+                    performance: exp.performance.map(|p| PerformanceExpectation {
+                        bench: p.bench,
+                        sizes: p.sizes.unwrap_or_else(default_bench_sizes),
+                        warmup: p.warmup.unwrap_or_else(default_bench_warmup),
+                        repetitions: p.repetitions.unwrap_or_else(default_bench_repetitions),
+                        max_time_ms: p.max_time_ms,
+                        max_allocations: p.max_allocations,
+                        max_ratio: p.max_ratio,
+                    }),
+                    run_miri: exp.run_miri,
+                    clippy: ClippyConfig::default(),
+                    formatting: FormattingConfig::default(),
+                    judge: None,
                 },
                 None => Expectations::default(),
             };
+            expectations.clippy = merge_clippy(parsed.eval_set.clippy.as_ref(), case_clippy);
+            expectations.formatting =
+                merge_formatting(parsed.eval_set.formatting.as_ref(), case_formatting);
+            expectations.judge = merge_judge(parsed.eval_set.judge.as_ref(), case_judge);
 
             let dependencies = c
                 .dependencies
@@ -140,6 +345,17 @@ pub fn parse_eval_set_str(content: &str, source_path: &Path) -> Result<EvalSet>
                 })
                 .collect();
 
+            let fixture = c.fixture.map(|f| {
+                let (path, target_file) = match f {
+                    TomlFixture::Path(path) => (path, None),
+                    TomlFixture::Table { path, target_file } => (path, target_file),
+                };
+                Fixture {
+                    path: base_dir.join(path),
+                    target_file: target_file.unwrap_or_else(default_fixture_target),
+                }
+            });
+
             Ok(EvalCase {
                 id: c.id,
                 name: c.name,
@@ -149,9 +365,15 @@ pub fn parse_eval_set_str(content: &str, source_path: &Path) -> Result<EvalSet>
                 context: vec![],
                 expectations,
                 tags: c.tags,
+                difficulty: c.difficulty,
                 dependencies,
                 timeout_secs: c.timeout_secs,
                 max_tokens: c.max_tokens,
+                fixture,
+                fim: c.fim.map(|f| FimSpec {
+                    prefix: f.prefix,
+                    suffix: f.suffix,
+                }),
             })
         })
         .collect::<Result<Vec<_>>>()?;
@@ -163,6 +385,7 @@ pub fn parse_eval_set_str(content: &str, source_path: &Path) -> Result<EvalSet>
         cases,
         default_language,
         default_timeout_secs: parsed.eval_set.default_timeout_secs,
+        scoring: parsed.eval_set.scoring,
     })
 }
 
@@ -219,9 +442,83 @@ pub fn validate_eval_set(set: &EvalSet) -> Vec<ValidationWarning> {
         }
     }
 
-    // Check for should_pass_tests=true without test_file
+    // Check that performance gates can be run and have something to enforce
     for case in &set.cases {
-        if case.expectations.should_pass_tests && case.expectations.test_file.is_none() {
+        let Some(perf) = &case.expectations.performance else {
+            continue;
+        };
+        let mut warn = |message: String| {
+            warnings.push(ValidationWarning {
+                case_id: Some(case.id.clone()),
+                message,
+            })
+        };
+        if !perf.bench.contains("fn setup") || !perf.bench.contains("fn run") {
+            warn("performance bench must define `fn setup(n: usize)` and `fn run(input)`".into());
+        }
+        if perf.sizes.is_empty() {
+            warn("performance sizes is empty".into());
+        }
+        if perf.max_time_ms.is_none() && perf.max_allocations.is_none() && perf.max_ratio.is_none()
+        {
+            warn("performance has no budget (max_time_ms, max_allocations or max_ratio)".into());
+        }
+        if perf.max_ratio.is_some() && case.expectations.reference_solution.is_none() {
+            warn("performance max_ratio requires a reference_solution".into());
+        }
+    }
+
+    // Check that the scoring profile produces sensible scores
+    if let Some(profile) = &set.scoring {
+        for message in profile.problems() {
+            warnings.push(ValidationWarning {
+                case_id: None,
+                message,
+            });
+        }
+    }
+
+    // Check that judge rubrics have something to grade
+    for case in &set.cases {
+        let Some(judge) = &case.expectations.judge else {
+            continue;
+        };
+        if judge.criteria.is_empty() {
+            warnings.push(ValidationWarning {
+                case_id: Some(case.id.clone()),
+                message: "judge rubric has no criteria".into(),
+            });
+        }
+        if !(0.0..=1.0).contains(&judge.weight) {
+            warnings.push(ValidationWarning {
+                case_id: Some(case.id.clone()),
+                message: format!(
+                    "judge weight {} is outside 0.0-1.0 and will be clamped",
+                    judge.weight
+                ),
+            });
+        }
+    }
+
+    // Check that formatting weights are usable as a share of the score
+    for case in &set.cases {
+        let weight = case.expectations.formatting.weight;
+        if !(0.0..=1.0).contains(&weight) {
+            warnings.push(ValidationWarning {
+                case_id: Some(case.id.clone()),
+                message: format!(
+                    "formatting weight {weight} is outside 0.0-1.0 and will be clamped"
+                ),
+            });
+        }
+    }
+
+    // Check for should_pass_tests=true without test_file (fixtures bring their own tests)
+    for case in &set.cases {
+        if case.expectations.should_pass_tests
+            && case.expectations.test_file.is_none()
+            && case.fixture.is_none()
+        {
             warnings.push(ValidationWarning {
                 case_id: Some(case.id.clone()),
                 message: "should_pass_tests is true but no test_file provided".into(),
@@ -229,9 +526,43 @@ pub fn validate_eval_set(set: &EvalSet) -> Vec<ValidationWarning> {
         }
     }
 
-    // Check for empty prompts
+    // Check that fixture directories exist and look like crates
+    for case in &set.cases {
+        if let Some(fixture) = &case.fixture {
+            if !fixture.path.join("Cargo.toml").is_file() {
+                warnings.push(ValidationWarning {
+                    case_id: Some(case.id.clone()),
+                    message: format!(
+                        "fixture directory {} does not contain a Cargo.toml",
+                        fixture.path.display()
+                    ),
+                });
+            }
+        }
+    }
+
+    // Check that expected compile-failure error codes look like rustc codes
     for case in &set.cases {
-        if case.prompt.trim().is_empty() {
+        for snippet in &case.expectations.expected_compile_failures {
+            if let Some(code) = &snippet.error_code {
+                let valid = code.len() == 5
+                    && code.starts_with('E')
+                    && code[1..].chars().all(|c| c.is_ascii_digit());
+                if !valid {
+                    warnings.push(ValidationWarning {
+                        case_id: Some(case.id.clone()),
+                        message: format!(
+                            "expected_compile_failures error_code '{code}' is not a rustc error code (e.g. E0382)"
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    // Check for empty prompts (FIM cases are driven by prefix/suffix instead)
+    for case in &set.cases {
+        if case.prompt.trim().is_empty() && case.fim.is_none() {
             warnings.push(ValidationWarning {
                 case_id: Some(case.id.clone()),
                 message: "prompt is empty".into(),
@@ -375,6 +706,322 @@ should_pass_tests = true
         assert!(result.is_err());
     }
 
+    #[test]
+    fn parse_fixture_resolves_relative_to_eval_set() {
+        let toml = r#"
+[eval_set]
+id = "fixtures"
+name = "Fixtures"
+
+[[cases]]
+id = "short"
+name = "Short form"
+prompt = "Implement the client"
+fixture = "fixtures/http-client"
+
+[[cases]]
+id = "table"
+name = "Table form"
+prompt = "Implement the client"
+fixture = { path = "fixtures/http-client", target_file = "src/client.rs" }
+"#;
+        let set = parse_eval_set_str(toml, &PathBuf::from("sets/test.toml")).unwrap();
+        let short = set.cases[0].fixture.as_ref().unwrap();
+        assert_eq!(short.path, PathBuf::from("sets/fixtures/http-client"));
+        assert_eq!(short.target_file, "src/lib.rs");
+        let table = set.cases[1].fixture.as_ref().unwrap();
+        assert_eq!(table.target_file, "src/client.rs");
+
+        // Fixtures bring their own tests, but this one is missing on disk
+        let warnings = validate_eval_set(&set);
+        assert!(!warnings.iter().any(|w| w.message.contains("no test_file")));
+        assert!(warnings.iter().any(|w| w.message.contains("Cargo.toml")));
+    }
+
+    #[test]
+    fn parse_fim_case_without_prompt() {
+        let toml = r#"
+[eval_set]
+id = "fim"
+name = "FIM"
+
+[[cases]]
+id = "complete_add"
+name = "Complete add"
+
+[cases.fim]
+prefix = "pub fn add(a: i32, b: i32) -> i32 {\n"
+suffix = "\n}\n"
+
+[cases.expectations]
+test_file = '#[test] fn t() { assert_eq!(add(1, 2), 3); }'
+"#;
+        let set = parse_eval_set_str(toml, &PathBuf::from("test.toml")).unwrap();
+        let fim = set.cases[0].fim.as_ref().unwrap();
+        assert!(fim.prefix.starts_with("pub fn add"));
+        assert_eq!(fim.suffix, "\n}\n");
+        assert!(validate_eval_set(&set).is_empty());
+    }
+
+    #[test]
+    fn parse_expected_compile_failures() {
+        let toml = r#"
+[eval_set]
+id = "typestate"
+name = "Typestate"
+
+[[cases]]
+id = "door"
+name = "Door typestate"
+prompt = "Write a typestate Door API"
+
+[cases.expectations]
+should_pass_tests = false
+
+[[cases.expectations.expected_compile_failures]]
+name = "open_twice"
+code = "fn main() { let d = Door::new().open(); d.open(); }"
+error_code = "E0599"
+
+[[cases.expectations.expected_compile_failures]]
+code = "fn main() { let d = Door::new(); drop(d); d.open(); }"
+error_code = "moved"
+"#;
+        let set = parse_eval_set_str(toml, &PathBuf::from("test.toml")).unwrap();
+        let snippets = &set.cases[0].expectations.expected_compile_failures;
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(snippets[0].name.as_deref(), Some("open_twice"));
+        assert_eq!(snippets[0].error_code.as_deref(), Some("E0599"));
+
+        let warnings = validate_eval_set(&set);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("'moved'"));
+    }
+
+    #[test]
+    fn parse_clippy_profiles() {
+        let toml = r#"
+[eval_set]
+id = "lints"
+name = "Lints"
+
+[eval_set.clippy]
+pedantic = true
+allow = ["module_name_repetitions"]
+category_weights = { correctness = 0.5, style = 0.02 }
+
+[[cases]]
+id = "inherits"
+name = "Inherits"
+prompt = "p"
+
+[[cases]]
+id = "overrides"
+name = "Overrides"
+prompt = "p"
+
+[cases.expectations.clippy]
+pedantic = false
+deny = ["clippy::unwrap_used"]
+config = "msrv = \"1.70\""
+category_weights = { style = 0.05 }
+"#;
+        let set = parse_eval_set_str(toml, Path::new("lints.toml")).unwrap();
+
+        let inherits = &set.cases[0].expectations.clippy;
+        assert!(inherits.pedantic);
+        assert_eq!(inherits.allow, vec!["module_name_repetitions"]);
+        assert_eq!(inherits.weight("correctness"), 0.5);
+
+        let overrides = &set.cases[1].expectations.clippy;
+        assert!(!overrides.pedantic);
+        assert_eq!(overrides.allow, vec!["module_name_repetitions"]);
+        assert_eq!(overrides.deny, vec!["clippy::unwrap_used"]);
+        assert_eq!(overrides.config.as_deref(), Some("msrv = \"1.70\""));
+        assert_eq!(overrides.weight("correctness"), 0.5);
+        assert_eq!(overrides.weight("style"), 0.05);
+        assert_eq!(overrides.weight("perf"), 0.1);
+    }
+
+    #[test]
+    fn parse_formatting_settings() {
+        let toml = r#"
+[eval_set]
+id = "fmt"
+name = "Fmt"
+
+[eval_set.formatting]
+config = 'max_width = 80'
+weight = 0.1
+
+[[cases]]
+id = "inherits"
+name = "Inherits"
+prompt = "p"
+
+[[cases]]
+id = "heavier"
+name = "Heavier"
+prompt = "p"
+
+[cases.expectations.formatting]
+weight = 1.5
+"#;
+        let set = parse_eval_set_str(toml, Path::new("fmt.toml")).unwrap();
+        let inherits = &set.cases[0].expectations.formatting;
+        assert_eq!(inherits.config.as_deref(), Some("max_width = 80"));
+        assert_eq!(inherits.weight, 0.1);
+
+        let heavier = &set.cases[1].expectations.formatting;
+        assert_eq!(heavier.config.as_deref(), Some("max_width = 80"));
+        assert_eq!(heavier.weight, 1.5);
+
+        let warnings = validate_eval_set(&set);
+        assert!(warnings
+            .iter()
+            .any(|w| w.case_id.as_deref() == Some("heavier") && w.message.contains("clamped")));
+    }
+
+    #[test]
+    fn parse_judge_rubrics() {
+        let toml = r#"
+[eval_set]
+id = "judged"
+name = "Judged"
+
+[eval_set.judge]
+weight = 0.2
+
+[[eval_set.judge.criteria]]
+name = "error_handling"
+description = "Errors are propagated with Result, not unwrap"
+weight = 2.0
+
+[[eval_set.judge.criteria]]
+name = "naming"
+description = "Names are descriptive"
+
+[[cases]]
+id = "inherits"
+name = "Inherits"
+prompt = "p"
+
+[[cases]]
+id = "docs"
+name = "Docs"
+prompt = "p"
+
+[cases.expectations.judge]
+instructions = "This is a public library API."
+criteria = [{ name = "docs", description = "Public items have doc comments" }]
+"#;
+        let set = parse_eval_set_str(toml, Path::new("judged.toml")).unwrap();
+        let inherits = set.cases[0].expectations.judge.as_ref().unwrap();
+        assert_eq!(inherits.criteria.len(), 2);
+        assert_eq!(inherits.criteria[0].weight, 2.0);
+        assert_eq!(inherits.criteria[1].weight, 1.0);
+        assert_eq!(inherits.weight, 0.2);
+
+        let docs = set.cases[1].expectations.judge.as_ref().unwrap();
+        assert_eq!(docs.criteria.len(), 1);
+        assert_eq!(docs.criteria[0].name, "docs");
+        assert_eq!(
+            docs.instructions.as_deref(),
+            Some("This is a public library API.")
+        );
+        assert_eq!(docs.weight, 0.2);
+
+        let plain = parse_eval_set_str(VALID_TOML, Path::new("test.toml")).unwrap();
+        assert!(plain.cases[0].expectations.judge.is_none());
+    }
+
+    #[test]
+    fn parse_scoring_profile() {
+        let toml = r#"
+[eval_set]
+id = "scored"
+name = "Scored"
+
+[eval_set.scoring]
+name = "lenient"
+zero_on_compile_failure = false
+
+[eval_set.scoring.weights]
+tests = 0.9
+
+[[cases]]
+id = "a"
+name = "A"
+prompt = "p"
+"#;
+        let set = parse_eval_set_str(toml, Path::new("scored.toml")).unwrap();
+        let scoring = set.scoring.as_ref().unwrap();
+        assert_eq!(scoring.name, "lenient");
+        assert!(!scoring.zero_on_compile_failure);
+        assert_eq!(scoring.weights.tests, 0.9);
+        assert_eq!(scoring.weights.compilation, 0.3);
+
+        let warnings = validate_eval_set(&set);
+        assert!(warnings
+            .iter()
+            .any(|w| w.case_id.is_none() && w.message.contains("sum to 1.450")));
+    }
+
+    #[test]
+    fn parse_performance_expectation() {
+        let toml = r#"
+[eval_set]
+id = "perf"
+name = "Perf"
+
+[[cases]]
+id = "sort"
+name = "Sort"
+prompt = "Write `pub fn sort(v: &mut [u64])` in O(n log n)."
+
+[cases.expectations]
+test_file = '#[test] fn t() {}'
+reference_solution = "pub fn sort(v: &mut [u64]) { v.sort_unstable() }"
+
+[cases.expectations.performance]
+bench = '''
+fn setup(n: usize) -> Vec<u64> { (0..n as u64).rev().collect() }
+fn run(mut v: Vec<u64>) -> Vec<u64> { sort(&mut v); v }
+'''
+sizes = [1000, 100000]
+max_ratio = 3.0
+
+[[cases]]
+id = "unbounded"
+name = "Unbounded"
+prompt = "p"
+
+[cases.expectations]
+test_file = '#[test] fn t() {}'
+
+[cases.expectations.performance]
+bench = "fn go() {}"
+max_ratio = 2.0
+"#;
+        let set = parse_eval_set_str(toml, Path::new("perf.toml")).unwrap();
+        let perf = set.cases[0].expectations.performance.as_ref().unwrap();
+        assert_eq!(perf.sizes, vec![1000, 100000]);
+        assert_eq!(perf.warmup, 3);
+        assert_eq!(perf.repetitions, 10);
+        assert_eq!(perf.max_ratio, Some(3.0));
+        assert!(set.cases[0].expectations.reference_solution.is_some());
+
+        let warnings = validate_eval_set(&set);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings
+            .iter()
+            .all(|w| w.case_id.as_deref() == Some("unbounded")));
+        assert!(warnings.iter().any(|w| w.message.contains("fn setup")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.message.contains("reference_solution")));
+    }
+
     #[test]
     fn load_directory() {
         let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,531 @@
+//! Dry-run planning.
+//!
+//! Works out what a run would evaluate (eval sets × cases × models ×
+//! attempts) and roughly what it would cost, without calling any provider.
+//! Input tokens are estimated from the size of each case's prompt and
+//! context; completion tokens are an assumed length per case, capped at the
+//! case's `max_tokens`. Costs come from the providers' [`ModelInfo`] pricing.
+//! With a judge, every generation of a case with a rubric is assumed to be
+//! graded, so its estimate is an upper bound.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::ModelSpec;
+use crate::judge::{self, JUDGE_MAX_TOKENS};
+use crate::model::{EvalCase, EvalSet};
+use crate::traits::{LlmProvider, ModelInfo, DEFAULT_SYSTEM_PROMPT};
+
+/// Rough number of characters per token for English text and code.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Estimated token count of `text`.
+pub fn estimate_tokens(text: &str) -> u64 {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN) as u64
+}
+
+/// Settings the estimates depend on.
+#[derive(Debug, Clone)]
+pub struct PlanConfig {
+    /// Attempts per (case, model) pair: the largest Pass@k.
+    pub attempts: u32,
+    /// Assumed completion length per generation, in tokens.
+    pub completion_tokens: u32,
+    /// Generation token limit for cases that don't set `max_tokens`.
+    pub max_tokens: u32,
+    /// System prompt override (`None` uses the default one).
+    pub system_prompt: Option<String>,
+    /// Model that grades cases with a judge rubric.
+    pub judge: Option<ModelSpec>,
+}
+
+impl Default for PlanConfig {
+    fn default() -> Self {
+        Self {
+            attempts: 1,
+            completion_tokens: 512,
+            max_tokens: 4096,
+            system_prompt: None,
+            judge: None,
+        }
+    }
+}
+
+/// What a run would evaluate, and its estimated size and cost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunPlan {
+    /// The evaluation matrix per eval set.
+    pub sets: Vec<SetPlan>,
+    /// Estimated usage and cost per model.
+    pub models: Vec<ModelPlan>,
+    /// Estimated usage and cost of the judge.
+    #[serde(default)]
+    pub judge: Option<ModelPlan>,
+    /// Things that would make the run fail or mislead.
+    pub problems: Vec<PlanProblem>,
+}
+
+/// The evaluation matrix of one eval set, after filters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetPlan {
+    /// Eval set ID.
+    pub id: String,
+    /// Cases that would run.
+    pub cases: usize,
+    /// Models evaluated.
+    pub models: usize,
+    /// Attempts per (case, model) pair.
+    pub attempts: u32,
+    /// Total generations: cases × models × attempts.
+    pub generations: usize,
+}
+
+/// Estimated usage of one model across the run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPlan {
+    /// Provider name.
+    pub provider: String,
+    /// Model identifier.
+    pub model: String,
+    /// Generations requested from the model.
+    pub generations: usize,
+    /// Estimated prompt tokens.
+    pub input_tokens: u64,
+    /// Assumed completion tokens.
+    pub output_tokens: u64,
+    /// Estimated cost in USD (`None` if the provider doesn't price the model).
+    pub cost_usd: Option<f64>,
+}
+
+/// A problem found while planning.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlanProblem {
+    /// No provider is configured under this name.
+    UnknownProvider { provider: String },
+    /// The provider lists its models, and this isn't one of them.
+    UnknownModel { provider: String, model: String },
+    /// A case's prompt plus the assumed completion doesn't fit the model's
+    /// context window.
+    ContextExceeded {
+        case_id: String,
+        model: String,
+        tokens: u64,
+        max_context: u32,
+    },
+}
+
+impl std::fmt::Display for PlanProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanProblem::UnknownProvider { provider } => {
+                write!(f, "provider '{provider}' is not configured")
+            }
+            PlanProblem::UnknownModel { provider, model } => {
+                write!(f, "model '{model}' is not among {provider}'s available models")
+            }
+            PlanProblem::ContextExceeded {
+                case_id,
+                model,
+                tokens,
+                max_context,
+            } => write!(
+                f,
+                "case '{case_id}' needs ~{tokens} tokens, more than {model}'s {max_context}-token context"
+            ),
+        }
+    }
+}
+
+impl RunPlan {
+    /// Total generations across all eval sets.
+    pub fn generations(&self) -> usize {
+        self.sets.iter().map(|s| s.generations).sum()
+    }
+
+    /// Estimated cost of the models (and judge) that have pricing.
+    pub fn cost_usd(&self) -> f64 {
+        self.models
+            .iter()
+            .chain(&self.judge)
+            .filter_map(|m| m.cost_usd)
+            .fold(0.0, |total, cost| total + cost)
+    }
+
+    /// Whether some model's cost is unknown, so `cost_usd` is a lower bound.
+    pub fn cost_incomplete(&self) -> bool {
+        self.models
+            .iter()
+            .chain(&self.judge)
+            .any(|m| m.cost_usd.is_none())
+    }
+}
+
+/// Plan a run of `models` over `eval_sets`, looking models up in
+/// `providers` without calling them.
+pub fn plan_run(
+    eval_sets: &[EvalSet],
+    models: &[ModelSpec],
+    providers: &HashMap<String, Arc<dyn LlmProvider>>,
+    config: &PlanConfig,
+) -> RunPlan {
+    let attempts = config.attempts.max(1);
+    let mut problems = Vec::new();
+
+    let sets = eval_sets
+        .iter()
+        .map(|set| SetPlan {
+            id: set.id.clone(),
+            cases: set.cases.len(),
+            models: models.len(),
+            attempts,
+            generations: set.cases.len() * models.len() * attempts as usize,
+        })
+        .collect();
+
+    let mut model_plans = Vec::with_capacity(models.len());
+    for spec in models {
+        let info = model_info(spec, providers, &mut problems);
+        let mut plan = ModelPlan::new(spec);
+        for case in eval_sets.iter().flat_map(|s| &s.cases) {
+            let input = input_tokens(case, config);
+            let output = output_tokens(case, config);
+            check_context(&info, case, spec, input + output, &mut problems);
+            plan.add(attempts, input, output);
+        }
+        plan.price(info.as_ref());
+        model_plans.push(plan);
+    }
+
+    // Each model's attempts at a rubric case are graded separately
+    let judge = config.judge.as_ref().map(|spec| {
+        let info = model_info(spec, providers, &mut problems);
+        let mut plan = ModelPlan::new(spec);
+        let graded = attempts * models.len() as u32;
+        for set in eval_sets {
+            for case in &set.cases {
+                let Some(rubric) = case
+                    .expectations
+                    .judge
+                    .as_ref()
+                    .filter(|r| !r.criteria.is_empty())
+                else {
+                    continue;
+                };
+                let language = case.language.unwrap_or(set.default_language);
+                let input = estimate_tokens(judge::SYSTEM_PROMPT)
+                    + estimate_tokens(&judge::build_prompt(&case.prompt, "", language, rubric))
+                    + output_tokens(case, config);
+                let output = u64::from(config.completion_tokens.min(JUDGE_MAX_TOKENS));
+                check_context(&info, case, spec, input + output, &mut problems);
+                plan.add(graded, input, output);
+            }
+        }
+        plan.price(info.as_ref());
+        plan
+    });
+
+    RunPlan {
+        sets,
+        models: model_plans,
+        judge,
+        problems,
+    }
+}
+
+impl ModelPlan {
+    fn new(spec: &ModelSpec) -> Self {
+        Self {
+            provider: spec.provider.clone(),
+            model: spec.model.clone(),
+            generations: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cost_usd: None,
+        }
+    }
+
+    /// Count `requests` requests of `input` prompt and `output` completion tokens.
+    fn add(&mut self, requests: u32, input: u64, output: u64) {
+        self.generations += requests as usize;
+        self.input_tokens += input * u64::from(requests);
+        self.output_tokens += output * u64::from(requests);
+    }
+
+    fn price(&mut self, info: Option<&ModelInfo>) {
+        self.cost_usd = info.map(|m| {
+            self.input_tokens as f64 / 1000.0 * m.cost_per_1k_input
+                + self.output_tokens as f64 / 1000.0 * m.cost_per_1k_output
+        });
+    }
+}
+
+/// Look `spec` up among the providers, recording a problem if it's unknown.
+fn model_info(
+    spec: &ModelSpec,
+    providers: &HashMap<String, Arc<dyn LlmProvider>>,
+    problems: &mut Vec<PlanProblem>,
+) -> Option<ModelInfo> {
+    match providers.get(&spec.provider) {
+        None => {
+            let problem = PlanProblem::UnknownProvider {
+                provider: spec.provider.clone(),
+            };
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
+            None
+        }
+        Some(provider) => {
+            let available = provider.available_models();
+            let info = available.iter().find(|m| m.id == spec.model).cloned();
+            // An empty list means the provider can't tell (e.g. Ollama)
+            let problem = PlanProblem::UnknownModel {
+                provider: spec.provider.clone(),
+                model: spec.model.clone(),
+            };
+            if info.is_none() && !available.is_empty() && !problems.contains(&problem) {
+                problems.push(problem);
+            }
+            info
+        }
+    }
+}
+
+/// Record a problem if a request of `tokens` doesn't fit the model's context.
+fn check_context(
+    info: &Option<ModelInfo>,
+    case: &EvalCase,
+    spec: &ModelSpec,
+    tokens: u64,
+    problems: &mut Vec<PlanProblem>,
+) {
+    if let Some(ModelInfo { max_context, .. }) = info {
+        // Zero means the provider doesn't know the window
+        if *max_context > 0 && tokens > u64::from(*max_context) {
+            problems.push(PlanProblem::ContextExceeded {
+                case_id: case.id.clone(),
+                model: spec.model.clone(),
+                tokens,
+                max_context: *max_context,
+            });
+        }
+    }
+}
+
+/// Assumed completion tokens of one generation for `case`.
+fn output_tokens(case: &EvalCase, config: &PlanConfig) -> u64 {
+    u64::from(
+        config
+            .completion_tokens
+            .min(case.max_tokens.unwrap_or(config.max_tokens)),
+    )
+}
+
+/// Estimated prompt tokens of one generation for `case`.
+///
+/// Completion (FIM) requests carry no system prompt.
+fn input_tokens(case: &EvalCase, config: &PlanConfig) -> u64 {
+    match &case.fim {
+        Some(fim) => estimate_tokens(&fim.prefix) + estimate_tokens(&fim.suffix),
+        None => {
+            let system = config
+                .system_prompt
+                .as_deref()
+                .unwrap_or(DEFAULT_SYSTEM_PROMPT);
+            estimate_tokens(system)
+                + estimate_tokens(&case.prompt)
+                + case
+                    .context
+                    .iter()
+                    .map(|f| estimate_tokens(&f.path) + estimate_tokens(&f.content))
+                    .sum::<u64>()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Expectations, FimSpec, JudgeCriterion, JudgeRubric, Language};
+    use crate::traits::{GenerateRequest, GenerateResponse};
+
+    struct PricedProvider {
+        models: Vec<ModelInfo>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for PricedProvider {
+        fn name(&self) -> &str {
+            "priced"
+        }
+
+        async fn generate(&self, _: &GenerateRequest) -> anyhow::Result<GenerateResponse> {
+            unreachable!("planning never calls the provider")
+        }
+
+        fn available_models(&self) -> Vec<ModelInfo> {
+            self.models.clone()
+        }
+    }
+
+    fn make_case(id: &str, prompt: &str) -> EvalCase {
+        EvalCase {
+            id: id.into(),
+            name: id.into(),
+            description: String::new(),
+            prompt: prompt.into(),
+            language: Some(Language::Rust),
+            context: vec![],
+            expectations: Expectations::default(),
+            tags: vec![],
+            difficulty: None,
+            dependencies: vec![],
+            timeout_secs: None,
+            max_tokens: None,
+            fixture: None,
+            fim: None,
+        }
+    }
+
+    fn make_set(cases: Vec<EvalCase>) -> EvalSet {
+        EvalSet {
+            id: "set".into(),
+            name: "Set".into(),
+            description: String::new(),
+            cases,
+            default_language: Language::Rust,
+            default_timeout_secs: 60,
+            scoring: None,
+        }
+    }
+
+    fn spec(provider: &str, model: &str) -> ModelSpec {
+        ModelSpec {
+            provider: provider.into(),
+            model: model.into(),
+        }
+    }
+
+    fn providers(max_context: u32) -> HashMap<String, Arc<dyn LlmProvider>> {
+        let provider = PricedProvider {
+            models: vec![ModelInfo {
+                id: "m".into(),
+                name: "M".into(),
+                provider: "p".into(),
+                max_context,
+                cost_per_1k_input: 1.0,
+                cost_per_1k_output: 2.0,
+            }],
+        };
+        HashMap::from([("p".to_string(), Arc::new(provider) as Arc<dyn LlmProvider>)])
+    }
+
+    #[test]
+    fn estimates_matrix_tokens_and_cost() {
+        let config = PlanConfig {
+            attempts: 5,
+            completion_tokens: 100,
+            system_prompt: Some(String::new()),
+            ..PlanConfig::default()
+        };
+        let mut short = make_case("short", &"x".repeat(400));
+        short.max_tokens = Some(40);
+        let set = make_set(vec![make_case("a", &"x".repeat(800)), short]);
+
+        let plan = plan_run(&[set], &[spec("p", "m")], &providers(100_000), &config);
+
+        assert_eq!(plan.generations(), 10);
+        assert!(plan.problems.is_empty());
+        let model = &plan.models[0];
+        // (200 + 100) + (100 + 40) tokens per attempt, five attempts each
+        assert_eq!(model.input_tokens, 5 * 300);
+        assert_eq!(model.output_tokens, 5 * 140);
+        assert!((plan.cost_usd() - (1.5 + 1.4)).abs() < 1e-9);
+        assert!(!plan.cost_incomplete());
+    }
+
+    #[test]
+    fn flags_unknown_providers_models_and_oversized_prompts() {
+        let set = make_set(vec![make_case("big", &"x".repeat(4_000))]);
+        let models = [spec("p", "m"), spec("p", "nope"), spec("missing", "m")];
+
+        let plan = plan_run(&[set], &models, &providers(1_000), &PlanConfig::default());
+
+        assert_eq!(plan.problems.len(), 3);
+        assert!(matches!(
+            &plan.problems[0],
+            PlanProblem::ContextExceeded { case_id, max_context: 1_000, .. } if case_id == "big"
+        ));
+        assert_eq!(
+            plan.problems[1].to_string(),
+            "model 'nope' is not among p's available models"
+        );
+        assert_eq!(
+            plan.problems[2],
+            PlanProblem::UnknownProvider {
+                provider: "missing".into()
+            }
+        );
+        assert!(plan.cost_incomplete());
+    }
+
+    #[test]
+    fn fim_cases_have_no_system_prompt() {
+        let mut case = make_case("fim", "ignored");
+        case.fim = Some(FimSpec {
+            prefix: "x".repeat(40),
+            suffix: "x".repeat(20),
+        });
+        assert_eq!(input_tokens(&case, &PlanConfig::default()), 15);
+    }
+
+    #[test]
+    fn judge_grades_every_rubric_generation() {
+        let mut graded = make_case("graded", "Write a parser");
+        graded.expectations.judge = Some(JudgeRubric {
+            criteria: vec![JudgeCriterion {
+                name: "naming".into(),
+                description: "Names are descriptive".into(),
+                weight: 1.0,
+            }],
+            ..JudgeRubric::default()
+        });
+        let set = make_set(vec![graded, make_case("plain", "Write a lexer")]);
+        let config = PlanConfig {
+            attempts: 3,
+            completion_tokens: 100,
+            judge: Some(spec("p", "m")),
+            ..PlanConfig::default()
+        };
+
+        let plan = plan_run(
+            std::slice::from_ref(&set),
+            &[spec("p", "m"), spec("p", "m")],
+            &providers(100_000),
+            &config,
+        );
+        let judge = plan.judge.as_ref().unwrap();
+        // Both models' three attempts at the one rubric case
+        assert_eq!(judge.generations, 6);
+        assert_eq!(judge.output_tokens, 6 * 100);
+        assert!(judge.input_tokens > 6 * 100);
+        let models: f64 = plan.models.iter().filter_map(|m| m.cost_usd).sum();
+        assert!(plan.cost_usd() > models);
+
+        let config = PlanConfig {
+            judge: Some(spec("missing", "judge")),
+            ..config
+        };
+        let plan = plan_run(&[set], &[spec("p", "m")], &providers(100_000), &config);
+        assert_eq!(
+            plan.problems,
+            vec![PlanProblem::UnknownProvider {
+                provider: "missing".into()
+            }]
+        );
+        assert!(plan.cost_incomplete());
+    }
+}
@@ -1,15 +1,24 @@
 //! Eval report types with JSON persistence and regression detection.
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use crate::model::Expectations;
+use crate::budget::BudgetSummary;
+use crate::model::{EvalCase, EvalSet, Expectations};
 use crate::results::{EvalResult, Score};
+use crate::scoring::{DefaultScorer, Scorer, ScoringProfile};
+use crate::significance::{
+    cliffs_delta, holm_adjust, paired_effect_size, paired_permutation_test, permutation_test,
+    SignificanceConfig,
+};
 use crate::statistics::AggregateStats;
+use crate::taxonomy::{failure_modes, ErrorCategory};
 
 /// A complete eval report.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +35,23 @@ pub struct EvalReport {
     pub results: Vec<EvalResult>,
     /// Aggregate statistics.
     pub aggregate: AggregateStats,
+    /// Scoring profile the results were scored with (`None` for custom
+    /// scorers and older reports).
+    #[serde(default)]
+    pub scoring: Option<ScoringProfile>,
+    /// Snapshot of each case's definition, keyed by case ID, so reports can
+    /// be rescored and compared without the eval set (empty for older
+    /// reports).
+    #[serde(default)]
+    pub cases: BTreeMap<String, CaseSnapshot>,
+    /// ID of the report whose generations these results re-executed (`None`
+    /// unless produced by `forgetest rescore`).
+    #[serde(default)]
+    pub rescored_from: Option<Uuid>,
+    /// Spending limits and what the run spent (`None` without a budget). If
+    /// a limit stopped the run, the results are partial.
+    #[serde(default)]
+    pub budget: Option<BudgetSummary>,
     /// Total wall-clock duration in milliseconds.
     pub duration_ms: u64,
 }
@@ -38,6 +64,70 @@ pub struct EvalSetSummary {
     pub case_count: usize,
 }
 
+/// The parts of a case definition that affect scoring and comparison.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseSnapshot {
+    /// The case's expectations, without test and reference code (covered by
+    /// `test_hash`).
+    pub expectations: Expectations,
+    /// SHA-256 of everything the model sees: prompt, context files and FIM
+    /// prefix/suffix.
+    pub prompt_hash: String,
+    /// SHA-256 of the test file and reference solution (`None` if the case
+    /// has neither).
+    #[serde(default)]
+    pub test_hash: Option<String>,
+}
+
+impl CaseSnapshot {
+    /// Snapshot a case definition.
+    pub fn from_case(case: &EvalCase) -> Self {
+        let mut prompt = vec![case.prompt.as_str()];
+        for file in &case.context {
+            prompt.extend([file.path.as_str(), file.content.as_str()]);
+        }
+        if let Some(fim) = &case.fim {
+            prompt.extend([fim.prefix.as_str(), fim.suffix.as_str()]);
+        }
+
+        let exp = &case.expectations;
+        let test_hash = (exp.test_file.is_some() || exp.reference_solution.is_some()).then(|| {
+            sha256_hex(&[
+                exp.test_file.as_deref().unwrap_or_default(),
+                exp.reference_solution.as_deref().unwrap_or_default(),
+            ])
+        });
+
+        let mut expectations = exp.clone();
+        expectations.test_file = None;
+        expectations.reference_solution = None;
+
+        Self {
+            expectations,
+            prompt_hash: sha256_hex(&prompt),
+            test_hash,
+        }
+    }
+
+    /// Whether the two snapshots describe the same case.
+    pub fn same_definition(&self, other: &CaseSnapshot) -> bool {
+        self.prompt_hash == other.prompt_hash
+            && self.test_hash == other.test_hash
+            && serde_json::to_value(&self.expectations).ok()
+                == serde_json::to_value(&other.expectations).ok()
+    }
+}
+
+/// Hex SHA-256 over `parts`, each terminated by a NUL byte.
+fn sha256_hex(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
 impl EvalReport {
     /// Save the report as JSON to a file.
     pub fn save_json(&self, path: &Path) -> Result<()> {
@@ -59,33 +149,56 @@ impl EvalReport {
         Ok(report)
     }
 
+    /// IDs of cases whose prompt in `eval_set` differs from the one this
+    /// report's generations were made for, so those generations no longer
+    /// answer the case. Always empty for reports without case snapshots.
+    pub fn changed_prompts(&self, eval_set: &EvalSet) -> Vec<String> {
+        eval_set
+            .cases
+            .iter()
+            .filter(|case| {
+                self.cases.get(&case.id).is_some_and(|snapshot| {
+                    snapshot.prompt_hash != CaseSnapshot::from_case(case).prompt_hash
+                })
+            })
+            .map(|case| case.id.clone())
+            .collect()
+    }
+
     /// Compare this report against a baseline to detect regressions.
+    ///
+    /// Scores with this report's scoring profile, or the default one.
     pub fn compare(&self, baseline: &EvalReport, threshold: f64) -> RegressionReport {
-        use std::collections::HashMap;
-
-        let defaults = Expectations::default();
+        let scorer = DefaultScorer::from_profile(self.scoring.clone().unwrap_or_default());
+        self.compare_with(baseline, threshold, &scorer)
+    }
 
-        // Build maps of (case_id, model) → best overall score using Score::compute
-        let score_map = |report: &EvalReport| -> HashMap<(String, String), f64> {
-            let mut map: HashMap<(String, String), f64> = HashMap::new();
-            for r in &report.results {
-                let score = Score::compute(r, &defaults);
-                let key = (r.case_id.clone(), r.model.clone());
-                let entry = map.entry(key).or_insert(0.0);
-                if score.overall > *entry {
-                    *entry = score.overall;
-                }
-            }
-            map
+    /// Compare against a baseline, scoring both reports with `scorer`.
+    ///
+    /// A (case, model) pair regresses when its best score drops by more than
+    /// `threshold`. Scores recorded on results by the same scorer are reused;
+    /// other results are rescored with the expectations snapshotted in their
+    /// report (default expectations for older reports without snapshots).
+    /// Cases whose definition changed are listed in `changed_cases` instead
+    /// of being compared.
+    pub fn compare_with(
+        &self,
+        baseline: &EvalReport,
+        threshold: f64,
+        scorer: &dyn Scorer,
+    ) -> RegressionReport {
+        let best = |scores: HashMap<(String, String), Vec<f64>>| -> HashMap<_, f64> {
+            scores
+                .into_iter()
+                .map(|(key, attempts)| (key, attempts.into_iter().fold(0.0, f64::max)))
+                .collect()
         };
-
-        let baseline_scores = score_map(baseline);
-        let current_scores = score_map(self);
+        let baseline_scores = best(baseline.attempt_scores(self, scorer));
+        let current_scores = best(self.attempt_scores(baseline, scorer));
 
         let mut regressions = Vec::new();
         let mut improvements = Vec::new();
         let mut unchanged = 0usize;
-        let mut new_cases = 0usize;
 
         for (key, &current) in &current_scores {
             if let Some(&baseline_val) = baseline_scores.get(key) {
@@ -97,6 +210,8 @@ impl EvalReport {
                         baseline_score: baseline_val,
                         current_score: current,
                         delta,
+                        p_value: None,
+                        effect_size: None,
                     });
                 } else if delta > threshold {
                     improvements.push(Improvement {
@@ -105,30 +220,291 @@ impl EvalReport {
                         baseline_score: baseline_val,
                         current_score: current,
                         delta,
+                        p_value: None,
+                        effect_size: None,
                     });
                 } else {
                     unchanged += 1;
                 }
-            } else {
-                new_cases += 1;
             }
         }
 
-        let removed_cases = baseline_scores
+        RegressionReport {
+            regressions,
+            improvements,
+            unchanged,
+            new_cases: count_missing(&current_scores, &baseline_scores),
+            removed_cases: count_missing(&baseline_scores, &current_scores),
+            scoring_mismatch: self.scoring_mismatch(baseline),
+            changed_cases: self.changed_cases(baseline),
+            significance: None,
+            models: vec![],
+            failure_modes: self.failure_mode_changes(baseline),
+        }
+    }
+
+    /// Compare against a baseline with significance tests, scoring with this
+    /// report's scoring profile (or the default one).
+    pub fn compare_significance(
+        &self,
+        baseline: &EvalReport,
+        threshold: f64,
+        config: &SignificanceConfig,
+    ) -> RegressionReport {
+        let scorer = DefaultScorer::from_profile(self.scoring.clone().unwrap_or_default());
+        self.compare_significance_with(baseline, threshold, config, &scorer)
+    }
+
+    /// Compare against a baseline with significance tests, scoring both
+    /// reports with `scorer`.
+    ///
+    /// Each (case, model) pair's attempts are compared with a permutation
+    /// test, with p-values Holm-adjusted across pairs; each model's per-case
+    /// mean scores are compared with a paired sign-flip test. A change counts
+    /// when it is significant at `config.alpha` and its mean delta exceeds
+    /// `threshold`. Scores on the reported changes are means over attempts.
+    pub fn compare_significance_with(
+        &self,
+        baseline: &EvalReport,
+        threshold: f64,
+        config: &SignificanceConfig,
+        scorer: &dyn Scorer,
+    ) -> RegressionReport {
+        let baseline_scores = baseline.attempt_scores(self, scorer);
+        let current_scores = self.attempt_scores(baseline, scorer);
+        let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len().max(1) as f64;
+
+        let mut paired: Vec<&(String, String)> = current_scores
             .keys()
-            .filter(|k| !current_scores.contains_key(k))
-            .count();
+            .filter(|key| baseline_scores.contains_key(*key))
+            .collect();
+        paired.sort();
+
+        let raw_p: Vec<f64> = paired
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                permutation_test(
+                    &baseline_scores[*key],
+                    &current_scores[*key],
+                    config.resamples,
+                    config.seed.wrapping_add(i as u64),
+                )
+            })
+            .collect();
+        let adjusted_p = holm_adjust(&raw_p);
+
+        let mut regressions = Vec::new();
+        let mut improvements = Vec::new();
+        let mut unchanged = 0usize;
+        let mut case_deltas: BTreeMap<&str, Vec<(f64, f64)>> = BTreeMap::new();
+
+        for (key, p_value) in paired.iter().zip(adjusted_p) {
+            let (before, after) = (&baseline_scores[*key], &current_scores[*key]);
+            let (baseline_score, current_score) = (mean(before), mean(after));
+            let delta = current_score - baseline_score;
+            case_deltas
+                .entry(key.1.as_str())
+                .or_default()
+                .push((baseline_score, current_score));
+
+            if p_value >= config.alpha || delta.abs() <= threshold {
+                unchanged += 1;
+                continue;
+            }
+            let effect_size = Some(cliffs_delta(before, after));
+            if delta < 0.0 {
+                regressions.push(Regression {
+                    case_id: key.0.clone(),
+                    model: key.1.clone(),
+                    baseline_score,
+                    current_score,
+                    delta,
+                    p_value: Some(p_value),
+                    effect_size,
+                });
+            } else {
+                improvements.push(Improvement {
+                    case_id: key.0.clone(),
+                    model: key.1.clone(),
+                    baseline_score,
+                    current_score,
+                    delta,
+                    p_value: Some(p_value),
+                    effect_size,
+                });
+            }
+        }
+
+        let models = case_deltas
+            .into_iter()
+            .enumerate()
+            .map(|(i, (model, cases))| {
+                let diffs: Vec<f64> = cases.iter().map(|(b, c)| c - b).collect();
+                let baseline_score = mean(&cases.iter().map(|(b, _)| *b).collect::<Vec<_>>());
+                let current_score = mean(&cases.iter().map(|(_, c)| *c).collect::<Vec<_>>());
+                let delta = current_score - baseline_score;
+                let p_value = paired_permutation_test(
+                    &diffs,
+                    config.resamples,
+                    config.seed.wrapping_add((paired.len() + i) as u64),
+                );
+                ModelComparison {
+                    model: model.to_string(),
+                    cases: cases.len(),
+                    baseline_score,
+                    current_score,
+                    delta,
+                    p_value,
+                    effect_size: paired_effect_size(&diffs),
+                    significant: p_value < config.alpha && delta.abs() > threshold,
+                }
+            })
+            .collect();
 
         RegressionReport {
             regressions,
             improvements,
             unchanged,
-            new_cases,
-            removed_cases,
+            new_cases: count_missing(&current_scores, &baseline_scores),
+            removed_cases: count_missing(&baseline_scores, &current_scores),
+            scoring_mismatch: self.scoring_mismatch(baseline),
+            changed_cases: self.changed_cases(baseline),
+            significance: Some(config.clone()),
+            models,
+            failure_modes: self.failure_mode_changes(baseline),
+        }
+    }
+
+    /// Changes in each model's failure modes since `baseline`, over the
+    /// (case, model) pairs both reports share and whose case didn't change.
+    fn failure_mode_changes(&self, baseline: &EvalReport) -> Vec<FailureModeChange> {
+        let changed = self.changed_cases(baseline);
+        let pairs = |report: &EvalReport| -> HashSet<(String, String)> {
+            report
+                .results
+                .iter()
+                .filter(|r| !changed.contains(&r.case_id))
+                .map(|r| (r.case_id.clone(), r.model.clone()))
+                .collect()
+        };
+        let shared: HashSet<_> = pairs(self)
+            .intersection(&pairs(baseline))
+            .cloned()
+            .collect();
+        fn by_model<'a>(
+            report: &'a EvalReport,
+            shared: &HashSet<(String, String)>,
+        ) -> BTreeMap<String, Vec<&'a EvalResult>> {
+            let mut map: BTreeMap<String, Vec<&EvalResult>> = BTreeMap::new();
+            for r in &report.results {
+                if shared.contains(&(r.case_id.clone(), r.model.clone())) {
+                    map.entry(r.model.clone()).or_default().push(r);
+                }
+            }
+            map
+        }
+        let (before, after) = (by_model(baseline, &shared), by_model(self, &shared));
+
+        let mut changes = vec![];
+        for (model, current_results) in &after {
+            let Some(baseline_results) = before.get(model) else {
+                continue;
+            };
+            let (old, new) = (
+                failure_modes(baseline_results.iter().copied()),
+                failure_modes(current_results.iter().copied()),
+            );
+            for category in ErrorCategory::ALL {
+                let (b, c) = (
+                    old.get(&category).copied().unwrap_or(0),
+                    new.get(&category).copied().unwrap_or(0),
+                );
+                let baseline_rate = b as f64 / baseline_results.len() as f64;
+                let current_rate = c as f64 / current_results.len() as f64;
+                if baseline_rate != current_rate {
+                    changes.push(FailureModeChange {
+                        model: model.clone(),
+                        category,
+                        baseline: b,
+                        current: c,
+                        baseline_rate,
+                        current_rate,
+                    });
+                }
+            }
+        }
+        changes
+    }
+
+    /// Overall score of every attempt, keyed by (case_id, model).
+    ///
+    /// Pairs whose case definition changed since `other` are left out.
+    fn attempt_scores(
+        &self,
+        other: &EvalReport,
+        scorer: &dyn Scorer,
+    ) -> HashMap<(String, String), Vec<f64>> {
+        let changed = self.changed_cases(other);
+        let mut map: HashMap<(String, String), Vec<f64>> = HashMap::new();
+        for r in &self.results {
+            if changed.contains(&r.case_id) {
+                continue;
+            }
+            map.entry((r.case_id.clone(), r.model.clone()))
+                .or_default()
+                .push(self.score_result(r, scorer).overall);
+        }
+        map
+    }
+
+    /// Expectations of a case from its snapshot, or defaults for older
+    /// reports.
+    pub fn case_expectations(&self, case_id: &str) -> Expectations {
+        self.cases
+            .get(case_id)
+            .map(|case| case.expectations.clone())
+            .unwrap_or_default()
+    }
+
+    /// Score a result of this report with `scorer`: the recorded score if
+    /// `scorer` produced it, otherwise a rescore against the case's
+    /// snapshotted expectations.
+    pub fn score_result(&self, result: &EvalResult, scorer: &dyn Scorer) -> Score {
+        match &result.score {
+            Some(score) if score.scorer == scorer.id() => score.clone(),
+            _ => scorer.score(result, &self.case_expectations(&result.case_id)),
+        }
+    }
+
+    /// IDs of cases whose snapshot differs between the two reports.
+    pub fn changed_cases(&self, other: &EvalReport) -> Vec<String> {
+        self.cases
+            .iter()
+            .filter(|(id, case)| {
+                other
+                    .cases
+                    .get(*id)
+                    .is_some_and(|before| !before.same_definition(case))
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Both reports record scoring profiles and they differ.
+    fn scoring_mismatch(&self, baseline: &EvalReport) -> bool {
+        match (&baseline.scoring, &self.scoring) {
+            (Some(before), Some(after)) => before != after,
+            _ => false,
         }
     }
 }
 
+/// Number of keys in `a` that are not in `b`.
+fn count_missing<K: Eq + std::hash::Hash, V, W>(a: &HashMap<K, V>, b: &HashMap<K, W>) -> usize {
+    a.keys().filter(|k| !b.contains_key(*k)).count()
+}
+
 /// Result of comparing two reports.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegressionReport {
@@ -142,6 +518,63 @@ pub struct RegressionReport {
     pub new_cases: usize,
     /// Cases in baseline but not current.
     pub removed_cases: usize,
+    /// The reports were scored with different scoring profiles, so recorded
+    /// scores may not be comparable.
+    #[serde(default)]
+    pub scoring_mismatch: bool,
+    /// Cases whose definition (prompt, tests or expectations) changed between
+    /// the reports. Their scores aren't compared.
+    #[serde(default)]
+    pub changed_cases: Vec<String>,
+    /// Settings of the significance tests (`None` for threshold comparison).
+    #[serde(default)]
+    pub significance: Option<SignificanceConfig>,
+    /// Model-level comparisons (significance comparison only).
+    #[serde(default)]
+    pub models: Vec<ModelComparison>,
+    /// Per-model changes in compiler error categories.
+    #[serde(default)]
+    pub failure_modes: Vec<FailureModeChange>,
+}
+
+/// A change in how often a model's attempts fail to compile with one
+/// category of error, over the (case, model) pairs both reports share.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureModeChange {
+    pub model: String,
+    pub category: ErrorCategory,
+    /// Attempts with an error of this category in the baseline.
+    pub baseline: usize,
+    /// Attempts with an error of this category in the current report.
+    pub current: usize,
+    /// Share of the model's baseline attempts with this category.
+    pub baseline_rate: f64,
+    /// Share of the model's current attempts with this category.
+    pub current_rate: f64,
+}
+
+impl FailureModeChange {
+    /// Change in the share of attempts, `current_rate - baseline_rate`.
+    pub fn delta(&self) -> f64 {
+        self.current_rate - self.baseline_rate
+    }
+}
+
+/// A model's mean score over the cases both reports share.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelComparison {
+    pub model: String,
+    /// Number of cases compared.
+    pub cases: usize,
+    pub baseline_score: f64,
+    pub current_score: f64,
+    pub delta: f64,
+    /// Paired sign-flip test over cases.
+    pub p_value: f64,
+    /// Share of cases that improved minus share that got worse (-1.0 to 1.0).
+    pub effect_size: f64,
+    /// Significant at the configured alpha and larger than the threshold.
+    pub significant: bool,
 }
 
 /// A detected regression.
@@ -152,6 +585,12 @@ pub struct Regression {
     pub baseline_score: f64,
     pub current_score: f64,
     pub delta: f64,
+    /// Holm-adjusted permutation test p-value (significance comparison only).
+    #[serde(default)]
+    pub p_value: Option<f64>,
+    /// Cliff's delta between the attempts (significance comparison only).
+    #[serde(default)]
+    pub effect_size: Option<f64>,
 }
 
 /// A detected improvement.
@@ -162,6 +601,12 @@ pub struct Improvement {
     pub baseline_score: f64,
     pub current_score: f64,
     pub delta: f64,
+    /// Holm-adjusted permutation test p-value (significance comparison only).
+    #[serde(default)]
+    pub p_value: Option<f64>,
+    /// Cliff's delta between the attempts (significance comparison only).
+    #[serde(default)]
+    pub effect_size: Option<f64>,
 }
 
 impl RegressionReport {
@@ -169,6 +614,12 @@ impl RegressionReport {
     pub fn to_markdown(&self) -> String {
         let mut md = String::new();
 
+        if self.scoring_mismatch {
+            md.push_str(
+                "> **Warning:** the reports use different scoring profiles; deltas may reflect scoring changes.\n\n",
+            );
+        }
+
         md.push_str(&format!(
             "**Summary:** {} regressions, {} improvements, {} unchanged\n\n",
             self.regressions.len(),
@@ -176,45 +627,131 @@ impl RegressionReport {
             self.unchanged
         ));
 
+        if !self.changed_cases.is_empty() {
+            md.push_str("### Changed Cases\n\n");
+            md.push_str("These case definitions changed between the reports, so their scores were not compared:\n\n");
+            for id in &self.changed_cases {
+                md.push_str(&format!("- `{id}`\n"));
+            }
+            md.push('\n');
+        }
+
+        let tested = self.significance.is_some();
+        let stats = |p: Option<f64>, effect: Option<f64>| match (p, effect) {
+            (Some(p), Some(effect)) => format!(" {p:.4} | {effect:+.2} |"),
+            _ => String::new(),
+        };
+        let header = |title: &str| {
+            if tested {
+                format!(
+                    "### {title}\n\n| Case | Model | Baseline | Current | Delta | p | Effect |\n\
+                     |------|-------|----------|---------|-------|---|--------|\n"
+                )
+            } else {
+                format!(
+                    "### {title}\n\n| Case | Model | Baseline | Current | Delta |\n\
+                     |------|-------|----------|---------|-------|\n"
+                )
+            }
+        };
+
+        if !self.models.is_empty() {
+            md.push_str("### Models\n\n");
+            md.push_str(
+                "| Model | Cases | Baseline | Current | Delta | p | Effect | Significant |\n",
+            );
+            md.push_str(
+                "|-------|-------|----------|---------|-------|---|--------|-------------|\n",
+            );
+            for m in &self.models {
+                md.push_str(&format!(
+                    "| {} | {} | {:.1}% | {:.1}% | {:+.1}% | {:.4} | {:+.2} | {} |\n",
+                    m.model,
+                    m.cases,
+                    m.baseline_score * 100.0,
+                    m.current_score * 100.0,
+                    m.delta * 100.0,
+                    m.p_value,
+                    m.effect_size,
+                    if m.significant { "yes" } else { "no" }
+                ));
+            }
+            md.push('\n');
+        }
+
         if !self.regressions.is_empty() {
-            md.push_str("### Regressions\n\n");
-            md.push_str("| Case | Model | Baseline | Current | Delta |\n");
-            md.push_str("|------|-------|----------|---------|-------|\n");
+            md.push_str(&header("Regressions"));
             for r in &self.regressions {
                 md.push_str(&format!(
-                    "| {} | {} | {:.1}% | {:.1}% | {:.1}% |\n",
+                    "| {} | {} | {:.1}% | {:.1}% | {:.1}% |{}\n",
                     r.case_id,
                     r.model,
                     r.baseline_score * 100.0,
                     r.current_score * 100.0,
-                    r.delta * 100.0
+                    r.delta * 100.0,
+                    stats(r.p_value, r.effect_size)
                 ));
             }
             md.push('\n');
         }
 
         if !self.improvements.is_empty() {
-            md.push_str("### Improvements\n\n");
-            md.push_str("| Case | Model | Baseline | Current | Delta |\n");
-            md.push_str("|------|-------|----------|---------|-------|\n");
+            md.push_str(&header("Improvements"));
             for i in &self.improvements {
                 md.push_str(&format!(
-                    "| {} | {} | {:.1}% | {:.1}% | +{:.1}% |\n",
+                    "| {} | {} | {:.1}% | {:.1}% | +{:.1}% |{}\n",
                     i.case_id,
                     i.model,
                     i.baseline_score * 100.0,
                     i.current_score * 100.0,
-                    i.delta * 100.0
+                    i.delta * 100.0,
+                    stats(i.p_value, i.effect_size)
                 ));
             }
         }
 
+        if !self.failure_modes.is_empty() {
+            md.push_str("\n### Failure Modes\n\n");
+            md.push_str("Share of attempts failing to compile with each error category:\n\n");
+            md.push_str("| Model | Category | Baseline | Current | Delta |\n");
+            md.push_str("|-------|----------|----------|---------|-------|\n");
+            for f in &self.failure_modes {
+                md.push_str(&format!(
+                    "| {} | {} | {:.1}% ({}) | {:.1}% ({}) | {:+.1}% |\n",
+                    f.model,
+                    f.category,
+                    f.baseline_rate * 100.0,
+                    f.baseline,
+                    f.current_rate * 100.0,
+                    f.current,
+                    f.delta() * 100.0
+                ));
+            }
+        }
+
+        if let Some(config) = &self.significance {
+            md.push_str(&format!(
+                "\n_Changes count when significant at α = {} ({} permutations; case p-values Holm-adjusted)._\n",
+                config.alpha, config.resamples
+            ));
+        }
+
         md
     }
 
+    /// Models whose mean score dropped significantly.
+    pub fn regressed_models(&self) -> impl Iterator<Item = &ModelComparison> {
+        self.models
+            .iter()
+            .filter(|m| m.significant && m.delta < 0.0)
+    }
+
     /// Returns true if there are any regressions.
+    ///
+    /// With significance comparison, only significant drops count, for a
+    /// single case or for a model as a whole.
     pub fn has_regressions(&self) -> bool {
-        !self.regressions.is_empty()
+        !self.regressions.is_empty() || self.regressed_models().next().is_some()
     }
 }
 
@@ -246,7 +783,13 @@ mod tests {
             aggregate: AggregateStats {
                 per_model: HashMap::new(),
                 per_case: HashMap::new(),
+                per_tag: HashMap::new(),
+                per_difficulty: HashMap::new(),
             },
+            scoring: None,
+            cases: Default::default(),
+            rescored_from: None,
+            budget: None,
             duration_ms: 0,
         }
     }
@@ -281,6 +824,14 @@ mod tests {
                 None
             },
             clippy: None,
+            compile_fail: vec![],
+            hallucinated_apis: vec![],
+            formatting: None,
+            miri: None,
+            performance: None,
+            metrics: None,
+            judge: None,
+            score: None,
             timing: TimingInfo {
                 llm_request_ms: 0,
                 compilation_ms: 0,
@@ -293,6 +844,7 @@ mod tests {
                 total_tokens: 0,
                 estimated_cost_usd: 0.0,
             },
+            cached: false,
             attempt: 1,
             run_id: Uuid::nil(),
         }
@@ -330,6 +882,267 @@ mod tests {
         assert_eq!(report.removed_cases, 1);
     }
 
+    #[test]
+    fn compare_reuses_recorded_scores_from_same_scorer() {
+        // Both results look identical when rescored, but the recorded scores
+        // (computed with real expectations) differ
+        let mut before = make_eval_result("case1", "model1", true, 3, 0);
+        let mut after = before.clone();
+        let mut score = Score::compute(&before, &Expectations::default());
+        score.overall = 0.9;
+        before.score = Some(score.clone());
+        score.overall = 0.6;
+        after.score = Some(score.clone());
+
+        let baseline = make_report(vec![before]);
+        let current = make_report(vec![after.clone()]);
+        let report = current.compare(&baseline, 0.05);
+        assert_eq!(report.regressions.len(), 1);
+        assert!((report.regressions[0].delta + 0.3).abs() < 1e-9);
+
+        // A score from a different scorer is ignored and the result is rescored
+        score.scorer = "other".into();
+        after.score = Some(score);
+        let current = make_report(vec![after]);
+        let report = current.compare_with(&baseline, 0.05, &DefaultScorer::default());
+        assert_eq!(report.improvements.len(), 1);
+    }
+
+    #[test]
+    fn compare_flags_different_scoring_profiles() {
+        let result = make_eval_result("case1", "model1", true, 3, 0);
+        let mut baseline = make_report(vec![result.clone()]);
+        let mut current = make_report(vec![result]);
+
+        baseline.scoring = Some(ScoringProfile::default());
+        current.scoring = Some(ScoringProfile::default());
+        assert!(!current.compare(&baseline, 0.05).scoring_mismatch);
+
+        let mut strict = ScoringProfile::default();
+        strict.pass.min_overall = 0.9;
+        current.scoring = Some(strict);
+        let report = current.compare(&baseline, 0.05);
+        assert!(report.scoring_mismatch);
+        assert!(report.to_markdown().contains("different scoring profiles"));
+
+        // Reports without a recorded profile can't be checked
+        baseline.scoring = None;
+        assert!(!current.compare(&baseline, 0.05).scoring_mismatch);
+    }
+
+    fn attempts(case_id: &str, model: &str, passing: usize, failing: usize) -> Vec<EvalResult> {
+        (0..passing)
+            .map(|_| make_eval_result(case_id, model, true, 3, 0))
+            .chain((0..failing).map(|_| make_eval_result(case_id, model, false, 0, 0)))
+            .collect()
+    }
+
+    #[test]
+    fn significance_ignores_noisy_best_score_drops() {
+        let mut before = attempts("noisy", "model1", 1, 4);
+        before.extend(attempts("broken", "model1", 5, 0));
+        let mut after = attempts("noisy", "model1", 0, 5);
+        after.extend(attempts("broken", "model1", 0, 5));
+        let baseline = make_report(before);
+        let current = make_report(after);
+
+        // The best score of "noisy" drops from 1.0 to 0.0
+        assert_eq!(current.compare(&baseline, 0.05).regressions.len(), 2);
+
+        let report = current.compare_significance(&baseline, 0.05, &SignificanceConfig::default());
+        assert_eq!(report.regressions.len(), 1);
+        let r = &report.regressions[0];
+        assert_eq!(r.case_id, "broken");
+        assert!(r.p_value.unwrap() < 0.05);
+        assert_eq!(r.effect_size, Some(-1.0));
+        assert!((r.baseline_score - 1.0).abs() < 1e-9);
+        assert_eq!(report.unchanged, 1);
+        assert!(report.has_regressions());
+
+        // Two cases aren't enough to call the model as a whole worse
+        assert_eq!(report.models.len(), 1);
+        assert_eq!(report.models[0].cases, 2);
+        assert!(!report.models[0].significant);
+
+        let md = report.to_markdown();
+        assert!(md.contains("| Case | Model | Baseline | Current | Delta | p | Effect |"));
+        assert!(md.contains("### Models"));
+    }
+
+    #[test]
+    fn significance_detects_model_level_drop() {
+        let cases: Vec<String> = (0..12).map(|i| format!("case{i}")).collect();
+        let baseline = make_report(
+            cases
+                .iter()
+                .flat_map(|c| attempts(c, "model1", 1, 0))
+                .collect(),
+        );
+        let current = make_report(
+            cases
+                .iter()
+                .flat_map(|c| attempts(c, "model1", 0, 1))
+                .collect(),
+        );
+
+        let report = current.compare_significance(&baseline, 0.05, &SignificanceConfig::default());
+        // No single case is significant with one attempt each...
+        assert!(report.regressions.is_empty());
+        // ...but every case dropping is
+        let model = &report.models[0];
+        assert!(model.significant);
+        assert!(model.p_value < 0.01);
+        assert_eq!(model.effect_size, -1.0);
+        assert!(report.has_regressions());
+
+        // Identical runs never trip
+        let report = baseline.compare_significance(&baseline, 0.05, &SignificanceConfig::default());
+        assert!(!report.has_regressions());
+        assert_eq!(report.unchanged, 12);
+    }
+
+    fn snapshot(should_pass_tests: bool, prompt_hash: &str) -> CaseSnapshot {
+        CaseSnapshot {
+            expectations: Expectations {
+                should_pass_tests,
+                ..Expectations::default()
+            },
+            prompt_hash: prompt_hash.into(),
+            test_hash: None,
+        }
+    }
+
+    #[test]
+    fn compare_scores_with_snapshotted_expectations() {
+        // Compiles without tests: only a failure if tests were expected
+        let result = make_eval_result("case1", "model1", true, 0, 0);
+        let mut baseline = make_report(vec![result.clone()]);
+        baseline.cases = BTreeMap::from([("case1".into(), snapshot(false, "p"))]);
+        let mut current = make_report(vec![result.clone()]);
+        current.cases = baseline.cases.clone();
+
+        let scorer = DefaultScorer::default();
+        let real = scorer.score(&result, &snapshot(false, "p").expectations);
+        let default = scorer.score(&result, &Expectations::default());
+        assert!(real.overall > default.overall);
+        let scores = current.attempt_scores(&baseline, &scorer);
+        assert_eq!(
+            scores[&("case1".into(), "model1".into())],
+            vec![real.overall]
+        );
+
+        // An older baseline without snapshots is scored with defaults
+        baseline.cases.clear();
+        let report = current.compare(&baseline, 0.05);
+        assert_eq!(report.improvements.len(), 1);
+        assert!(report.changed_cases.is_empty());
+    }
+
+    #[test]
+    fn compare_reports_changed_case_definitions() {
+        let mut baseline = make_report(vec![
+            make_eval_result("case1", "model1", true, 3, 0),
+            make_eval_result("case2", "model1", true, 3, 0),
+        ]);
+        baseline.cases = BTreeMap::from([
+            ("case1".into(), snapshot(true, "p1")),
+            ("case2".into(), snapshot(true, "p2")),
+        ]);
+        let mut current = make_report(vec![
+            make_eval_result("case1", "model1", false, 0, 0),
+            make_eval_result("case2", "model1", true, 3, 0),
+        ]);
+        current.cases = BTreeMap::from([
+            ("case1".into(), snapshot(true, "p1-edited")),
+            ("case2".into(), snapshot(true, "p2")),
+        ]);
+
+        let report = current.compare(&baseline, 0.05);
+        assert_eq!(report.changed_cases, vec!["case1".to_string()]);
+        assert!(!report.has_regressions());
+        assert_eq!(report.unchanged, 1);
+        assert_eq!((report.new_cases, report.removed_cases), (0, 0));
+        assert!(report.to_markdown().contains("### Changed Cases"));
+
+        // Changed expectations count too
+        current.cases.insert("case1".into(), snapshot(false, "p1"));
+        assert_eq!(current.compare(&baseline, 0.05).changed_cases.len(), 1);
+    }
+
+    #[test]
+    fn case_snapshot_hashes() {
+        let set = crate::parser::parse_eval_set_str(
+            r##"
+[eval_set]
+id = "s"
+name = "s"
+
+[[cases]]
+id = "c"
+name = "c"
+prompt = "Write add"
+
+[cases.expectations]
+test_file = "#[test] fn t() {}"
+expected_functions = ["add"]
+"##,
+            Path::new("s.toml"),
+        )
+        .unwrap();
+        let mut case = set.cases[0].clone();
+        let snap = CaseSnapshot::from_case(&case);
+        assert!(snap.expectations.test_file.is_none());
+        assert_eq!(snap.expectations.expected_functions, vec!["add"]);
+        assert_eq!(snap.prompt_hash.len(), 64);
+        assert!(snap.same_definition(&CaseSnapshot::from_case(&case)));
+
+        case.expectations.test_file = Some("#[test] fn u() {}".into());
+        let edited = CaseSnapshot::from_case(&case);
+        assert_eq!(edited.prompt_hash, snap.prompt_hash);
+        assert_ne!(edited.test_hash, snap.test_hash);
+        assert!(!edited.same_definition(&snap));
+
+        case.prompt.push_str(" carefully");
+        assert_ne!(CaseSnapshot::from_case(&case).prompt_hash, snap.prompt_hash);
+    }
+
+    #[test]
+    fn compare_reports_failure_mode_changes() {
+        let failing = |case_id: &str, code: &str| {
+            let mut r = make_eval_result(case_id, "model1", false, 0, 0);
+            r.compilation.errors = vec![CompilerDiagnostic {
+                level: DiagnosticLevel::Error,
+                message: "error".into(),
+                code: Some(code.into()),
+                spans: vec![],
+            }];
+            r
+        };
+        let baseline = make_report(vec![
+            failing("case1", "E0308"),
+            make_eval_result("case2", "model1", true, 3, 0),
+        ]);
+        let current = make_report(vec![failing("case1", "E0502"), failing("case2", "E0499")]);
+
+        let report = current.compare(&baseline, 0.05);
+        let change = |category| {
+            report
+                .failure_modes
+                .iter()
+                .find(|f| f.category == category)
+                .unwrap()
+        };
+        let borrow = change(ErrorCategory::BorrowCheck);
+        assert_eq!((borrow.baseline, borrow.current), (0, 2));
+        assert!((borrow.delta() - 1.0).abs() < 1e-12);
+        let types = change(ErrorCategory::TypeMismatch);
+        assert_eq!((types.baseline, types.current), (1, 0));
+        assert_eq!(report.failure_modes.len(), 2);
+
+        let md = report.to_markdown();
+        assert!(md.contains("| model1 | borrow check | 0.0% (0) | 100.0% (2) | +100.0% |"));
+    }
+
     #[test]
     fn json_roundtrip() {
         let report = make_report(vec![make_eval_result("case1", "model1", true, 3, 0)]);
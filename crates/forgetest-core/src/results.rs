@@ -3,10 +3,15 @@
 //! These types capture the outcome of running an eval case: compilation results,
 //! test execution, clippy analysis, timing, token usage, and computed scores.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::model::Expectations;
+use crate::hallucination::HallucinatedApi;
+use crate::metrics::CodeMetrics;
+use crate::model::{ClippyConfig, Expectations, DEFAULT_CLIPPY_WEIGHT};
+use crate::scoring::{DefaultScorer, Scorer, DEFAULT_SCORER_ID};
 
 /// The result of running one eval case against one model.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,16 +32,90 @@ pub struct EvalResult {
     /// Clippy analysis result (if clippy was run).
     #[serde(default)]
     pub clippy: Option<ClippyResult>,
+    /// Outcomes of the expected compile-failure snippets.
+    #[serde(default)]
+    pub compile_fail: Vec<CompileFailResult>,
+    /// Uses of items that don't exist in the dependency crates (only checked
+    /// when compilation fails).
+    #[serde(default)]
+    pub hallucinated_apis: Vec<HallucinatedApi>,
+    /// rustfmt conformance of the generated code (None if not checked).
+    #[serde(default)]
+    pub formatting: Option<FormattingResult>,
+    /// Outcome of running the tests under Miri (None if not requested).
+    #[serde(default)]
+    pub miri: Option<MiriResult>,
+    /// Outcome of the performance gate (None if the case has none).
+    #[serde(default)]
+    pub performance: Option<PerformanceResult>,
+    /// Static metrics of the generated code (None if it didn't parse).
+    #[serde(default)]
+    pub metrics: Option<CodeMetrics>,
+    /// Grades from the LLM judge (None if the case has no rubric or the
+    /// judge stage didn't run).
+    #[serde(default)]
+    pub judge: Option<JudgeResult>,
+    /// Score computed by the engine's scorer when the result was produced.
+    #[serde(default)]
+    pub score: Option<Score>,
     /// Timing information.
     pub timing: TimingInfo,
     /// Token usage for this generation.
     pub token_usage: TokenUsage,
+    /// Whether the generation was served from the response cache. Its token
+    /// usage is what the original request cost; nothing was spent this run.
+    #[serde(default)]
+    pub cached: bool,
     /// Which attempt this is (for Pass@k sampling).
     pub attempt: u32,
     /// Unique run identifier.
     pub run_id: Uuid,
 }
 
+#[cfg(test)]
+impl EvalResult {
+    /// A compiled result with nothing else run, for tests to adjust.
+    pub(crate) fn stub(case_id: &str, model: &str) -> Self {
+        Self {
+            case_id: case_id.into(),
+            model: model.into(),
+            provider: "test".into(),
+            generated_code: String::new(),
+            compilation: CompilationResult {
+                success: true,
+                errors: vec![],
+                warnings: vec![],
+                duration_ms: 0,
+            },
+            test_execution: None,
+            clippy: None,
+            compile_fail: vec![],
+            hallucinated_apis: vec![],
+            formatting: None,
+            miri: None,
+            performance: None,
+            metrics: None,
+            judge: None,
+            score: None,
+            timing: TimingInfo {
+                llm_request_ms: 0,
+                compilation_ms: 0,
+                test_execution_ms: 0,
+                total_ms: 0,
+            },
+            token_usage: TokenUsage {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+                estimated_cost_usd: 0.0,
+            },
+            cached: false,
+            attempt: 1,
+            run_id: Uuid::nil(),
+        }
+    }
+}
+
 /// Result of compiling generated code.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompilationResult {
@@ -123,6 +202,114 @@ pub struct TestFailure {
     pub stdout: String,
 }
 
+/// Result of checking the generated code with `rustfmt --check`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormattingResult {
+    /// Number of hunks that differ from rustfmt's output (0 = formatted).
+    pub hunks: u32,
+    /// The diff, with file names and colors stripped.
+    pub diff: String,
+}
+
+/// Benchmark statistics for one input size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchMeasurement {
+    /// Input size passed to `setup`.
+    pub size: usize,
+    /// Median time of one `run` call in nanoseconds.
+    pub median_ns: u64,
+    /// Mean time in nanoseconds.
+    pub mean_ns: u64,
+    /// Fastest time in nanoseconds.
+    pub min_ns: u64,
+    /// Median heap allocations during one `run` call.
+    pub allocations: u64,
+}
+
+/// Outcome of a performance gate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceResult {
+    /// Measurements for the generated code, one per input size.
+    pub measurements: Vec<BenchMeasurement>,
+    /// Measurements for the reference solution, if one was benchmarked.
+    #[serde(default)]
+    pub reference: Vec<BenchMeasurement>,
+    /// Whether every budget was met.
+    pub passed: bool,
+    /// Why the gate failed.
+    #[serde(default)]
+    pub failure: Option<String>,
+}
+
+/// Grades an LLM judge gave the generated code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JudgeResult {
+    /// Model that acted as the judge.
+    pub model: String,
+    /// Score per rubric criterion (0.0-1.0).
+    pub criteria: BTreeMap<String, f64>,
+    /// Weighted mean of the criterion scores (0.0-1.0).
+    pub score: f64,
+    /// The judge's explanation of its grades.
+    pub rationale: String,
+    /// Tokens spent on judging, tracked separately from the model under test.
+    /// Zero when the verdict came from the cache.
+    pub token_usage: TokenUsage,
+    /// Whether the verdict was reused for identical code.
+    #[serde(default)]
+    pub cached: bool,
+}
+
+/// Result of running the tests under Miri to detect undefined behaviour.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MiriResult {
+    /// True if the stage did not run (e.g. Miri is not installed).
+    pub skipped: bool,
+    /// Why the stage was skipped.
+    #[serde(default)]
+    pub skip_reason: Option<String>,
+    /// Undefined behaviour reports.
+    #[serde(default)]
+    pub findings: Vec<CompilerDiagnostic>,
+    /// Time spent running Miri in milliseconds.
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+impl MiriResult {
+    /// A result for a stage that did not run.
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Self {
+            skipped: true,
+            skip_reason: Some(reason.into()),
+            findings: Vec::new(),
+            duration_ms: 0,
+        }
+    }
+
+    /// Whether Miri reported undefined behaviour.
+    pub fn ub_detected(&self) -> bool {
+        !self.findings.is_empty()
+    }
+}
+
+/// Outcome of compiling one expected-compile-failure snippet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompileFailResult {
+    /// Snippet label.
+    pub name: String,
+    /// Whether the compiler rejected the snippet.
+    pub rejected: bool,
+    /// The error code the snippet was expected to fail with, if any.
+    #[serde(default)]
+    pub expected_code: Option<String>,
+    /// Error codes the compiler actually reported.
+    #[serde(default)]
+    pub error_codes: Vec<String>,
+    /// Whether the snippet was rejected with the expected error code.
+    pub passed: bool,
+}
+
 /// Result of running clippy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClippyResult {
@@ -130,6 +317,23 @@ pub struct ClippyResult {
     pub warnings: Vec<CompilerDiagnostic>,
     /// Total warning count.
     pub warning_count: u32,
+    /// Warning counts by lint category (e.g. "style", "correctness").
+    /// Warnings whose category could not be determined are not listed.
+    #[serde(default)]
+    pub by_category: BTreeMap<String, u32>,
+}
+
+impl ClippyResult {
+    /// Total score penalty for the warnings, using the per-category weights.
+    pub fn penalty(&self, config: &ClippyConfig) -> f64 {
+        let categorized: u32 = self.by_category.values().sum();
+        let uncategorized = self.warning_count.saturating_sub(categorized);
+        self.by_category
+            .iter()
+            .map(|(category, count)| config.weight(category) * *count as f64)
+            .sum::<f64>()
+            + uncategorized as f64 * DEFAULT_CLIPPY_WEIGHT
+    }
 }
 
 /// Timing breakdown for an eval run.
@@ -165,108 +369,43 @@ pub struct Score {
     pub compilation: f64,
     /// Test score: ratio of passed tests (0.0-1.0).
     pub tests: f64,
-    /// Clippy score: 1.0 minus penalty per warning (0.0-1.0).
+    /// Clippy score: 1.0 minus weighted penalty per warning (0.0-1.0).
     pub clippy: f64,
     /// Expected functions/types score: fraction found (0.0-1.0).
     pub structure: f64,
+    /// Formatting score: 1.0 minus 0.1 per rustfmt hunk (0.0-1.0).
+    #[serde(default = "full_score")]
+    pub formatting: f64,
+    /// Undefined behaviour score: 0.0 if Miri reported UB, 1.0 otherwise.
+    #[serde(default = "full_score")]
+    pub ub: f64,
+    /// LLM judge score (0.0-1.0); None if the result wasn't judged.
+    #[serde(default)]
+    pub judge: Option<f64>,
+    /// Additional components from custom scorers, by name.
+    #[serde(default)]
+    pub extra: BTreeMap<String, f64>,
     /// Weighted overall score.
     pub overall: f64,
+    /// Identifier of the scorer that produced this score.
+    #[serde(default = "default_scorer_id")]
+    pub scorer: String,
 }
 
-impl Score {
-    /// Compute the score for an eval result given the expectations.
-    ///
-    /// Scoring:
-    /// - Compilation: 1.0 if succeeded, 0.0 if failed
-    /// - Tests: ratio of passed / total (0.0 if no tests run)
-    /// - Clippy: 1.0 - 0.1 per warning, capped at 0.0
-    /// - Structure: fraction of expected_functions and expected_types found
-    /// - Overall: weighted average (compilation 30%, tests 45%, structure 15%, clippy 10%)
-    ///
-    /// If compilation fails, overall is 0.0.
-    /// If no expected_functions/expected_types are defined, structure defaults to 1.0.
-    pub fn compute(result: &EvalResult, expectations: &Expectations) -> Score {
-        let compilation = if result.compilation.success { 1.0 } else { 0.0 };
-
-        let tests = if expectations.should_pass_tests {
-            match &result.test_execution {
-                Some(test_result) => {
-                    let total = test_result.passed + test_result.failed;
-                    if total == 0 {
-                        0.0
-                    } else {
-                        test_result.passed as f64 / total as f64
-                    }
-                }
-                None => 0.0,
-            }
-        } else {
-            1.0
-        };
-
-        let clippy = match &result.clippy {
-            Some(clippy_result) => (1.0 - clippy_result.warning_count as f64 * 0.1).max(0.0),
-            None => 1.0,
-        };
-
-        // Check expected functions and types in the generated code
-        let structure = Self::compute_structure_score(
-            &result.generated_code,
-            &expectations.expected_functions,
-            &expectations.expected_types,
-        );
-
-        // If compilation failed, everything is 0
-        let overall = if compilation == 0.0 {
-            0.0
-        } else {
-            compilation * 0.3 + tests * 0.45 + structure * 0.15 + clippy * 0.1
-        };
+fn full_score() -> f64 {
+    1.0
+}
 
-        Score {
-            compilation,
-            tests,
-            clippy,
-            structure,
-            overall,
-        }
-    }
+fn default_scorer_id() -> String {
+    DEFAULT_SCORER_ID.to_string()
+}
 
-    /// Compute the structure score: fraction of expected functions/types found.
+impl Score {
+    /// Compute the score for an eval result with the [`DefaultScorer`].
     ///
-    /// Uses simple pattern matching (`fn name` and `struct/enum/type name`) to
-    /// detect presence. Returns 1.0 if no expectations are defined.
-    fn compute_structure_score(
-        code: &str,
-        expected_functions: &[String],
-        expected_types: &[String],
-    ) -> f64 {
-        let total = expected_functions.len() + expected_types.len();
-        if total == 0 {
-            return 1.0;
-        }
-
-        let mut found = 0;
-
-        for func in expected_functions {
-            // Match "fn func_name" with word boundary (followed by non-alphanumeric)
-            let pattern = format!("fn {func}");
-            if code.contains(&pattern) {
-                found += 1;
-            }
-        }
-
-        for type_name in expected_types {
-            // Match "struct Name", "enum Name", or "type Name"
-            let found_type = ["struct ", "enum ", "type "]
-                .iter()
-                .any(|prefix| code.contains(&format!("{prefix}{type_name}")));
-            if found_type {
-                found += 1;
-            }
-        }
-
-        found as f64 / total as f64
+    /// See [`crate::scoring`] for how each component is computed.
+    pub fn compute(result: &EvalResult, expectations: &Expectations) -> Score {
+        DefaultScorer::default().score(result, expectations)
     }
 }
 
@@ -305,7 +444,16 @@ mod tests {
             clippy: Some(ClippyResult {
                 warnings: vec![],
                 warning_count: clippy_warnings,
+                by_category: Default::default(),
             }),
+            compile_fail: vec![],
+            hallucinated_apis: vec![],
+            formatting: None,
+            miri: None,
+            performance: None,
+            metrics: None,
+            judge: None,
+            score: None,
             timing: TimingInfo {
                 llm_request_ms: 1000,
                 compilation_ms: 100,
@@ -318,6 +466,7 @@ mod tests {
                 total_tokens: 300,
                 estimated_cost_usd: 0.01,
             },
+            cached: false,
             attempt: 1,
             run_id: Uuid::nil(),
         }
@@ -364,6 +513,136 @@ mod tests {
         assert_eq!(score.clippy, 0.0);
     }
 
+    #[test]
+    fn score_clippy_category_weights() {
+        let mut result = make_result(true, 5, 0, 3);
+        result.clippy.as_mut().unwrap().by_category =
+            BTreeMap::from([("correctness".to_string(), 1), ("style".to_string(), 1)]);
+
+        let mut expectations = Expectations::default();
+        expectations.clippy.category_weights = BTreeMap::from([
+            ("correctness".to_string(), 0.5),
+            ("style".to_string(), 0.02),
+        ]);
+
+        // correctness 0.5 + style 0.02 + one uncategorized at the default 0.1
+        let score = Score::compute(&result, &expectations);
+        assert!((score.clippy - 0.38).abs() < 1e-9);
+    }
+
+    #[test]
+    fn score_formatting_weight() {
+        let mut result = make_result(true, 5, 0, 0);
+        result.formatting = Some(FormattingResult {
+            hunks: 5,
+            diff: String::new(),
+        });
+
+        // Unweighted by default: the hunks are recorded but don't move the score
+        let score = Score::compute(&result, &Expectations::default());
+        assert!((score.formatting - 0.5).abs() < 1e-9);
+        assert!((score.overall - 1.0).abs() < 1e-9);
+
+        let mut expectations = Expectations::default();
+        expectations.formatting.weight = 0.2;
+        let score = Score::compute(&result, &expectations);
+        assert!((score.overall - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn score_undefined_behaviour_zeroes_overall() {
+        let mut result = make_result(true, 5, 0, 0);
+        result.miri = Some(MiriResult::skipped("miri not installed"));
+        let score = Score::compute(&result, &Expectations::default());
+        assert_eq!(score.ub, 1.0);
+        assert!((score.overall - 1.0).abs() < f64::EPSILON);
+
+        result.miri = Some(MiriResult {
+            skipped: false,
+            skip_reason: None,
+            findings: vec![CompilerDiagnostic {
+                level: DiagnosticLevel::Error,
+                message: "Undefined Behavior: out-of-bounds pointer use".into(),
+                code: Some("miri::ub".into()),
+                spans: vec![],
+            }],
+            duration_ms: 10,
+        });
+        let score = Score::compute(&result, &Expectations::default());
+        assert_eq!(score.ub, 0.0);
+        assert_eq!(score.tests, 1.0);
+        assert_eq!(score.overall, 0.0);
+    }
+
+    #[test]
+    fn score_performance_gate_counts_as_test() {
+        let mut result = make_result(true, 3, 0, 0);
+        result.performance = Some(PerformanceResult {
+            measurements: vec![],
+            reference: vec![],
+            passed: false,
+            failure: Some("too slow".into()),
+        });
+        let score = Score::compute(&result, &Expectations::default());
+        assert!((score.tests - 0.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn score_custom_weights() {
+        use crate::scoring::ScoreWeights;
+
+        let scorer = DefaultScorer::with_weights(
+            "tests-heavy",
+            ScoreWeights {
+                compilation: 0.25,
+                tests: 0.7,
+                structure: 0.0,
+                clippy: 0.05,
+            },
+        );
+        let result = make_result(true, 1, 1, 0);
+        let score = scorer.score(&result, &Expectations::default());
+        assert_eq!(score.scorer, "tests-heavy");
+        assert!((score.overall - 0.65).abs() < 1e-9);
+        assert!(!scorer.is_correct(&result, &Expectations::default()));
+
+        let default = Score::compute(&result, &Expectations::default());
+        assert_eq!(default.scorer, "default");
+    }
+
+    #[test]
+    fn score_profile_settings() {
+        use crate::scoring::{PenaltyCurve, ScoringProfile};
+
+        let expectations = Expectations::default();
+        let noisy = make_result(true, 2, 0, 20);
+        assert_eq!(Score::compute(&noisy, &expectations).clippy, 0.0);
+
+        let mut profile = ScoringProfile::default();
+        profile.clippy.curve = PenaltyCurve::Exponential;
+        let score = DefaultScorer::from_profile(profile.clone()).score(&noisy, &expectations);
+        assert!((score.clippy - (-2.0f64).exp()).abs() < 1e-9);
+
+        profile.clippy.floor = 0.5;
+        let score = DefaultScorer::from_profile(profile.clone()).score(&noisy, &expectations);
+        assert_eq!(score.clippy, 0.5);
+
+        // Compile failures keep partial credit when zeroing is disabled
+        let broken = make_result(false, 0, 0, 0);
+        profile.zero_on_compile_failure = false;
+        let score = DefaultScorer::from_profile(profile.clone()).score(&broken, &expectations);
+        assert!((score.overall - 0.25).abs() < 1e-9);
+
+        // Pass criteria decide correctness
+        let clean = make_result(true, 2, 0, 0);
+        let scorer = DefaultScorer::from_profile(profile.clone());
+        assert!(scorer.is_correct(&noisy, &expectations));
+        profile.pass.min_overall = 0.99;
+        let scorer = DefaultScorer::from_profile(profile);
+        assert!(!scorer.is_correct(&noisy, &expectations));
+        assert!(scorer.is_correct(&clean, &expectations));
+    }
+
     #[test]
     fn score_expected_functions_found() {
         let mut result = make_result(true, 5, 0, 0);
@@ -400,6 +679,36 @@ mod tests {
         assert_eq!(score.structure, 1.0);
     }
 
+    #[test]
+    fn score_compile_fail_snippets_count_as_tests() {
+        let mut result = make_result(true, 3, 0, 0);
+        result.compile_fail = vec![
+            CompileFailResult {
+                name: "use_after_move".into(),
+                rejected: true,
+                expected_code: Some("E0382".into()),
+                error_codes: vec!["E0382".into()],
+                passed: true,
+            },
+            CompileFailResult {
+                name: "wrong_state".into(),
+                rejected: false,
+                expected_code: None,
+                error_codes: vec![],
+                passed: false,
+            },
+        ];
+        let score = Score::compute(&result, &Expectations::default());
+        assert!((score.tests - 0.8).abs() < f64::EPSILON);
+
+        let expectations = Expectations {
+            should_pass_tests: false,
+            ..Expectations::default()
+        };
+        let score = Score::compute(&result, &expectations);
+        assert!((score.tests - 0.5).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn score_serde_roundtrip() {
         let result = make_result(true, 5, 0, 0);
@@ -0,0 +1,404 @@
+//! Pluggable scoring.
+//!
+//! A [`Scorer`] turns an [`EvalResult`] into a [`Score`]. The engine, the
+//! aggregate statistics and report comparison all take the scorer to use, so
+//! swapping it changes every number consistently. [`DefaultScorer`] implements
+//! the standard weighting, configured by a declarative [`ScoringProfile`]; the
+//! component functions in this module can be reused by custom scorers.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Expectations;
+use crate::results::{EvalResult, Score};
+
+/// Identifier of the built-in [`DefaultScorer`].
+pub const DEFAULT_SCORER_ID: &str = "default";
+
+/// Computes scores for eval results.
+pub trait Scorer: Send + Sync {
+    /// Stable identifier recorded on every score this scorer produces.
+    fn id(&self) -> &str;
+
+    /// Score a result against its case's expectations.
+    fn score(&self, result: &EvalResult, expectations: &Expectations) -> Score;
+
+    /// Whether a result counts as functionally correct for Pass@k.
+    ///
+    /// Defaults to "compiles and all tests pass"; style components such as
+    /// clippy do not affect correctness.
+    fn is_correct(&self, result: &EvalResult, expectations: &Expectations) -> bool {
+        PassCriteria::default().is_met(&self.score(result, expectations))
+    }
+
+    /// The declarative profile behind this scorer, if it has one.
+    ///
+    /// Embedded in reports so comparisons can detect differently scored runs.
+    fn profile(&self) -> Option<&ScoringProfile> {
+        None
+    }
+}
+
+/// A declarative scoring configuration.
+///
+/// Set with a `[scoring]` table in `forgetest.toml` or `[eval_set.scoring]`
+/// in an eval set; omitted fields keep their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringProfile {
+    /// Profile name, recorded as the scorer id on every score.
+    pub name: String,
+    /// Component weights of the overall score.
+    pub weights: ScoreWeights,
+    /// How clippy warnings reduce the clippy component.
+    pub clippy: ClippyPenalty,
+    /// Whether a compile failure zeroes the overall score.
+    pub zero_on_compile_failure: bool,
+    /// What counts as a correct sample for Pass@k.
+    pub pass: PassCriteria,
+}
+
+impl Default for ScoringProfile {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_SCORER_ID.to_string(),
+            weights: ScoreWeights::default(),
+            clippy: ClippyPenalty::default(),
+            zero_on_compile_failure: true,
+            pass: PassCriteria::default(),
+        }
+    }
+}
+
+impl ScoringProfile {
+    /// Describe settings that are likely mistakes.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let w = &self.weights;
+        let weights = [
+            ("compilation", w.compilation),
+            ("tests", w.tests),
+            ("structure", w.structure),
+            ("clippy", w.clippy),
+        ];
+        for (name, weight) in weights {
+            if weight < 0.0 {
+                problems.push(format!("scoring weight {name} is negative ({weight})"));
+            }
+        }
+        let sum: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        if (sum - 1.0).abs() > 1e-6 {
+            problems.push(format!(
+                "scoring weights sum to {sum:.3}, so overall scores won't range 0.0-1.0"
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.clippy.floor) {
+            problems.push(format!(
+                "clippy penalty floor {} is outside 0.0-1.0",
+                self.clippy.floor
+            ));
+        }
+        problems
+    }
+}
+
+/// Weights of the components in the overall score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreWeights {
+    /// Weight of the compilation component (0.0-1.0; weights should sum to 1.0).
+    pub compilation: f64,
+    /// Weight of the test pass rate component (0.0-1.0).
+    pub tests: f64,
+    /// Weight of the expected functions and types component (0.0-1.0).
+    pub structure: f64,
+    /// Weight of the clippy component (0.0-1.0).
+    pub clippy: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            compilation: 0.3,
+            tests: 0.45,
+            structure: 0.15,
+            clippy: 0.1,
+        }
+    }
+}
+
+/// Shape of the clippy penalty curve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PenaltyCurve {
+    /// `1.0 - penalty`: each warning costs its category weight.
+    #[default]
+    Linear,
+    /// `e^-penalty`: early warnings cost most and the score never hits zero.
+    Exponential,
+}
+
+/// Maps the summed clippy penalty to the clippy component.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClippyPenalty {
+    /// How the summed warning penalty maps to a 0.0-1.0 component.
+    pub curve: PenaltyCurve,
+    /// Lowest clippy component a result can get.
+    pub floor: f64,
+}
+
+impl Default for ClippyPenalty {
+    fn default() -> Self {
+        Self {
+            curve: PenaltyCurve::Linear,
+            floor: 0.0,
+        }
+    }
+}
+
+impl ClippyPenalty {
+    /// The clippy component for a result (1.0 if clippy didn't run).
+    pub fn score(&self, result: &EvalResult, expectations: &Expectations) -> f64 {
+        let Some(clippy_result) = &result.clippy else {
+            return 1.0;
+        };
+        let penalty = clippy_result.penalty(&expectations.clippy);
+        let score = match self.curve {
+            PenaltyCurve::Linear => 1.0 - penalty,
+            PenaltyCurve::Exponential => (-penalty).exp(),
+        };
+        score.clamp(self.floor.clamp(0.0, 1.0), 1.0)
+    }
+}
+
+/// Minimum component scores for a sample to count as correct.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PassCriteria {
+    /// Minimum compilation component (0.0-1.0).
+    pub min_compilation: f64,
+    /// Minimum test pass rate component (0.0-1.0).
+    pub min_tests: f64,
+    /// Minimum overall score (0.0-1.0).
+    pub min_overall: f64,
+}
+
+impl Default for PassCriteria {
+    fn default() -> Self {
+        Self {
+            min_compilation: 1.0,
+            min_tests: 0.99,
+            min_overall: 0.0,
+        }
+    }
+}
+
+impl PassCriteria {
+    /// Whether `score` meets every minimum.
+    pub fn is_met(&self, score: &Score) -> bool {
+        score.compilation >= self.min_compilation
+            && score.tests >= self.min_tests
+            && score.overall >= self.min_overall
+    }
+}
+
+/// The standard scorer.
+///
+/// - Overall: weighted sum of compilation, tests, structure and clippy
+///   (30/45/15/10 by default), blended with formatting by
+///   `expectations.formatting.weight` and with the judge score (if judged)
+///   by the rubric's `weight`
+/// - If compilation fails (unless the profile disables it) or Miri reports
+///   undefined behaviour, overall is 0.0
+#[derive(Debug, Clone, Default)]
+pub struct DefaultScorer {
+    profile: ScoringProfile,
+}
+
+impl DefaultScorer {
+    /// A scorer configured by `profile`, recorded under the profile's name.
+    pub fn from_profile(profile: ScoringProfile) -> Self {
+        Self { profile }
+    }
+
+    /// A default scorer with custom weights, recorded under `id`.
+    pub fn with_weights(id: impl Into<String>, weights: ScoreWeights) -> Self {
+        Self::from_profile(ScoringProfile {
+            name: id.into(),
+            weights,
+            ..ScoringProfile::default()
+        })
+    }
+
+    /// The component weights.
+    pub fn weights(&self) -> &ScoreWeights {
+        &self.profile.weights
+    }
+}
+
+impl Scorer for DefaultScorer {
+    fn id(&self) -> &str {
+        &self.profile.name
+    }
+
+    fn is_correct(&self, result: &EvalResult, expectations: &Expectations) -> bool {
+        self.profile.pass.is_met(&self.score(result, expectations))
+    }
+
+    fn profile(&self) -> Option<&ScoringProfile> {
+        Some(&self.profile)
+    }
+
+    fn score(&self, result: &EvalResult, expectations: &Expectations) -> Score {
+        let compilation = compilation_score(result);
+        let tests = test_score(result, expectations);
+        let clippy = self.profile.clippy.score(result, expectations);
+        let structure = structure_score(
+            &result.generated_code,
+            &expectations.expected_functions,
+            &expectations.expected_types,
+        );
+        let formatting = formatting_score(result);
+        let ub = ub_score(result);
+        let judge = judge_score(result);
+
+        // If compilation failed or the code has UB, everything is 0
+        let zeroed = compilation == 0.0 && self.profile.zero_on_compile_failure;
+        let overall = if zeroed || ub == 0.0 {
+            0.0
+        } else {
+            let w = &self.profile.weights;
+            let base = compilation * w.compilation
+                + tests * w.tests
+                + structure * w.structure
+                + clippy * w.clippy;
+            let weight = expectations.formatting.weight.clamp(0.0, 1.0);
+            let overall = base * (1.0 - weight) + formatting * weight;
+            match (judge, &expectations.judge) {
+                (Some(judge), Some(rubric)) => {
+                    let weight = rubric.weight.clamp(0.0, 1.0);
+                    overall * (1.0 - weight) + judge * weight
+                }
+                _ => overall,
+            }
+        };
+
+        Score {
+            compilation,
+            tests,
+            clippy,
+            structure,
+            formatting,
+            ub,
+            judge,
+            extra: BTreeMap::new(),
+            overall,
+            scorer: self.profile.name.clone(),
+        }
+    }
+}
+
+/// 1.0 if the code compiled, 0.0 otherwise.
+pub fn compilation_score(result: &EvalResult) -> f64 {
+    if result.compilation.success {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Ratio of passed tests (0.0 if no tests ran).
+///
+/// Rejected compile-fail snippets and a met performance gate count as passed
+/// tests. If the case doesn't require tests to pass, the score is 1.0 unless
+/// there are such extra checks.
+pub fn test_score(result: &EvalResult, expectations: &Expectations) -> f64 {
+    let extra_total = result.compile_fail.len() as u32 + u32::from(result.performance.is_some());
+    let extra_passed = result.compile_fail.iter().filter(|c| c.passed).count() as u32
+        + u32::from(result.performance.as_ref().is_some_and(|p| p.passed));
+
+    if expectations.should_pass_tests {
+        match &result.test_execution {
+            Some(test_result) => {
+                let total = test_result.passed + test_result.failed + extra_total;
+                if total == 0 {
+                    0.0
+                } else {
+                    (test_result.passed + extra_passed) as f64 / total as f64
+                }
+            }
+            None if extra_total > 0 => extra_passed as f64 / extra_total as f64,
+            None => 0.0,
+        }
+    } else if extra_total > 0 {
+        extra_passed as f64 / extra_total as f64
+    } else {
+        1.0
+    }
+}
+
+/// 1.0 minus the per-category penalty for each clippy warning (0.1 by
+/// default), capped at 0.0. 1.0 if clippy didn't run.
+pub fn clippy_score(result: &EvalResult, expectations: &Expectations) -> f64 {
+    ClippyPenalty::default().score(result, expectations)
+}
+
+/// 1.0 minus 0.1 per rustfmt hunk, capped at 0.0. 1.0 if not checked.
+pub fn formatting_score(result: &EvalResult) -> f64 {
+    match &result.formatting {
+        Some(formatting_result) => (1.0 - formatting_result.hunks as f64 * 0.1).max(0.0),
+        None => 1.0,
+    }
+}
+
+/// 0.0 if Miri reported undefined behaviour, 1.0 otherwise (including skipped).
+pub fn ub_score(result: &EvalResult) -> f64 {
+    match &result.miri {
+        Some(miri) if miri.ub_detected() => 0.0,
+        _ => 1.0,
+    }
+}
+
+/// The LLM judge's weighted criterion score, if the result was judged.
+pub fn judge_score(result: &EvalResult) -> Option<f64> {
+    result.judge.as_ref().map(|j| j.score)
+}
+
+/// Fraction of expected functions/types found.
+///
+/// Uses simple pattern matching (`fn name` and `struct/enum/type name`) to
+/// detect presence. Returns 1.0 if no expectations are defined.
+pub fn structure_score(
+    code: &str,
+    expected_functions: &[String],
+    expected_types: &[String],
+) -> f64 {
+    let total = expected_functions.len() + expected_types.len();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let mut found = 0;
+
+    for func in expected_functions {
+        // Match "fn func_name" with word boundary (followed by non-alphanumeric)
+        let pattern = format!("fn {func}");
+        if code.contains(&pattern) {
+            found += 1;
+        }
+    }
+
+    for type_name in expected_types {
+        // Match "struct Name", "enum Name", or "type Name"
+        let found_type = ["struct ", "enum ", "type "]
+            .iter()
+            .any(|prefix| code.contains(&format!("{prefix}{type_name}")));
+        if found_type {
+            found += 1;
+        }
+    }
+
+    found as f64 / total as f64
+}
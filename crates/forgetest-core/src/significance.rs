@@ -0,0 +1,213 @@
+//! Significance tests for comparing two runs or two models.
+//!
+//! With pass@k sampling a single attempt's score is noisy, so a lower score in
+//! the current run is often chance. These tests decide whether a difference
+//! is larger than resampling noise would explain. All tests are two-sided;
+//! permutation tests are driven by a seeded generator, so the same reports
+//! always give the same p-values.
+
+use serde::{Deserialize, Serialize};
+
+use crate::confidence::SplitMix64;
+
+/// Settings for significance-based comparison.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignificanceConfig {
+    /// Significance level; differences with `p < alpha` are significant.
+    pub alpha: f64,
+    /// Number of random permutations per test.
+    pub resamples: u32,
+    /// Seed for the permutations.
+    pub seed: u64,
+}
+
+impl Default for SignificanceConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.05,
+            resamples: 10_000,
+            seed: 0,
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len().max(1) as f64
+}
+
+/// Two-sided permutation test for a difference in means between two
+/// independent samples, e.g. the attempts of one case in two runs.
+///
+/// Returns 1.0 if either sample is empty.
+pub fn permutation_test(a: &[f64], b: &[f64], resamples: u32, seed: u64) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 1.0;
+    }
+    let observed = (mean(b) - mean(a)).abs();
+    let mut pooled: Vec<f64> = a.iter().chain(b).copied().collect();
+    let (n, k) = (pooled.len(), a.len());
+    let mut rng = SplitMix64::new(seed);
+
+    let mut extreme = 0u32;
+    for _ in 0..resamples {
+        // Partial Fisher-Yates: the first `k` values become the new `a`
+        for i in 0..k {
+            let j = i + rng.below(n - i);
+            pooled.swap(i, j);
+        }
+        let diff = (mean(&pooled[k..]) - mean(&pooled[..k])).abs();
+        if diff >= observed - 1e-12 {
+            extreme += 1;
+        }
+    }
+    (extreme + 1) as f64 / (resamples + 1) as f64
+}
+
+/// Two-sided sign-flip permutation test for paired differences, e.g. the
+/// per-case change in a model's mean score between two runs.
+///
+/// Returns 1.0 if there are no differences.
+pub fn paired_permutation_test(diffs: &[f64], resamples: u32, seed: u64) -> f64 {
+    if diffs.is_empty() {
+        return 1.0;
+    }
+    let observed = mean(diffs).abs();
+    let mut rng = SplitMix64::new(seed);
+
+    let mut extreme = 0u32;
+    for _ in 0..resamples {
+        let flipped: f64 = diffs
+            .iter()
+            .map(|d| if rng.next() & 1 == 0 { *d } else { -d })
+            .sum();
+        if (flipped / diffs.len() as f64).abs() >= observed - 1e-12 {
+            extreme += 1;
+        }
+    }
+    (extreme + 1) as f64 / (resamples + 1) as f64
+}
+
+/// Exact two-sided McNemar test on discordant pairs: `only_a` cases solved
+/// only by A and `only_b` cases solved only by B.
+///
+/// Under the null hypothesis each discordant case is equally likely to go
+/// either way, so the p-value is a binomial tail with p = 0.5.
+pub fn mcnemar_exact(only_a: usize, only_b: usize) -> f64 {
+    let n = only_a + only_b;
+    if n == 0 {
+        return 1.0;
+    }
+    // Sum C(n, i) / 2^n for i <= min(only_a, only_b), in log space
+    let mut log_term = -(n as f64) * std::f64::consts::LN_2;
+    let mut tail = 0.0;
+    for i in 0..=only_a.min(only_b) {
+        tail += log_term.exp();
+        log_term += ((n - i) as f64).ln() - ((i + 1) as f64).ln();
+    }
+    (2.0 * tail).min(1.0)
+}
+
+/// Cliff's delta between two samples: P(b > a) - P(b < a), from -1.0 (every
+/// `b` is lower) to 1.0 (every `b` is higher).
+pub fn cliffs_delta(a: &[f64], b: &[f64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let dominance: i64 = b
+        .iter()
+        .flat_map(|y| a.iter().map(move |x| (y > x) as i64 - (y < x) as i64))
+        .sum();
+    dominance as f64 / (a.len() * b.len()) as f64
+}
+
+/// Effect size for paired differences: the share of pairs that went up minus
+/// the share that went down, from -1.0 to 1.0.
+pub fn paired_effect_size(diffs: &[f64]) -> f64 {
+    if diffs.is_empty() {
+        return 0.0;
+    }
+    let up = diffs.iter().filter(|d| **d > 1e-12).count() as f64;
+    let down = diffs.iter().filter(|d| **d < -1e-12).count() as f64;
+    (up - down) / diffs.len() as f64
+}
+
+/// Holm-Bonferroni adjusted p-values, in input order.
+///
+/// Controls the chance of any false positive when many cases are tested at
+/// once.
+pub fn holm_adjust(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+
+    let mut adjusted = vec![0.0; m];
+    let mut running_max: f64 = 0.0;
+    for (rank, &i) in order.iter().enumerate() {
+        running_max = running_max.max(((m - rank) as f64 * p_values[i]).min(1.0));
+        adjusted[i] = running_max;
+    }
+    adjusted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutation_test_detects_clear_drop() {
+        let before = [1.0; 6];
+        let after = [0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        let p = permutation_test(&before, &after, 5000, 1);
+        assert!(p < 0.05, "p = {p}");
+
+        // Same attempts: no evidence of a change
+        assert!(permutation_test(&before, &before, 1000, 1) > 0.99);
+        // One attempt each can never be significant
+        assert!(permutation_test(&[1.0], &[0.0], 1000, 1) > 0.4);
+        assert_eq!(permutation_test(&[], &[1.0], 1000, 1), 1.0);
+    }
+
+    #[test]
+    fn paired_test_uses_consistency_across_cases() {
+        let consistent = [-0.2; 12];
+        assert!(paired_permutation_test(&consistent, 5000, 3) < 0.01);
+
+        let mixed = [-0.4, 0.3, -0.2, 0.4, -0.3, 0.2];
+        assert!(paired_permutation_test(&mixed, 5000, 3) > 0.5);
+
+        // Deterministic for a given seed
+        assert_eq!(
+            paired_permutation_test(&mixed, 500, 9),
+            paired_permutation_test(&mixed, 500, 9)
+        );
+    }
+
+    #[test]
+    fn effect_sizes() {
+        assert_eq!(cliffs_delta(&[1.0, 1.0], &[0.0, 0.0]), -1.0);
+        assert_eq!(cliffs_delta(&[0.0, 1.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cliffs_delta(&[0.5], &[1.0, 0.5]), 0.5);
+        assert_eq!(paired_effect_size(&[-0.1, -0.2, 0.0, 0.3]), -0.25);
+    }
+
+    #[test]
+    fn mcnemar_exact_matches_binomial() {
+        // 8 vs 1 discordant: 2 * (1 + 9) / 512
+        assert!((mcnemar_exact(8, 1) - 20.0 / 512.0).abs() < 1e-12);
+        assert!((mcnemar_exact(1, 8) - mcnemar_exact(8, 1)).abs() < 1e-12);
+        assert_eq!(mcnemar_exact(3, 3), 1.0);
+        assert_eq!(mcnemar_exact(0, 0), 1.0);
+        // Large counts don't underflow
+        assert!(mcnemar_exact(700, 500) < 1e-6);
+    }
+
+    #[test]
+    fn holm_adjustment() {
+        let adjusted = holm_adjust(&[0.04, 0.01, 0.03]);
+        // Sorted: 0.01 * 3, 0.03 * 2, 0.04 * 1 (monotone)
+        assert!((adjusted[1] - 0.03).abs() < 1e-12);
+        assert!((adjusted[2] - 0.06).abs() < 1e-12);
+        assert!((adjusted[0] - 0.06).abs() < 1e-12);
+        assert!(holm_adjust(&[]).is_empty());
+    }
+}
@@ -2,12 +2,16 @@
 //!
 //! Implements the standard Pass@k estimator from the Codex paper (Chen et al., 2021).
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
-use crate::model::EvalSet;
-use crate::results::{EvalResult, Score};
+use crate::confidence::{interval, ConfidenceInterval, IntervalConfig, IntervalMethod};
+use crate::metrics::CodeMetrics;
+use crate::model::{EvalSet, Expectations};
+use crate::results::EvalResult;
+use crate::scoring::{DefaultScorer, Scorer};
+use crate::taxonomy::{failure_modes, ErrorCategory};
 
 /// Compute Pass@k using the unbiased estimator.
 ///
@@ -58,6 +62,16 @@ pub fn compute_pass_at_k_batch(
     results: &[EvalResult],
     eval_set: &EvalSet,
     k_values: &[u32],
+) -> HashMap<(String, String), HashMap<u32, f64>> {
+    compute_pass_at_k_batch_with(results, eval_set, k_values, &DefaultScorer::default())
+}
+
+/// Compute Pass@k for a batch of results, judging correctness with `scorer`.
+pub fn compute_pass_at_k_batch_with(
+    results: &[EvalResult],
+    eval_set: &EvalSet,
+    k_values: &[u32],
+    scorer: &dyn Scorer,
 ) -> HashMap<(String, String), HashMap<u32, f64>> {
     let mut grouped: HashMap<(String, String), Vec<&EvalResult>> = HashMap::new();
     for r in results {
@@ -81,10 +95,7 @@ pub fn compute_pass_at_k_batch(
             .iter()
             .filter(|r| {
                 if let Some(exp) = expectations {
-                    // "Correct" for Pass@k means: compiles AND all tests pass.
-                    // Clippy warnings should NOT affect functional correctness.
-                    let score = Score::compute(r, exp);
-                    score.compilation >= 1.0 && score.tests >= 0.99
+                    scorer.is_correct(r, exp)
                 } else {
                     r.compilation.success
                 }
@@ -108,6 +119,14 @@ pub struct AggregateStats {
     pub per_model: HashMap<String, ModelStats>,
     /// Per-case statistics.
     pub per_case: HashMap<String, CaseStats>,
+    /// Per-model statistics over the cases with each tag, keyed by tag and
+    /// then model.
+    #[serde(default)]
+    pub per_tag: HashMap<String, HashMap<String, GroupStats>>,
+    /// Per-model statistics over the cases of each difficulty, keyed by
+    /// difficulty and then model (empty if no case has a difficulty).
+    #[serde(default)]
+    pub per_difficulty: HashMap<String, HashMap<String, GroupStats>>,
 }
 
 /// Statistics for a single model across all eval cases.
@@ -123,12 +142,124 @@ pub struct ModelStats {
     pub avg_test_pass_rate: f64,
     /// Average clippy score.
     pub avg_clippy_score: f64,
-    /// Total tokens used.
+    /// Total tokens used, excluding cached generations.
     pub total_tokens: u64,
-    /// Total estimated cost in USD.
+    /// Total estimated cost in USD, excluding cached generations.
     pub total_cost_usd: f64,
-    /// Average latency in milliseconds.
+    /// Average latency in milliseconds, over generations that weren't cached.
     pub avg_latency_ms: u64,
+    /// Number of attempts whose generation came from the response cache.
+    #[serde(default)]
+    pub cached_results: usize,
+    /// Estimated cost of the cached generations when they were made, in USD
+    /// (not included in `total_cost_usd`).
+    #[serde(default)]
+    pub cached_cost_usd: f64,
+    /// Average static code metrics over solutions that compiled.
+    #[serde(default)]
+    pub metrics: Option<MetricsSummary>,
+    /// Average LLM judge score over judged results (None if none were judged).
+    #[serde(default)]
+    pub avg_judge_score: Option<f64>,
+    /// Tokens spent by the judge, not included in `total_tokens`.
+    #[serde(default)]
+    pub judge_tokens: u64,
+    /// Estimated judge cost in USD, not included in `total_cost_usd`.
+    #[serde(default)]
+    pub judge_cost_usd: f64,
+    /// Number of attempts whose compiler errors include each category.
+    #[serde(default)]
+    pub failure_modes: BTreeMap<ErrorCategory, usize>,
+    /// Total uses of non-existent dependency APIs across attempts.
+    #[serde(default)]
+    pub hallucinated_apis: usize,
+    /// Fraction of attempts that use at least one non-existent dependency API.
+    #[serde(default)]
+    pub hallucination_rate: f64,
+    /// Confidence intervals for the rates and scores above.
+    #[serde(default)]
+    pub intervals: Option<ModelIntervals>,
+}
+
+/// Confidence intervals for a model's metrics, computed over cases.
+///
+/// A metric's interval is `None` when no case has a value for it (e.g. test
+/// pass rate when no tests ran).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelIntervals {
+    pub method: IntervalMethod,
+    /// Confidence level, e.g. 0.95.
+    pub level: f64,
+    /// Number of cases the intervals are computed over.
+    pub cases: usize,
+    pub pass_at_k: HashMap<u32, ConfidenceInterval>,
+    pub compilation_rate: Option<ConfidenceInterval>,
+    pub test_pass_rate: Option<ConfidenceInterval>,
+    pub clippy_score: Option<ConfidenceInterval>,
+    pub judge_score: Option<ConfidenceInterval>,
+}
+
+impl ModelIntervals {
+    /// One-line description, e.g. "95% bootstrap intervals over 15 cases".
+    pub fn describe(&self) -> String {
+        format!(
+            "{:.0}% {} intervals over {} cases",
+            self.level * 100.0,
+            self.method,
+            self.cases
+        )
+    }
+}
+
+/// Static code metrics averaged over a model's compiled solutions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSummary {
+    /// Number of solutions averaged.
+    pub solutions: usize,
+    /// Average lines of code.
+    pub avg_lines_of_code: f64,
+    /// Average number of functions.
+    pub avg_functions: f64,
+    /// Average of each solution's mean cyclomatic complexity.
+    pub avg_complexity: f64,
+    /// Highest cyclomatic complexity of any function.
+    pub max_complexity: u32,
+    /// Average `unsafe` blocks.
+    pub avg_unsafe_blocks: f64,
+    /// Average `unwrap`/`expect` calls.
+    pub avg_unwrap_calls: f64,
+    /// Average `clone` calls.
+    pub avg_clone_calls: f64,
+    /// Average heap allocation sites.
+    pub avg_heap_allocations: f64,
+}
+
+impl MetricsSummary {
+    /// Average metrics over solutions; `None` if there are none.
+    pub fn from_metrics<'a>(metrics: impl IntoIterator<Item = &'a CodeMetrics>) -> Option<Self> {
+        let metrics: Vec<&CodeMetrics> = metrics.into_iter().collect();
+        if metrics.is_empty() {
+            return None;
+        }
+        let n = metrics.len() as f64;
+        let avg = |f: fn(&CodeMetrics) -> f64| metrics.iter().map(|m| f(m)).sum::<f64>() / n;
+
+        Some(Self {
+            solutions: metrics.len(),
+            avg_lines_of_code: avg(|m| m.lines_of_code as f64),
+            avg_functions: avg(|m| m.functions as f64),
+            avg_complexity: avg(CodeMetrics::mean_complexity),
+            max_complexity: metrics
+                .iter()
+                .map(|m| m.max_complexity())
+                .max()
+                .unwrap_or(0),
+            avg_unsafe_blocks: avg(|m| m.unsafe_blocks as f64),
+            avg_unwrap_calls: avg(|m| m.unwrap_calls as f64),
+            avg_clone_calls: avg(|m| m.clone_calls as f64),
+            avg_heap_allocations: avg(|m| m.heap_allocations as f64),
+        })
+    }
 }
 
 /// Statistics for a single eval case across all models.
@@ -140,13 +271,93 @@ pub struct CaseStats {
     pub per_model_pass_rate: HashMap<String, f64>,
 }
 
-/// Compute aggregate statistics from all results.
+/// A model's statistics over a group of cases (one tag or difficulty).
+///
+/// Each case contributes the mean of its attempts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupStats {
+    /// Number of cases in the group the model attempted.
+    pub cases: usize,
+    /// Average fraction of correct attempts.
+    pub pass_rate: f64,
+    /// Average overall score.
+    pub avg_score: f64,
+    /// Average compilation success rate.
+    pub compilation_rate: f64,
+}
+
+impl GroupStats {
+    /// Average `(pass rate, score, compilation rate)` triples, one per case.
+    fn from_cases(cases: &[(f64, f64, f64)]) -> Self {
+        let n = cases.len().max(1) as f64;
+        Self {
+            cases: cases.len(),
+            pass_rate: cases.iter().map(|c| c.0).sum::<f64>() / n,
+            avg_score: cases.iter().map(|c| c.1).sum::<f64>() / n,
+            compilation_rate: cases.iter().map(|c| c.2).sum::<f64>() / n,
+        }
+    }
+}
+
+/// Per-case `(pass rate, score, compilation rate)` of each model, by group.
+type GroupCases = HashMap<String, HashMap<String, Vec<(f64, f64, f64)>>>;
+
+/// Labels of `per_tag` or `per_difficulty` groups in display order: common
+/// difficulty labels from easiest to hardest, then the rest alphabetically.
+pub fn group_order(groups: &HashMap<String, HashMap<String, GroupStats>>) -> Vec<&str> {
+    const DIFFICULTIES: [&str; 6] = ["trivial", "easy", "medium", "hard", "very-hard", "expert"];
+    let mut labels: Vec<&str> = groups.keys().map(String::as_str).collect();
+    labels.sort_by_key(|label| {
+        let known = DIFFICULTIES
+            .iter()
+            .position(|d| label.eq_ignore_ascii_case(d));
+        (known.unwrap_or(DIFFICULTIES.len()), *label)
+    });
+    labels
+}
+
+/// Mean of the values each case contributes, skipping cases without any.
+fn case_means<'a>(
+    by_case: &HashMap<&str, Vec<&'a EvalResult>>,
+    value: impl Fn(&'a EvalResult) -> Option<f64>,
+) -> Vec<f64> {
+    let mut case_ids: Vec<&&str> = by_case.keys().collect();
+    case_ids.sort();
+    case_ids
+        .into_iter()
+        .filter_map(|case_id| {
+            let values: Vec<f64> = by_case[*case_id].iter().filter_map(|r| value(r)).collect();
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        })
+        .collect()
+}
+
+/// Compute aggregate statistics from all results with the default scorer.
 pub fn compute_aggregate_stats(
     results: &[EvalResult],
     eval_set: &EvalSet,
     k_values: &[u32],
 ) -> AggregateStats {
-    let pass_at_k_batch = compute_pass_at_k_batch(results, eval_set, k_values);
+    compute_aggregate_stats_with(
+        results,
+        eval_set,
+        k_values,
+        &DefaultScorer::default(),
+        &IntervalConfig::default(),
+    )
+}
+
+/// Compute aggregate statistics from all results, scoring with `scorer` and
+/// computing confidence intervals with `intervals`.
+pub fn compute_aggregate_stats_with(
+    results: &[EvalResult],
+    eval_set: &EvalSet,
+    k_values: &[u32],
+    scorer: &dyn Scorer,
+    intervals: &IntervalConfig,
+) -> AggregateStats {
+    let pass_at_k_batch = compute_pass_at_k_batch_with(results, eval_set, k_values, scorer);
+    let default_expectations = Expectations::default();
 
     // Per-model stats
     let mut model_results: HashMap<String, Vec<&EvalResult>> = HashMap::new();
@@ -168,15 +379,13 @@ pub fn compute_aggregate_stats(
 
         let test_pass_rate = model_res
             .iter()
-            .filter_map(|r| {
-                r.test_execution.as_ref().map(|t| {
-                    let total = t.passed + t.failed;
-                    if total == 0 {
-                        0.0
-                    } else {
-                        t.passed as f64 / total as f64
-                    }
-                })
+            .filter(|r| r.test_execution.is_some())
+            .map(|r| {
+                let exp = case_expectations
+                    .get(r.case_id.as_str())
+                    .copied()
+                    .unwrap_or(&default_expectations);
+                scorer.score(r, exp).tests
             })
             .sum::<f64>()
             / model_res
@@ -187,10 +396,13 @@ pub fn compute_aggregate_stats(
 
         let clippy_score = model_res
             .iter()
-            .filter_map(|r| {
-                r.clippy
-                    .as_ref()
-                    .map(|c| (1.0 - c.warning_count as f64 * 0.1).max(0.0))
+            .filter(|r| r.clippy.is_some())
+            .map(|r| {
+                let exp = case_expectations
+                    .get(r.case_id.as_str())
+                    .copied()
+                    .unwrap_or(&default_expectations);
+                scorer.score(r, exp).clippy
             })
             .sum::<f64>()
             / model_res
@@ -199,18 +411,81 @@ pub fn compute_aggregate_stats(
                 .count()
                 .max(1) as f64;
 
-        let total_tokens: u64 = model_res
+        // Cached generations cost nothing this run
+        let (cached, fresh): (Vec<&EvalResult>, Vec<&EvalResult>) =
+            model_res.iter().copied().partition(|r| r.cached);
+
+        let total_tokens: u64 = fresh
             .iter()
             .map(|r| r.token_usage.total_tokens as u64)
             .sum();
 
-        let total_cost: f64 = model_res
+        let total_cost: f64 = fresh.iter().map(|r| r.token_usage.estimated_cost_usd).sum();
+        let cached_cost: f64 = cached
             .iter()
             .map(|r| r.token_usage.estimated_cost_usd)
             .sum();
 
-        let avg_latency = model_res.iter().map(|r| r.timing.total_ms).sum::<u64>()
-            / model_res.len().max(1) as u64;
+        let judged: Vec<_> = model_res.iter().filter_map(|r| r.judge.as_ref()).collect();
+        let avg_judge_score = (!judged.is_empty())
+            .then(|| judged.iter().map(|j| j.score).sum::<f64>() / judged.len() as f64);
+        let judge_tokens: u64 = judged
+            .iter()
+            .map(|j| j.token_usage.total_tokens as u64)
+            .sum();
+        let judge_cost_usd: f64 = judged
+            .iter()
+            .map(|j| j.token_usage.estimated_cost_usd)
+            .sum();
+
+        // Confidence intervals over cases: each case contributes the mean of
+        // its attempts
+        let mut by_case: HashMap<&str, Vec<&EvalResult>> = HashMap::new();
+        for r in model_res {
+            by_case.entry(r.case_id.as_str()).or_default().push(r);
+        }
+        let expectations_for = |r: &EvalResult| {
+            case_expectations
+                .get(r.case_id.as_str())
+                .copied()
+                .unwrap_or(&default_expectations)
+        };
+        let ci = |values: Vec<f64>| interval(&values, intervals);
+        let mut pass_at_k_ci = HashMap::new();
+        for &k in k_values {
+            let mut per_case: Vec<(&String, f64)> = pass_at_k_batch
+                .iter()
+                .filter(|((_, m), _)| m == model)
+                .filter_map(|((case_id, _), scores)| scores.get(&k).map(|&s| (case_id, s)))
+                .collect();
+            per_case.sort_by(|a, b| a.0.cmp(b.0));
+            if let Some(range) = ci(per_case.into_iter().map(|(_, s)| s).collect()) {
+                pass_at_k_ci.insert(k, range);
+            }
+        }
+        let model_intervals = ModelIntervals {
+            method: intervals.method,
+            level: intervals.level,
+            cases: by_case.len(),
+            pass_at_k: pass_at_k_ci,
+            compilation_rate: ci(case_means(&by_case, |r| {
+                Some(if r.compilation.success { 1.0 } else { 0.0 })
+            })),
+            test_pass_rate: ci(case_means(&by_case, |r| {
+                r.test_execution
+                    .as_ref()
+                    .map(|_| scorer.score(r, expectations_for(r)).tests)
+            })),
+            clippy_score: ci(case_means(&by_case, |r| {
+                r.clippy
+                    .as_ref()
+                    .map(|_| scorer.score(r, expectations_for(r)).clippy)
+            })),
+            judge_score: ci(case_means(&by_case, |r| r.judge.as_ref().map(|j| j.score))),
+        };
+
+        let avg_latency =
+            fresh.iter().map(|r| r.timing.total_ms).sum::<u64>() / fresh.len().max(1) as u64;
 
         // Aggregate Pass@k for this model
         let mut model_pass_k = HashMap::new();
@@ -239,6 +514,25 @@ pub fn compute_aggregate_stats(
                 total_tokens,
                 total_cost_usd: total_cost,
                 avg_latency_ms: avg_latency,
+                cached_results: cached.len(),
+                cached_cost_usd: cached_cost,
+                metrics: MetricsSummary::from_metrics(
+                    model_res
+                        .iter()
+                        .filter(|r| r.compilation.success)
+                        .filter_map(|r| r.metrics.as_ref()),
+                ),
+                avg_judge_score,
+                judge_tokens,
+                judge_cost_usd,
+                failure_modes: failure_modes(model_res.iter().copied()),
+                hallucinated_apis: model_res.iter().map(|r| r.hallucinated_apis.len()).sum(),
+                hallucination_rate: model_res
+                    .iter()
+                    .filter(|r| !r.hallucinated_apis.is_empty())
+                    .count() as f64
+                    / model_res.len().max(1) as f64,
+                intervals: Some(model_intervals),
             },
         );
     }
@@ -262,8 +556,7 @@ pub fn compute_aggregate_stats(
                 .iter()
                 .filter(|r| {
                     if let Some(exp) = case_expectations.get(case_id.as_str()) {
-                        let score = Score::compute(r, exp);
-                        score.compilation >= 1.0 && score.tests >= 0.99
+                        scorer.is_correct(r, exp)
                     } else {
                         r.compilation.success
                     }
@@ -281,9 +574,63 @@ pub fn compute_aggregate_stats(
         );
     }
 
+    // Per-tag and per-difficulty stats: each (case, model) contributes its
+    // pass rate, mean score and compilation rate to every group of the case
+    let mut per_tag_cases: GroupCases = HashMap::new();
+    let mut per_difficulty_cases: GroupCases = HashMap::new();
+    for case in &eval_set.cases {
+        let Some(model_map) = case_model_results.get(&case.id) else {
+            continue;
+        };
+        for (model, res) in model_map {
+            let n = res.len().max(1) as f64;
+            let summary = (
+                res.iter()
+                    .filter(|r| scorer.is_correct(r, &case.expectations))
+                    .count() as f64
+                    / n,
+                res.iter()
+                    .map(|r| scorer.score(r, &case.expectations).overall)
+                    .sum::<f64>()
+                    / n,
+                res.iter().filter(|r| r.compilation.success).count() as f64 / n,
+            );
+            for tag in &case.tags {
+                per_tag_cases
+                    .entry(tag.clone())
+                    .or_default()
+                    .entry(model.clone())
+                    .or_default()
+                    .push(summary);
+            }
+            if let Some(difficulty) = &case.difficulty {
+                per_difficulty_cases
+                    .entry(difficulty.clone())
+                    .or_default()
+                    .entry(model.clone())
+                    .or_default()
+                    .push(summary);
+            }
+        }
+    }
+    let group_stats = |groups: GroupCases| {
+        groups
+            .into_iter()
+            .map(|(group, models)| {
+                let stats = models
+                    .into_iter()
+                    .map(|(model, cases)| (model, GroupStats::from_cases(&cases)))
+                    .collect();
+                (group, stats)
+            })
+            .collect()
+    };
+
     AggregateStats {
         per_model,
         per_case,
+        per_tag: group_stats(per_tag_cases),
+        per_difficulty: group_stats(per_difficulty_cases),
     }
 }
 
@@ -291,6 +638,179 @@ pub fn compute_aggregate_stats(
 mod tests {
     use super::*;
 
+    #[test]
+    fn metrics_summary_averages() {
+        let a = crate::metrics::analyze("fn a(x: Option<u8>) -> u8 { x.unwrap() }").unwrap();
+        let b =
+            crate::metrics::analyze("fn b(x: u8) -> u8 { if x > 1 { x } else { 0 } }\nfn c() {}")
+                .unwrap();
+
+        let summary = MetricsSummary::from_metrics([&a, &b]).unwrap();
+        assert_eq!(summary.solutions, 2);
+        assert!((summary.avg_functions - 1.5).abs() < f64::EPSILON);
+        assert!((summary.avg_unwrap_calls - 0.5).abs() < f64::EPSILON);
+        // a: mean 1.0; b: (2 + 1) / 2 = 1.5
+        assert!((summary.avg_complexity - 1.25).abs() < f64::EPSILON);
+        assert_eq!(summary.max_complexity, 2);
+
+        assert!(MetricsSummary::from_metrics([]).is_none());
+    }
+
+    fn compiled_result(case_id: &str, success: bool) -> EvalResult {
+        let mut result = EvalResult::stub(case_id, "m");
+        result.compilation.success = success;
+        result
+    }
+
+    #[test]
+    fn aggregate_intervals_bracket_point_estimates() {
+        let toml = (0..10)
+            .map(|i| format!("[[cases]]\nid = \"c{i}\"\nname = \"c{i}\"\nprompt = \"p\"\n"))
+            .collect::<String>();
+        let set = crate::parser::parse_eval_set_str(
+            &format!("[eval_set]\nid = \"s\"\nname = \"s\"\n{toml}"),
+            std::path::Path::new("s.toml"),
+        )
+        .unwrap();
+        let results: Vec<EvalResult> = (0..10)
+            .map(|i| compiled_result(&format!("c{i}"), i < 7))
+            .collect();
+
+        let stats = compute_aggregate_stats(&results, &set, &[1]);
+        let model = &stats.per_model["m"];
+        let ci = model.intervals.as_ref().unwrap();
+        assert_eq!(ci.cases, 10);
+        assert!(ci.pass_at_k[&1].contains(model.pass_at_k[&1]));
+        let compile = ci.compilation_rate.unwrap();
+        assert!(compile.contains(0.7) && compile.lower < 0.7 && compile.upper > 0.7);
+        // No tests ran and nothing was judged
+        assert!(ci.test_pass_rate.is_none());
+        assert!(ci.judge_score.is_none());
+
+        let wilson = IntervalConfig {
+            method: IntervalMethod::Wilson,
+            ..Default::default()
+        };
+        let stats =
+            compute_aggregate_stats_with(&results, &set, &[1], &DefaultScorer::default(), &wilson);
+        let ci = stats.per_model["m"].intervals.as_ref().unwrap();
+        assert_eq!(ci.method, IntervalMethod::Wilson);
+        assert!(ci
+            .describe()
+            .starts_with("95% Wilson intervals over 10 cases"));
+    }
+
+    #[test]
+    fn test_pass_rate_matches_test_scores() {
+        let set = crate::parser::parse_eval_set_str(
+            "[eval_set]\nid = \"s\"\nname = \"s\"\n[[cases]]\nid = \"a\"\nname = \"a\"\nprompt = \"p\"\n",
+            std::path::Path::new("s.toml"),
+        )
+        .unwrap();
+        let mut result = compiled_result("a", true);
+        result.test_execution = Some(crate::results::TestResult {
+            passed: 1,
+            failed: 1,
+            ignored: 0,
+            duration_ms: 0,
+            failures: vec![],
+        });
+        // A rejected misuse snippet counts as a passed test
+        result.compile_fail = vec![crate::results::CompileFailResult {
+            name: "misuse".into(),
+            rejected: true,
+            expected_code: None,
+            error_codes: vec![],
+            passed: true,
+        }];
+
+        let stats = compute_aggregate_stats(std::slice::from_ref(&result), &set, &[1]);
+        let model = &stats.per_model["m"];
+        let expected = crate::scoring::test_score(&result, &set.cases[0].expectations);
+        assert!((expected - 2.0 / 3.0).abs() < 1e-9);
+        assert!((model.avg_test_pass_rate - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cached_generations_are_kept_out_of_cost_and_latency() {
+        let set = crate::parser::parse_eval_set_str(
+            "[eval_set]\nid = \"s\"\nname = \"s\"\n[[cases]]\nid = \"a\"\nname = \"a\"\nprompt = \"p\"\n",
+            std::path::Path::new("s.toml"),
+        )
+        .unwrap();
+        let mut fresh = compiled_result("a", true);
+        fresh.token_usage.total_tokens = 100;
+        fresh.token_usage.estimated_cost_usd = 0.5;
+        fresh.timing.total_ms = 1000;
+        let mut cached = fresh.clone();
+        cached.cached = true;
+        cached.attempt = 2;
+        cached.timing.total_ms = 10;
+
+        let stats = compute_aggregate_stats(&[fresh, cached], &set, &[1]);
+        let model = &stats.per_model["m"];
+        assert_eq!(model.total_tokens, 100);
+        assert_eq!(model.total_cost_usd, 0.5);
+        assert_eq!(model.avg_latency_ms, 1000);
+        assert_eq!(model.cached_results, 1);
+        assert_eq!(model.cached_cost_usd, 0.5);
+    }
+
+    #[test]
+    fn aggregate_groups_by_tag_and_difficulty() {
+        let set = crate::parser::parse_eval_set_str(
+            r#"
+[eval_set]
+id = "s"
+name = "s"
+
+[[cases]]
+id = "a"
+name = "a"
+prompt = "p"
+tags = ["basics"]
+difficulty = "easy"
+
+[[cases]]
+id = "b"
+name = "b"
+prompt = "p"
+tags = ["basics", "async"]
+difficulty = "hard"
+
+[[cases]]
+id = "c"
+name = "c"
+prompt = "p"
+tags = ["async"]
+"#,
+            std::path::Path::new("s.toml"),
+        )
+        .unwrap();
+        let results = vec![
+            compiled_result("a", true),
+            compiled_result("a", true),
+            compiled_result("b", true),
+            compiled_result("b", false),
+            compiled_result("c", false),
+        ];
+
+        let stats = compute_aggregate_stats(&results, &set, &[1]);
+        let basics = &stats.per_tag["basics"]["m"];
+        assert_eq!(basics.cases, 2);
+        // Case means: a = 1.0, b = 0.5
+        assert!((basics.compilation_rate - 0.75).abs() < 1e-12);
+        let async_ = &stats.per_tag["async"]["m"];
+        assert_eq!(async_.cases, 2);
+        assert!((async_.compilation_rate - 0.25).abs() < 1e-12);
+        assert!(async_.avg_score < basics.avg_score);
+
+        assert_eq!(stats.per_difficulty.len(), 2);
+        assert_eq!(stats.per_difficulty["hard"]["m"].cases, 1);
+        assert_eq!(group_order(&stats.per_difficulty), vec!["easy", "hard"]);
+        assert_eq!(group_order(&stats.per_tag), vec!["async", "basics"]);
+    }
+
     #[test]
     fn pass_at_k_all_success() {
         assert!((pass_at_k(10, 10, 1) - 1.0).abs() < f64::EPSILON);
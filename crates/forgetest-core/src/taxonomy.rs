@@ -0,0 +1,208 @@
+//! Compiler error taxonomy.
+//!
+//! Classifies rustc errors into a handful of failure modes by their error
+//! code, so reports can say *why* a model's code fails to compile rather
+//! than only how often. Errors without a code are classified by message,
+//! which is how rustc reports most parse errors.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::results::{CompilerDiagnostic, EvalResult};
+
+/// The kind of mistake behind a compiler error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Moves, conflicting borrows and mutability (E0382, E0502, ...).
+    BorrowCheck,
+    /// Mismatched types, wrong argument counts, failed inference (E0308, ...).
+    TypeMismatch,
+    /// Unresolved imports, names, methods and fields: usually a hallucinated
+    /// API (E0425, E0432, E0599, ...).
+    Unresolved,
+    /// Unsatisfied trait bounds and incomplete impls (E0277, E0046, ...).
+    TraitBound,
+    /// Parse errors.
+    Syntax,
+    /// Missing or insufficient lifetimes (E0106, E0597, E0515, ...).
+    Lifetime,
+    /// Anything else.
+    Other,
+}
+
+impl ErrorCategory {
+    /// All categories, in display order.
+    pub const ALL: [ErrorCategory; 7] = [
+        ErrorCategory::BorrowCheck,
+        ErrorCategory::TypeMismatch,
+        ErrorCategory::Unresolved,
+        ErrorCategory::TraitBound,
+        ErrorCategory::Syntax,
+        ErrorCategory::Lifetime,
+        ErrorCategory::Other,
+    ];
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCategory::BorrowCheck => write!(f, "borrow check"),
+            ErrorCategory::TypeMismatch => write!(f, "type mismatch"),
+            ErrorCategory::Unresolved => write!(f, "unresolved/API"),
+            ErrorCategory::TraitBound => write!(f, "trait bounds"),
+            ErrorCategory::Syntax => write!(f, "syntax"),
+            ErrorCategory::Lifetime => write!(f, "lifetime"),
+            ErrorCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
+const BORROW_CHECK: &[&str] = &[
+    "E0373", "E0381", "E0382", "E0383", "E0384", "E0499", "E0502", "E0503", "E0505", "E0506",
+    "E0507", "E0508", "E0509", "E0594", "E0596",
+];
+const TYPE_MISMATCH: &[&str] = &[
+    "E0053", "E0057", "E0060", "E0061", "E0069", "E0070", "E0107", "E0271", "E0282", "E0283",
+    "E0308", "E0605", "E0606", "E0631",
+];
+const UNRESOLVED: &[&str] = &[
+    "E0405", "E0407", "E0412", "E0422", "E0423", "E0425", "E0432", "E0433", "E0531", "E0532",
+    "E0559", "E0560", "E0599", "E0609", "E0610",
+];
+const TRAIT_BOUND: &[&str] = &[
+    "E0038", "E0046", "E0117", "E0119", "E0191", "E0220", "E0275", "E0276", "E0277", "E0369",
+    "E0404", "E0600", "E0608", "E0614",
+];
+const SYNTAX: &[&str] = &["E0585", "E0586", "E0758", "E0762", "E0763", "E0765"];
+const LIFETIME: &[&str] = &[
+    "E0106", "E0261", "E0262", "E0263", "E0310", "E0311", "E0491", "E0495", "E0515", "E0597",
+    "E0621", "E0623", "E0700", "E0713", "E0716", "E0759",
+];
+
+/// Message prefixes of codeless errors that come from the parser.
+const SYNTAX_MESSAGES: &[&str] = &[
+    "expected ",
+    "unexpected ",
+    "unclosed delimiter",
+    "mismatched closing delimiter",
+    "unterminated ",
+    "this file contains an unclosed delimiter",
+    "missing ",
+];
+
+/// Classify a compiler error.
+///
+/// Returns `None` for rustc's summary lines such as "aborting due to 2
+/// previous errors", which don't describe a mistake of their own.
+pub fn classify(diagnostic: &CompilerDiagnostic) -> Option<ErrorCategory> {
+    let message = diagnostic.message.trim();
+    if message.starts_with("aborting due to") || message.starts_with("could not compile") {
+        return None;
+    }
+    let Some(code) = diagnostic.code.as_deref() else {
+        let syntax = SYNTAX_MESSAGES.iter().any(|p| message.starts_with(p));
+        return Some(if syntax {
+            ErrorCategory::Syntax
+        } else {
+            ErrorCategory::Other
+        });
+    };
+    let category = [
+        (BORROW_CHECK, ErrorCategory::BorrowCheck),
+        (TYPE_MISMATCH, ErrorCategory::TypeMismatch),
+        (UNRESOLVED, ErrorCategory::Unresolved),
+        (TRAIT_BOUND, ErrorCategory::TraitBound),
+        (SYNTAX, ErrorCategory::Syntax),
+        (LIFETIME, ErrorCategory::Lifetime),
+    ]
+    .into_iter()
+    .find(|(codes, _)| codes.contains(&code))
+    .map_or(ErrorCategory::Other, |(_, category)| category);
+    Some(category)
+}
+
+/// Categories of a result's compiler errors (empty if it compiled).
+pub fn result_categories(result: &EvalResult) -> BTreeSet<ErrorCategory> {
+    result
+        .compilation
+        .errors
+        .iter()
+        .filter_map(classify)
+        .collect()
+}
+
+/// Failure-mode histogram: for each category, the number of `results` whose
+/// compiler errors include it. A result with several errors of one category
+/// counts once for it.
+pub fn failure_modes<'a>(
+    results: impl IntoIterator<Item = &'a EvalResult>,
+) -> BTreeMap<ErrorCategory, usize> {
+    let mut histogram = BTreeMap::new();
+    for r in results {
+        for category in result_categories(r) {
+            *histogram.entry(category).or_default() += 1;
+        }
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::DiagnosticLevel;
+
+    fn error(code: Option<&str>, message: &str) -> CompilerDiagnostic {
+        CompilerDiagnostic {
+            level: DiagnosticLevel::Error,
+            message: message.into(),
+            code: code.map(Into::into),
+            spans: vec![],
+        }
+    }
+
+    #[test]
+    fn classifies_by_code() {
+        let cases = [
+            ("E0502", ErrorCategory::BorrowCheck),
+            ("E0382", ErrorCategory::BorrowCheck),
+            ("E0308", ErrorCategory::TypeMismatch),
+            ("E0432", ErrorCategory::Unresolved),
+            ("E0599", ErrorCategory::Unresolved),
+            ("E0277", ErrorCategory::TraitBound),
+            ("E0106", ErrorCategory::Lifetime),
+            ("E0597", ErrorCategory::Lifetime),
+            ("E0765", ErrorCategory::Syntax),
+            ("E0133", ErrorCategory::Other),
+        ];
+        for (code, category) in cases {
+            assert_eq!(
+                classify(&error(Some(code), "msg")),
+                Some(category),
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn classifies_codeless_errors_by_message() {
+        assert_eq!(
+            classify(&error(None, "expected one of `;` or `}`, found `let`")),
+            Some(ErrorCategory::Syntax)
+        );
+        assert_eq!(
+            classify(&error(None, "this file contains an unclosed delimiter")),
+            Some(ErrorCategory::Syntax)
+        );
+        assert_eq!(
+            classify(&error(None, "linking with `cc` failed")),
+            Some(ErrorCategory::Other)
+        );
+        assert_eq!(
+            classify(&error(None, "aborting due to 2 previous errors")),
+            None
+        );
+    }
+}
@@ -6,8 +6,16 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use crate::model::{ContextFile, Language};
-use crate::results::{ClippyResult, CompilationResult, TestResult, TokenUsage};
+use crate::error::ProviderError;
+use crate::hallucination::{find_hallucinated_apis, HallucinatedApi};
+use crate::model::{
+    ClippyConfig, CompileFailExpectation, ContextFile, Fixture, FormattingConfig, Language,
+    PerformanceExpectation,
+};
+use crate::results::{
+    ClippyResult, CompilationResult, CompileFailResult, CompilerDiagnostic, FormattingResult,
+    MiriResult, PerformanceResult, TestResult, TokenUsage,
+};
 
 // ---------------------------------------------------------------------------
 // LLM Provider trait
@@ -22,6 +30,20 @@ pub trait LlmProvider: Send + Sync {
     /// Generate code from a prompt.
     async fn generate(&self, request: &GenerateRequest) -> anyhow::Result<GenerateResponse>;
 
+    /// Fill-in-the-middle completion between a prefix and a suffix.
+    ///
+    /// The returned `extracted_code` is the raw completion (no markdown
+    /// extraction). Providers without a completion endpoint keep the default,
+    /// which fails with [`ProviderError::Unsupported`].
+    async fn complete(&self, request: &CompletionRequest) -> anyhow::Result<GenerateResponse> {
+        Err(ProviderError::Unsupported(format!(
+            "{} does not support fill-in-the-middle completion (model {})",
+            self.name(),
+            request.model
+        ))
+        .into())
+    }
+
     /// List available models for this provider.
     fn available_models(&self) -> Vec<ModelInfo>;
 }
@@ -46,6 +68,35 @@ pub struct GenerateRequest {
     /// Stop sequences.
     #[serde(default)]
     pub stop_sequences: Vec<String>,
+    /// Pass@k attempt the request is made for (0 outside an eval, e.g. for
+    /// judge requests). Providers don't send it; it keeps the otherwise
+    /// identical requests of different attempts apart in recordings.
+    #[serde(default)]
+    pub attempt: u32,
+}
+
+/// Request for a fill-in-the-middle (FIM) completion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionRequest {
+    /// Model identifier.
+    pub model: String,
+    /// Code before the cursor.
+    pub prefix: String,
+    /// Code after the cursor.
+    #[serde(default)]
+    pub suffix: String,
+    /// Maximum tokens to generate.
+    pub max_tokens: u32,
+    /// Sampling temperature.
+    pub temperature: f64,
+    /// Stop sequences.
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+    /// Pass@k attempt the request is made for (0 outside an eval, e.g. for
+    /// judge requests). Providers don't send it; it keeps the otherwise
+    /// identical requests of different attempts apart in recordings.
+    #[serde(default)]
+    pub attempt: u32,
 }
 
 /// Response from an LLM code generation request.
@@ -95,6 +146,54 @@ pub trait CodeRunner: Send + Sync {
 
     /// Run clippy on generated code.
     async fn run_clippy(&self, request: &ClippyRequest) -> anyhow::Result<ClippyResult>;
+
+    /// Compile each snippet against the generated crate; rejected snippets pass.
+    async fn check_compile_failures(
+        &self,
+        request: &CompileFailRequest,
+    ) -> anyhow::Result<Vec<CompileFailResult>> {
+        let _ = request;
+        anyhow::bail!("this runner does not support compile-fail expectations")
+    }
+
+    /// Benchmark generated code against a performance budget.
+    async fn run_benchmark(&self, request: &BenchmarkRequest) -> anyhow::Result<PerformanceResult> {
+        let _ = request;
+        anyhow::bail!("this runner does not support performance expectations")
+    }
+
+    /// Run the tests under Miri. Runners without Miri support report the
+    /// stage as skipped.
+    async fn run_miri(&self, request: &TestRequest) -> anyhow::Result<MiriResult> {
+        let _ = request;
+        Ok(MiriResult::skipped("this runner does not support Miri"))
+    }
+
+    /// Check formatting of generated code. Returns `None` if the runner
+    /// cannot check formatting for the language.
+    async fn check_formatting(
+        &self,
+        request: &FormatRequest,
+    ) -> anyhow::Result<Option<FormattingResult>> {
+        let _ = request;
+        Ok(None)
+    }
+
+    /// Find uses of items that don't exist in the dependency crates among a
+    /// failed compile's errors. The default trusts rustc's errors and makes
+    /// no suggestions; runners with access to the crates' sources can check
+    /// the items against them.
+    async fn check_api_usage(
+        &self,
+        request: &ApiCheckRequest,
+    ) -> anyhow::Result<Vec<HallucinatedApi>> {
+        Ok(find_hallucinated_apis(
+            &request.errors,
+            &request.code,
+            &request.dependencies,
+            &[],
+        ))
+    }
 }
 
 /// Request to compile code.
@@ -109,6 +208,9 @@ pub struct CompileRequest {
     pub dependencies: Vec<Dependency>,
     /// Compilation timeout in seconds.
     pub timeout_secs: u64,
+    /// Fixture crate to compile the code inside of.
+    #[serde(default)]
+    pub fixture: Option<Fixture>,
 }
 
 /// A crate dependency.
@@ -128,7 +230,8 @@ pub struct Dependency {
 pub struct TestRequest {
     /// The source code to test.
     pub code: String,
-    /// Test code to compile against the source.
+    /// Test code to compile against the source (may be empty for fixtures,
+    /// whose own test suite is run).
     pub test_code: String,
     /// Programming language.
     pub language: Language,
@@ -137,6 +240,9 @@ pub struct TestRequest {
     pub dependencies: Vec<Dependency>,
     /// Timeout in seconds.
     pub timeout_secs: u64,
+    /// Fixture crate to run inside of.
+    #[serde(default)]
+    pub fixture: Option<Fixture>,
 }
 
 /// Request to run clippy. Same shape as CompileRequest.
@@ -151,6 +257,79 @@ pub struct ClippyRequest {
     pub dependencies: Vec<Dependency>,
     /// Timeout in seconds.
     pub timeout_secs: u64,
+    /// Fixture crate to run inside of.
+    #[serde(default)]
+    pub fixture: Option<Fixture>,
+    /// Lint profile to run with.
+    #[serde(default)]
+    pub config: ClippyConfig,
+}
+
+/// Request to benchmark generated code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRequest {
+    /// The generated source code.
+    pub code: String,
+    /// Benchmark and budgets.
+    pub performance: PerformanceExpectation,
+    /// Baseline solution for relative budgets.
+    #[serde(default)]
+    pub reference_solution: Option<String>,
+    /// Programming language.
+    pub language: Language,
+    /// Additional dependencies.
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    /// Timeout in seconds.
+    pub timeout_secs: u64,
+    /// Fixture crate to run inside of.
+    #[serde(default)]
+    pub fixture: Option<Fixture>,
+}
+
+/// Request to check formatting of generated code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatRequest {
+    /// The source code to check.
+    pub code: String,
+    /// Programming language.
+    pub language: Language,
+    /// rustfmt configuration.
+    #[serde(default)]
+    pub config: FormattingConfig,
+    /// Timeout in seconds.
+    pub timeout_secs: u64,
+}
+
+/// Request to compile misuse snippets against the generated crate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompileFailRequest {
+    /// The generated source code.
+    pub code: String,
+    /// Snippets that are expected to fail to compile.
+    pub snippets: Vec<CompileFailExpectation>,
+    /// Programming language.
+    pub language: Language,
+    /// Additional dependencies.
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    /// Timeout in seconds (per snippet).
+    pub timeout_secs: u64,
+    /// Fixture crate to run inside of.
+    #[serde(default)]
+    pub fixture: Option<Fixture>,
+}
+
+/// Request to check a failed compile for non-existent dependency APIs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiCheckRequest {
+    /// The source code that failed to compile.
+    pub code: String,
+    /// The compiler errors.
+    pub errors: Vec<CompilerDiagnostic>,
+    /// The case's dependencies.
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
 }
 
 // ---------------------------------------------------------------------------
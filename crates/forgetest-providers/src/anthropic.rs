@@ -262,6 +262,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let response = provider.generate(&request).await.unwrap();
@@ -289,6 +290,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let err = provider.generate(&request).await.unwrap_err();
@@ -314,9 +316,28 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let err = provider.generate(&request).await.unwrap_err();
         assert!(err.to_string().contains("rate limited"));
     }
+
+    #[tokio::test]
+    async fn fim_completion_unsupported() {
+        let provider = AnthropicProvider::new("test-key", None);
+        let request = forgetest_core::traits::CompletionRequest {
+            model: "claude-sonnet-4-20250514".into(),
+            prefix: "fn add(".into(),
+            suffix: String::new(),
+            max_tokens: 64,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        };
+
+        let err = provider.complete(&request).await.unwrap_err();
+        let provider_err = err.downcast_ref::<ProviderError>().unwrap();
+        assert!(provider_err.is_permanent());
+    }
 }
@@ -6,6 +6,8 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use forgetest_core::budget::BudgetConfig;
+use forgetest_core::scoring::ScoringProfile;
 use forgetest_core::traits::LlmProvider;
 
 use crate::anthropic::AnthropicProvider;
@@ -96,6 +98,12 @@ pub struct ForgetestConfig {
     /// Output directory for results.
     #[serde(default = "default_output_dir")]
     pub output_dir: PathBuf,
+    /// Default scoring profile (`[scoring]`); eval sets can override it.
+    #[serde(default)]
+    pub scoring: Option<ScoringProfile>,
+    /// Spending limits for runs (`[budget.global]`, `[budget.per_model]`).
+    #[serde(default)]
+    pub budget: BudgetConfig,
 }
 
 fn default_provider() -> String {
@@ -128,6 +136,8 @@ impl Default for ForgetestConfig {
             retry_delay_ms: default_retry_delay(),
             parallelism: default_parallelism(),
             output_dir: default_output_dir(),
+            scoring: None,
+            budget: BudgetConfig::default(),
         }
     }
 }
@@ -337,5 +347,40 @@ default_model = "claude-sonnet-4-20250514"
             config.providers.get("anthropic"),
             Some(ProviderConfig::Anthropic { .. })
         ));
+        assert!(config.scoring.is_none());
+    }
+
+    #[test]
+    fn parse_scoring_profile() {
+        let toml_str = r#"
+[scoring]
+name = "tests-first"
+zero_on_compile_failure = false
+
+[scoring.weights]
+compilation = 0.2
+tests = 0.7
+structure = 0.0
+clippy = 0.1
+
+[scoring.clippy]
+curve = "exponential"
+
+[scoring.pass]
+min_tests = 0.8
+"#;
+        let config: ForgetestConfig = toml::from_str(toml_str).unwrap();
+        let scoring = config.scoring.unwrap();
+        assert_eq!(scoring.name, "tests-first");
+        assert!(!scoring.zero_on_compile_failure);
+        assert_eq!(scoring.weights.tests, 0.7);
+        assert_eq!(
+            scoring.clippy.curve,
+            forgetest_core::scoring::PenaltyCurve::Exponential
+        );
+        assert_eq!(scoring.pass.min_tests, 0.8);
+        // Unset criteria keep their defaults
+        assert_eq!(scoring.pass.min_compilation, 1.0);
+        assert!(scoring.problems().is_empty());
     }
 }
@@ -1,7 +1,8 @@
 //! forgetest-providers — LLM provider integrations.
 //!
 //! Implements the `LlmProvider` trait for Anthropic, OpenAI, and Ollama,
-//! allowing forgetest to generate code from multiple LLM backends.
+//! allowing forgetest to generate code from multiple LLM backends. Provider
+//! interactions can be recorded to a cassette and replayed offline.
 
 pub mod anthropic;
 pub mod config;
@@ -9,6 +10,8 @@ pub mod error;
 pub mod mock;
 pub mod ollama;
 pub mod openai;
+pub mod replay;
 
 pub use config::{create_provider, load_config, ForgetestConfig, ProviderConfig};
 pub use error::ProviderError;
+pub use replay::{Cassette, CassetteRecorder, RecordingProvider, ReplayProvider};
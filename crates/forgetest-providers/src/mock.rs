@@ -8,7 +8,8 @@ use async_trait::async_trait;
 
 use forgetest_core::results::TokenUsage;
 use forgetest_core::traits::{
-    extract_code_from_markdown, GenerateRequest, GenerateResponse, LlmProvider, ModelInfo,
+    extract_code_from_markdown, CompletionRequest, GenerateRequest, GenerateResponse, LlmProvider,
+    ModelInfo,
 };
 
 /// A mock LLM provider for testing the eval engine without real API calls.
@@ -92,6 +93,34 @@ impl LlmProvider for MockProvider {
         })
     }
 
+    async fn complete(&self, request: &CompletionRequest) -> anyhow::Result<GenerateResponse> {
+        self.call_count.fetch_add(1, Ordering::Relaxed);
+
+        // Match against the prefix; completions are returned raw, not markdown-extracted
+        let content = self
+            .responses
+            .iter()
+            .find(|(key, _)| request.prefix.contains(key.as_str()))
+            .map(|(_, v)| v.clone())
+            .unwrap_or_else(|| self.default_response.clone());
+
+        let prompt_tokens = ((request.prefix.len() + request.suffix.len()) / 4) as u32;
+        let completion_tokens = (content.len() / 4) as u32;
+
+        Ok(GenerateResponse {
+            extracted_code: content.clone(),
+            content,
+            model: request.model.clone(),
+            token_usage: TokenUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                estimated_cost_usd: 0.0,
+            },
+            latency_ms: 1,
+        })
+    }
+
     fn available_models(&self) -> Vec<ModelInfo> {
         vec![ModelInfo {
             id: "mock-model".into(),
@@ -119,6 +148,7 @@ mod tests {
             max_tokens: 100,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let response = provider.generate(&request).await.unwrap();
@@ -148,6 +178,7 @@ mod tests {
             max_tokens: 100,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let resp = provider.generate(&req_fib).await.unwrap();
@@ -161,10 +192,29 @@ mod tests {
             max_tokens: 100,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let resp = provider.generate(&req_add).await.unwrap();
         assert!(resp.content.contains("add"));
         assert_eq!(provider.call_count(), 2);
     }
+
+    #[tokio::test]
+    async fn fim_completion_returns_raw_text() {
+        let provider = MockProvider::with_fixed_response("    a + b");
+        let request = CompletionRequest {
+            model: "mock".into(),
+            prefix: "fn add(a: i32, b: i32) -> i32 {\n".into(),
+            suffix: "\n}".into(),
+            max_tokens: 64,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        };
+
+        let response = provider.complete(&request).await.unwrap();
+        assert_eq!(response.extracted_code, "    a + b");
+        assert_eq!(provider.call_count(), 1);
+    }
 }
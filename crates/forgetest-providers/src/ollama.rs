@@ -8,8 +8,8 @@ use tracing::instrument;
 
 use forgetest_core::results::TokenUsage;
 use forgetest_core::traits::{
-    extract_code_from_markdown, GenerateRequest, GenerateResponse, LlmProvider, ModelInfo,
-    DEFAULT_SYSTEM_PROMPT,
+    extract_code_from_markdown, CompletionRequest, GenerateRequest, GenerateResponse, LlmProvider,
+    ModelInfo, DEFAULT_SYSTEM_PROMPT,
 };
 
 use crate::error::ProviderError;
@@ -61,6 +61,29 @@ struct OllamaMessage {
 #[derive(Serialize)]
 struct OllamaOptions {
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct OllamaGenerateRequest {
+    model: String,
+    prompt: String,
+    suffix: String,
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Deserialize)]
+struct OllamaGenerateResponse {
+    response: String,
+    model: String,
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    eval_count: Option<u32>,
 }
 
 #[derive(Deserialize)]
@@ -130,6 +153,8 @@ impl LlmProvider for OllamaProvider {
             stream: false,
             options: Some(OllamaOptions {
                 temperature: request.temperature,
+                num_predict: None,
+                stop: Vec::new(),
             }),
         };
 
@@ -139,18 +164,7 @@ impl LlmProvider for OllamaProvider {
             .json(&body)
             .send()
             .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ProviderError::Timeout(DEFAULT_TIMEOUT_SECS)
-                } else if e.is_connect() {
-                    ProviderError::NetworkError(format!(
-                        "Ollama not reachable at {}. Is it running? Start with: ollama serve",
-                        self.base_url
-                    ))
-                } else {
-                    ProviderError::NetworkError(e.to_string())
-                }
-            })?;
+            .map_err(|e| self.map_send_error(e))?;
 
         let status = response.status().as_u16();
         if status == 404 {
@@ -196,6 +210,71 @@ impl LlmProvider for OllamaProvider {
         })
     }
 
+    #[instrument(skip(self, request), fields(model = %request.model))]
+    async fn complete(&self, request: &CompletionRequest) -> anyhow::Result<GenerateResponse> {
+        let start = Instant::now();
+
+        let body = OllamaGenerateRequest {
+            model: request.model.clone(),
+            prompt: request.prefix.clone(),
+            suffix: request.suffix.clone(),
+            stream: false,
+            options: OllamaOptions {
+                temperature: request.temperature,
+                num_predict: Some(request.max_tokens),
+                stop: request.stop_sequences.clone(),
+            },
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| self.map_send_error(e))?;
+
+        let status = response.status().as_u16();
+        if status == 404 {
+            return Err(ProviderError::ModelNotFound(format!(
+                "Model '{}' not found locally. Pull it with: ollama pull {}",
+                request.model, request.model
+            ))
+            .into());
+        }
+        if status >= 400 {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::ApiError {
+                status,
+                message: body,
+            }
+            .into());
+        }
+
+        let api_response: OllamaGenerateResponse =
+            response.json().await.map_err(|e| ProviderError::ApiError {
+                status: 0,
+                message: format!("failed to parse response: {e}"),
+            })?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        let prompt_tokens = api_response.prompt_eval_count.unwrap_or(0);
+        let completion_tokens = api_response.eval_count.unwrap_or(0);
+
+        Ok(GenerateResponse {
+            content: api_response.response.clone(),
+            extracted_code: api_response.response,
+            model: api_response.model,
+            token_usage: TokenUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                estimated_cost_usd: 0.0,
+            },
+            latency_ms,
+        })
+    }
+
     fn available_models(&self) -> Vec<ModelInfo> {
         // Try to dynamically fetch models — fall back to empty on error
         // Note: this is a sync context so we can't use async here.
@@ -205,6 +284,20 @@ impl LlmProvider for OllamaProvider {
 }
 
 impl OllamaProvider {
+    /// Classify a transport-level request failure.
+    fn map_send_error(&self, e: reqwest::Error) -> ProviderError {
+        if e.is_timeout() {
+            ProviderError::Timeout(DEFAULT_TIMEOUT_SECS)
+        } else if e.is_connect() {
+            ProviderError::NetworkError(format!(
+                "Ollama not reachable at {}. Is it running? Start with: ollama serve",
+                self.base_url
+            ))
+        } else {
+            ProviderError::NetworkError(e.to_string())
+        }
+    }
+
     /// Dynamically fetch available models from the Ollama instance.
     pub async fn list_models_async(&self) -> anyhow::Result<Vec<ModelInfo>> {
         let response = self
@@ -243,7 +336,7 @@ impl OllamaProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{body_partial_json, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
@@ -272,6 +365,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let response = provider.generate(&request).await.unwrap();
@@ -299,12 +393,51 @@ mod tests {
             max_tokens: 100,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let err = provider.generate(&request).await.unwrap_err();
         assert!(err.to_string().contains("not found"));
     }
 
+    #[tokio::test]
+    async fn fim_completion_uses_generate_with_suffix() {
+        let server = MockServer::start().await;
+
+        let response_body = serde_json::json!({
+            "response": "    a + b",
+            "model": "qwen2.5-coder:7b",
+            "prompt_eval_count": 12,
+            "eval_count": 4
+        });
+
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .and(body_partial_json(serde_json::json!({
+                "prompt": "fn add(a: i32, b: i32) -> i32 {\n",
+                "suffix": "\n}",
+                "stream": false
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&response_body))
+            .mount(&server)
+            .await;
+
+        let provider = OllamaProvider::new(&server.uri());
+        let request = CompletionRequest {
+            model: "qwen2.5-coder:7b".into(),
+            prefix: "fn add(a: i32, b: i32) -> i32 {\n".into(),
+            suffix: "\n}".into(),
+            max_tokens: 64,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        };
+
+        let response = provider.complete(&request).await.unwrap();
+        assert_eq!(response.extracted_code, "    a + b");
+        assert_eq!(response.token_usage.total_tokens, 16);
+    }
+
     #[tokio::test]
     async fn dynamic_model_listing() {
         let server = MockServer::start().await;
@@ -8,8 +8,8 @@ use tracing::instrument;
 
 use forgetest_core::results::TokenUsage;
 use forgetest_core::traits::{
-    extract_code_from_markdown, GenerateRequest, GenerateResponse, LlmProvider, ModelInfo,
-    DEFAULT_SYSTEM_PROMPT,
+    extract_code_from_markdown, CompletionRequest, GenerateRequest, GenerateResponse, LlmProvider,
+    ModelInfo, DEFAULT_SYSTEM_PROMPT,
 };
 
 use crate::error::ProviderError;
@@ -41,6 +41,77 @@ impl OpenAiProvider {
     }
 }
 
+impl OpenAiProvider {
+    /// POST a JSON body to an API path and classify error responses.
+    async fn post<T: Serialize>(
+        &self,
+        api_path: &str,
+        body: &T,
+        model: &str,
+    ) -> Result<reqwest::Response, ProviderError> {
+        let mut req = self
+            .client
+            .post(format!("{}{}", self.base_url, api_path))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("content-type", "application/json");
+
+        if let Some(org) = &self.org_id {
+            req = req.header("OpenAI-Organization", org);
+        }
+
+        let response = req.json(body).send().await.map_err(|e| {
+            if e.is_timeout() {
+                ProviderError::Timeout(DEFAULT_TIMEOUT_SECS)
+            } else {
+                ProviderError::NetworkError(e.to_string())
+            }
+        })?;
+
+        let status = response.status().as_u16();
+        if status == 429 {
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(5)
+                * 1000;
+            return Err(ProviderError::RateLimited {
+                retry_after_ms: retry_after,
+            });
+        }
+        if status == 401 {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::AuthenticationFailed(body));
+        }
+        if status == 404 {
+            return Err(ProviderError::ModelNotFound(model.to_string()));
+        }
+        if status >= 400 {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::ApiError {
+                status,
+                message: body,
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Estimate request cost from per-model pricing, falling back to GPT-4.1 pricing.
+    fn estimate_cost(&self, response_model: &str, request_model: &str, usage: &OpenAiUsage) -> f64 {
+        let (cost_per_1k_in, cost_per_1k_out) = self
+            .available_models()
+            .iter()
+            .find(|m| m.id == response_model || request_model == m.id)
+            .map(|m| (m.cost_per_1k_input, m.cost_per_1k_output))
+            .unwrap_or((0.002, 0.008));
+        (usage.prompt_tokens as f64 * cost_per_1k_in
+            + usage.completion_tokens as f64 * cost_per_1k_out)
+            / 1_000.0
+    }
+}
+
 #[derive(Serialize)]
 struct OpenAiRequest {
     model: String,
@@ -73,6 +144,30 @@ struct OpenAiChoiceMessage {
     content: String,
 }
 
+#[derive(Serialize)]
+struct OpenAiCompletionRequest {
+    model: String,
+    prompt: String,
+    suffix: String,
+    max_tokens: u32,
+    temperature: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiCompletionResponse {
+    choices: Vec<OpenAiCompletionChoice>,
+    #[serde(default)]
+    usage: OpenAiUsage,
+    model: String,
+}
+
+#[derive(Deserialize)]
+struct OpenAiCompletionChoice {
+    text: String,
+}
+
 #[derive(Deserialize, Default)]
 struct OpenAiUsage {
     #[serde(default)]
@@ -123,53 +218,9 @@ impl LlmProvider for OpenAiProvider {
             ],
         };
 
-        let mut req = self
-            .client
-            .post(format!("{}/v1/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("content-type", "application/json");
-
-        if let Some(org) = &self.org_id {
-            req = req.header("OpenAI-Organization", org);
-        }
-
-        let response = req.json(&body).send().await.map_err(|e| {
-            if e.is_timeout() {
-                ProviderError::Timeout(DEFAULT_TIMEOUT_SECS)
-            } else {
-                ProviderError::NetworkError(e.to_string())
-            }
-        })?;
-
-        let status = response.status().as_u16();
-        if status == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse::<u64>().ok())
-                .unwrap_or(5)
-                * 1000;
-            return Err(ProviderError::RateLimited {
-                retry_after_ms: retry_after,
-            }
-            .into());
-        }
-        if status == 401 {
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::AuthenticationFailed(body).into());
-        }
-        if status == 404 {
-            return Err(ProviderError::ModelNotFound(request.model.clone()).into());
-        }
-        if status >= 400 {
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::ApiError {
-                status,
-                message: body,
-            }
-            .into());
-        }
+        let response = self
+            .post("/v1/chat/completions", &body, &request.model)
+            .await?;
 
         let api_response: OpenAiResponse =
             response.json().await.map_err(|e| ProviderError::ApiError {
@@ -185,16 +236,8 @@ impl LlmProvider for OpenAiProvider {
             .unwrap_or_default();
         let extracted_code = extract_code_from_markdown(&content);
 
-        // Look up per-model pricing from available_models, fall back to GPT-4.1 pricing
-        let (cost_per_1k_in, cost_per_1k_out) = self
-            .available_models()
-            .iter()
-            .find(|m| m.id == api_response.model || request.model == m.id)
-            .map(|m| (m.cost_per_1k_input, m.cost_per_1k_output))
-            .unwrap_or((0.002, 0.008));
-        let estimated_cost = (api_response.usage.prompt_tokens as f64 * cost_per_1k_in
-            + api_response.usage.completion_tokens as f64 * cost_per_1k_out)
-            / 1_000.0;
+        let estimated_cost =
+            self.estimate_cost(&api_response.model, &request.model, &api_response.usage);
 
         Ok(GenerateResponse {
             content,
@@ -210,6 +253,50 @@ impl LlmProvider for OpenAiProvider {
         })
     }
 
+    #[instrument(skip(self, request), fields(model = %request.model))]
+    async fn complete(&self, request: &CompletionRequest) -> anyhow::Result<GenerateResponse> {
+        let start = Instant::now();
+
+        let body = OpenAiCompletionRequest {
+            model: request.model.clone(),
+            prompt: request.prefix.clone(),
+            suffix: request.suffix.clone(),
+            max_tokens: request.max_tokens,
+            temperature: request.temperature,
+            stop: request.stop_sequences.clone(),
+        };
+
+        let response = self.post("/v1/completions", &body, &request.model).await?;
+
+        let api_response: OpenAiCompletionResponse =
+            response.json().await.map_err(|e| ProviderError::ApiError {
+                status: 0,
+                message: format!("failed to parse response: {e}"),
+            })?;
+
+        let latency_ms = start.elapsed().as_millis() as u64;
+        let content = api_response
+            .choices
+            .first()
+            .map(|c| c.text.clone())
+            .unwrap_or_default();
+        let estimated_cost =
+            self.estimate_cost(&api_response.model, &request.model, &api_response.usage);
+
+        Ok(GenerateResponse {
+            extracted_code: content.clone(),
+            content,
+            model: api_response.model,
+            token_usage: TokenUsage {
+                prompt_tokens: api_response.usage.prompt_tokens,
+                completion_tokens: api_response.usage.completion_tokens,
+                total_tokens: api_response.usage.total_tokens,
+                estimated_cost_usd: estimated_cost,
+            },
+            latency_ms,
+        })
+    }
+
     fn available_models(&self) -> Vec<ModelInfo> {
         vec![
             ModelInfo {
@@ -243,7 +330,7 @@ impl LlmProvider for OpenAiProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{header, method, path};
+    use wiremock::matchers::{body_partial_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
@@ -272,6 +359,7 @@ mod tests {
             max_tokens: 1024,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let response = provider.generate(&request).await.unwrap();
@@ -304,12 +392,49 @@ mod tests {
             max_tokens: 100,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let response = provider.generate(&request).await.unwrap();
         assert_eq!(response.model, "custom-model");
     }
 
+    #[tokio::test]
+    async fn fim_completion_uses_completions_endpoint() {
+        let server = MockServer::start().await;
+
+        let response_body = serde_json::json!({
+            "choices": [{"text": "    a + b", "index": 0}],
+            "model": "gpt-3.5-turbo-instruct",
+            "usage": {"prompt_tokens": 12, "completion_tokens": 4, "total_tokens": 16}
+        });
+
+        Mock::given(method("POST"))
+            .and(path("/v1/completions"))
+            .and(body_partial_json(serde_json::json!({
+                "prompt": "fn add(a: i32, b: i32) -> i32 {\n",
+                "suffix": "\n}"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&response_body))
+            .mount(&server)
+            .await;
+
+        let provider = OpenAiProvider::new("key", Some(server.uri()), None);
+        let request = CompletionRequest {
+            model: "gpt-3.5-turbo-instruct".into(),
+            prefix: "fn add(a: i32, b: i32) -> i32 {\n".into(),
+            suffix: "\n}".into(),
+            max_tokens: 64,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        };
+
+        let response = provider.complete(&request).await.unwrap();
+        assert_eq!(response.extracted_code, "    a + b");
+        assert_eq!(response.token_usage.total_tokens, 16);
+    }
+
     #[tokio::test]
     async fn error_response() {
         let server = MockServer::start().await;
@@ -329,6 +454,7 @@ mod tests {
             max_tokens: 100,
             temperature: 0.0,
             stop_sequences: vec![],
+            attempt: 0,
         };
 
         let err = provider.generate(&request).await.unwrap_err();
@@ -0,0 +1,503 @@
+//! Record and replay provider interactions.
+//!
+//! [`RecordingProvider`] wraps a real provider and appends every request and
+//! response it sees to a cassette file. [`ReplayProvider`] serves those
+//! responses back, keyed by a hash of the request, so a full eval can run
+//! against real model output with no network and no API keys.
+//!
+//! A cassette is JSON Lines: a header line with the format version, then one
+//! interaction per line.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use forgetest_core::traits::{
+    CompletionRequest, GenerateRequest, GenerateResponse, LlmProvider, ModelInfo,
+};
+
+use crate::error::ProviderError;
+
+/// Cassette format version written by this build.
+const CASSETTE_VERSION: u32 = 2;
+
+/// A request as recorded in a cassette.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedRequest {
+    /// A chat-style generation.
+    Generate(GenerateRequest),
+    /// A fill-in-the-middle completion.
+    Complete(CompletionRequest),
+}
+
+impl RecordedRequest {
+    /// Model the request was made to.
+    pub fn model(&self) -> &str {
+        match self {
+            RecordedRequest::Generate(r) => &r.model,
+            RecordedRequest::Complete(r) => &r.model,
+        }
+    }
+
+    /// Hex SHA-256 of the provider name and the full request, including the
+    /// Pass@k attempt it was made for.
+    pub fn key(&self, provider: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(provider.as_bytes());
+        hasher.update([0]);
+        hasher.update(serde_json::to_vec(self).unwrap_or_default());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// One recorded request and the provider's response to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Request hash the response is replayed for.
+    pub key: String,
+    /// Provider name the request was sent to.
+    pub provider: String,
+    /// The request.
+    pub request: RecordedRequest,
+    /// The response, including raw content, token usage and latency.
+    pub response: GenerateResponse,
+}
+
+/// A recorded set of provider interactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Format version.
+    pub version: u32,
+    /// Interactions in the order they were recorded.
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Self {
+            version: CASSETTE_VERSION,
+            interactions: Vec::new(),
+        }
+    }
+}
+
+/// The first line of a cassette file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteHeader {
+    version: u32,
+}
+
+impl Cassette {
+    /// Load a cassette file.
+    ///
+    /// A last line that doesn't parse (a write interrupted by a crash) is
+    /// skipped with a warning.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read cassette {}", path.display()))?;
+        let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+        // Version 1 cassettes were a single JSON document
+        let header: CassetteHeader = serde_json::from_str(lines.next().unwrap_or_default())
+            .or_else(|_| serde_json::from_str(&content))
+            .with_context(|| format!("failed to parse cassette {}", path.display()))?;
+        anyhow::ensure!(
+            header.version == CASSETTE_VERSION,
+            "cassette {} has format version {}, but this build reads version \
+             {CASSETTE_VERSION}; record it again",
+            path.display(),
+            header.version
+        );
+
+        let lines: Vec<&str> = lines.collect();
+        let mut interactions = Vec::with_capacity(lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            match serde_json::from_str(line) {
+                Ok(interaction) => interactions.push(interaction),
+                Err(e) if i + 1 == lines.len() => {
+                    tracing::warn!("skipping truncated last line of {}: {e}", path.display());
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!(
+                            "invalid interaction on line {} of {}",
+                            i + 2,
+                            path.display()
+                        )
+                    })
+                }
+            }
+        }
+        Ok(Self {
+            version: header.version,
+            interactions,
+        })
+    }
+
+    /// Write the cassette to a file, replacing it atomically.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut content = header_line()?;
+        for interaction in &self.interactions {
+            content.push_str(&serde_json::to_string(interaction)?);
+            content.push('\n');
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, content)
+            .with_context(|| format!("failed to write cassette {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("failed to write cassette {}", path.display()))
+    }
+}
+
+fn header_line() -> Result<String> {
+    let mut line = serde_json::to_string(&CassetteHeader {
+        version: CASSETTE_VERSION,
+    })?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// A cassette being recorded, shared by the providers that feed it.
+#[derive(Debug)]
+pub struct CassetteRecorder {
+    path: PathBuf,
+    file: Mutex<File>,
+    recorded: AtomicUsize,
+}
+
+impl CassetteRecorder {
+    /// Start a new, empty cassette at `path`, replacing any existing one.
+    pub fn create(path: &Path) -> Result<Self> {
+        let mut file = File::create(path)
+            .with_context(|| format!("failed to create cassette {}", path.display()))?;
+        file.write_all(header_line()?.as_bytes())
+            .with_context(|| format!("failed to write cassette {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+            recorded: AtomicUsize::new(0),
+        })
+    }
+
+    /// Path of the cassette file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of interactions recorded so far.
+    pub fn len(&self) -> usize {
+        self.recorded.load(Ordering::Relaxed)
+    }
+
+    /// Whether nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append an interaction to the file, so a run that dies part-way keeps
+    /// everything recorded before it.
+    pub fn record(
+        &self,
+        provider: &str,
+        request: RecordedRequest,
+        response: &GenerateResponse,
+    ) -> Result<()> {
+        let interaction = Interaction {
+            key: request.key(provider),
+            provider: provider.to_string(),
+            request,
+            response: response.clone(),
+        };
+        let mut line = serde_json::to_string(&interaction)?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        // One write per line, so a crash can only tear the last one
+        file.write_all(line.as_bytes())
+            .with_context(|| format!("failed to append to cassette {}", self.path.display()))?;
+        self.recorded.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Record an interaction, logging rather than returning a failure: the
+    /// response has been paid for, so the request must not be retried.
+    fn record_or_warn(
+        &self,
+        provider: &str,
+        request: RecordedRequest,
+        response: &GenerateResponse,
+    ) {
+        if let Err(e) = self.record(provider, request, response) {
+            tracing::warn!("{e:#}");
+        }
+    }
+}
+
+/// Wraps a provider and records its interactions to a cassette.
+pub struct RecordingProvider {
+    inner: Arc<dyn LlmProvider>,
+    provider: String,
+    recorder: Arc<CassetteRecorder>,
+}
+
+impl RecordingProvider {
+    /// Record the interactions of `inner`, configured under the name `provider`.
+    pub fn new(
+        inner: Arc<dyn LlmProvider>,
+        provider: impl Into<String>,
+        recorder: Arc<CassetteRecorder>,
+    ) -> Self {
+        Self {
+            inner,
+            provider: provider.into(),
+            recorder,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RecordingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn generate(&self, request: &GenerateRequest) -> Result<GenerateResponse> {
+        let response = self.inner.generate(request).await?;
+        self.recorder.record_or_warn(
+            &self.provider,
+            RecordedRequest::Generate(request.clone()),
+            &response,
+        );
+        Ok(response)
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<GenerateResponse> {
+        let response = self.inner.complete(request).await?;
+        self.recorder.record_or_warn(
+            &self.provider,
+            RecordedRequest::Complete(request.clone()),
+            &response,
+        );
+        Ok(response)
+    }
+
+    fn available_models(&self) -> Vec<ModelInfo> {
+        self.inner.available_models()
+    }
+}
+
+/// Serves a provider's recorded responses from a cassette.
+///
+/// Requests are matched by hash, so anything that changes the request (the
+/// prompt, context files, sampling settings, the Pass@k attempt) misses and
+/// fails with [`ProviderError::NotRecorded`]. Identical requests get the
+/// responses recorded for them in turn; once those run out, they miss too.
+pub struct ReplayProvider {
+    provider: String,
+    responses: HashMap<String, Vec<GenerateResponse>>,
+    models: BTreeSet<String>,
+    served: Mutex<HashMap<String, usize>>,
+}
+
+impl ReplayProvider {
+    /// Replay the interactions recorded for `provider` in `cassette`.
+    pub fn new(cassette: &Cassette, provider: &str) -> Self {
+        let mut responses: HashMap<String, Vec<GenerateResponse>> = HashMap::new();
+        let mut models = BTreeSet::new();
+        for interaction in cassette
+            .interactions
+            .iter()
+            .filter(|i| i.provider == provider)
+        {
+            models.insert(interaction.request.model().to_string());
+            responses
+                .entry(interaction.key.clone())
+                .or_default()
+                .push(interaction.response.clone());
+        }
+        Self {
+            provider: provider.to_string(),
+            responses,
+            models,
+            served: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn replay(&self, request: RecordedRequest) -> Result<GenerateResponse> {
+        let key = request.key(&self.provider);
+        let Some(responses) = self.responses.get(&key) else {
+            return Err(ProviderError::NotRecorded(format!(
+                "{}/{} request {}",
+                self.provider,
+                request.model(),
+                &key[..12]
+            ))
+            .into());
+        };
+        let mut served = self.served.lock().unwrap_or_else(|e| e.into_inner());
+        let n = served.entry(key.clone()).or_default();
+        let Some(response) = responses.get(*n) else {
+            return Err(ProviderError::NotRecorded(format!(
+                "{}/{} request {} beyond the {} recorded",
+                self.provider,
+                request.model(),
+                &key[..12],
+                responses.len()
+            ))
+            .into());
+        };
+        *n += 1;
+        Ok(response.clone())
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ReplayProvider {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    async fn generate(&self, request: &GenerateRequest) -> Result<GenerateResponse> {
+        self.replay(RecordedRequest::Generate(request.clone()))
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<GenerateResponse> {
+        self.replay(RecordedRequest::Complete(request.clone()))
+    }
+
+    fn available_models(&self) -> Vec<ModelInfo> {
+        self.models
+            .iter()
+            .map(|model| ModelInfo {
+                id: model.clone(),
+                name: model.clone(),
+                provider: self.provider.clone(),
+                max_context: 0,
+                cost_per_1k_input: 0.0,
+                cost_per_1k_output: 0.0,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockProvider;
+    use forgetest_core::results::TokenUsage;
+
+    fn request(prompt: &str) -> GenerateRequest {
+        GenerateRequest {
+            model: "mock-model".into(),
+            prompt: prompt.into(),
+            system_prompt: None,
+            context_files: vec![],
+            max_tokens: 100,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_replay_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.jsonl");
+        let recorder = Arc::new(CassetteRecorder::create(&path).unwrap());
+        let mut responses = HashMap::new();
+        responses.insert("add".to_string(), "fn add() {}".to_string());
+        let recording = RecordingProvider::new(
+            Arc::new(MockProvider::new(responses)),
+            "anthropic",
+            Arc::clone(&recorder),
+        );
+
+        let live = recording.generate(&request("Write add")).await.unwrap();
+        recording.generate(&request("Write sub")).await.unwrap();
+        let fim = CompletionRequest {
+            model: "mock-model".into(),
+            prefix: "fn add(a: i32, b: i32) -> i32 {\n".into(),
+            suffix: "\n}".into(),
+            max_tokens: 64,
+            temperature: 0.0,
+            stop_sequences: vec![],
+            attempt: 0,
+        };
+        recording.complete(&fim).await.unwrap();
+        assert_eq!(recorder.len(), 3);
+
+        let cassette = Cassette::load(&path).unwrap();
+        let replay = ReplayProvider::new(&cassette, "anthropic");
+        let replayed = replay.generate(&request("Write add")).await.unwrap();
+        assert_eq!(replayed.content, live.content);
+        assert_eq!(replayed.latency_ms, live.latency_ms);
+        assert_eq!(
+            replayed.token_usage.total_tokens,
+            live.token_usage.total_tokens
+        );
+        assert!(replay.complete(&fim).await.is_ok());
+        assert_eq!(replay.available_models()[0].id, "mock-model");
+
+        // A changed request, or another provider's name, is a permanent miss
+        let err = replay.generate(&request("Write mul")).await.unwrap_err();
+        assert!(err.downcast_ref::<ProviderError>().unwrap().is_permanent());
+        let other = ReplayProvider::new(&cassette, "openai");
+        assert!(other.generate(&request("Write add")).await.is_err());
+
+        // A torn last line loses only that interaction; the old format is refused
+        let mut torn = std::fs::read_to_string(&path).unwrap();
+        torn.push_str("{\"key\": \"ab");
+        std::fs::write(&path, torn).unwrap();
+        assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 3);
+        std::fs::write(&path, "{\n  \"version\": 1,\n  \"interactions\": []\n}").unwrap();
+        let err = Cassette::load(&path).unwrap_err();
+        assert!(err.to_string().contains("format version 1"));
+    }
+
+    #[tokio::test]
+    async fn identical_requests_replay_in_turn_until_they_run_out() {
+        let mut cassette = Cassette::default();
+        for code in ["fn a() {}", "fn b() {}"] {
+            let request = RecordedRequest::Generate(request("same"));
+            cassette.interactions.push(Interaction {
+                key: request.key("p"),
+                provider: "p".into(),
+                request,
+                response: GenerateResponse {
+                    content: code.into(),
+                    extracted_code: code.into(),
+                    model: "mock-model".into(),
+                    token_usage: TokenUsage {
+                        prompt_tokens: 10,
+                        completion_tokens: 5,
+                        total_tokens: 15,
+                        estimated_cost_usd: 0.01,
+                    },
+                    latency_ms: 1200,
+                },
+            });
+        }
+
+        let replay = ReplayProvider::new(&cassette, "p");
+        let mut codes = Vec::new();
+        for _ in 0..2 {
+            codes.push(replay.generate(&request("same")).await.unwrap().content);
+        }
+        assert_eq!(codes, ["fn a() {}", "fn b() {}"]);
+        let err = replay.generate(&request("same")).await.unwrap_err();
+        assert!(err.downcast_ref::<ProviderError>().unwrap().is_permanent());
+
+        // Another attempt is another request
+        let mut second = request("same");
+        second.attempt = 2;
+        assert!(replay.generate(&second).await.is_err());
+    }
+}
@@ -5,7 +5,12 @@
 use anyhow::Result;
 use std::path::Path;
 
+use forgetest_core::confidence::percent_with_interval;
+use forgetest_core::head_to_head::{format_delta, format_metric, HeadToHead};
+use forgetest_core::leaderboard::Leaderboard;
 use forgetest_core::report::EvalReport;
+use forgetest_core::statistics::{group_order, GroupStats};
+use forgetest_core::taxonomy::{result_categories, ErrorCategory};
 
 /// Escape a string for safe HTML insertion.
 fn html_escape(s: &str) -> String {
@@ -42,6 +47,19 @@ pub fn generate_html(report: &EvalReport) -> String {
         report.models_evaluated.len(),
         report.created_at.format("%Y-%m-%d %H:%M:%S UTC")
     ));
+    if let Some(original) = report.rescored_from {
+        html.push_str(&format!(
+            "<p class=\"meta\">Rescored from the generations of report <code>{original}</code></p>\n"
+        ));
+    }
+    if let Some(budget) = report.budget.as_ref().filter(|b| b.is_partial()) {
+        let reasons: Vec<String> = budget.exhausted.iter().map(|e| e.to_string()).collect();
+        html.push_str(&format!(
+            "<p class=\"partial\"><strong>Partial report:</strong> {}. {} generations were skipped.</p>\n",
+            html_escape(&reasons.join("; ")),
+            budget.skipped
+        ));
+    }
     html.push_str("</header>\n");
 
     // Summary dashboard
@@ -50,21 +68,59 @@ pub fn generate_html(report: &EvalReport) -> String {
 
     // Model summary table
     html.push_str("<table class=\"summary\">\n");
-    html.push_str("<thead><tr><th>Model</th><th>Pass@1</th><th>Compile %</th><th>Test Pass %</th><th>Cost</th><th>Avg Latency</th></tr></thead>\n");
+    html.push_str("<thead><tr><th>Model</th><th>Pass@1</th><th>Compile %</th><th>Test Pass %</th><th>Cost</th><th>Avg Latency</th><th>Judge</th><th>Judge Cost</th><th>Hallucinated APIs</th></tr></thead>\n");
     html.push_str("<tbody>\n");
     for (model, stats) in &report.aggregate.per_model {
         let pass_1 = stats.pass_at_k.get(&1).copied().unwrap_or(0.0);
+        let judge = match stats.avg_judge_score {
+            Some(score) => format!("{:.1}%", score * 100.0),
+            None => "-".to_string(),
+        };
+        let ci = stats.intervals.as_ref();
         html.push_str(&format!(
-            "<tr><td>{}</td><td>{:.1}%</td><td>{:.1}%</td><td>{:.1}%</td><td>${:.4}</td><td>{}ms</td></tr>\n",
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>${:.4}</td><td>{}ms</td><td>{}</td><td>${:.4}</td><td>{:.1}% ({})</td></tr>\n",
             html_escape(model),
-            pass_1 * 100.0,
-            stats.avg_compilation_rate * 100.0,
-            stats.avg_test_pass_rate * 100.0,
+            percent_with_interval(pass_1, ci.and_then(|c| c.pass_at_k.get(&1))),
+            percent_with_interval(
+                stats.avg_compilation_rate,
+                ci.and_then(|c| c.compilation_rate.as_ref())
+            ),
+            percent_with_interval(
+                stats.avg_test_pass_rate,
+                ci.and_then(|c| c.test_pass_rate.as_ref())
+            ),
             stats.total_cost_usd,
             stats.avg_latency_ms,
+            judge,
+            stats.judge_cost_usd,
+            stats.hallucination_rate * 100.0,
+            stats.hallucinated_apis,
         ));
     }
     html.push_str("</tbody></table>\n");
+    if let Some(ci) = report
+        .aggregate
+        .per_model
+        .values()
+        .find_map(|s| s.intervals.as_ref())
+    {
+        html.push_str(&format!(
+            "<p class=\"meta\">Ranges in brackets: {}</p>\n",
+            ci.describe()
+        ));
+    }
+
+    let cached: usize = report
+        .aggregate
+        .per_model
+        .values()
+        .map(|s| s.cached_results)
+        .sum();
+    if cached > 0 {
+        html.push_str(&format!(
+            "<p class=\"meta\">{cached} generations came from the response cache; their cost and latency are not included.</p>\n"
+        ));
+    }
 
     // SVG bar chart for Pass@1
     if !report.aggregate.per_model.is_empty() {
@@ -73,11 +129,76 @@ pub fn generate_html(report: &EvalReport) -> String {
 
     html.push_str("</section>\n");
 
+    // Model x tag and model x difficulty heatmaps
+    for (title, groups) in [
+        ("By Tag", &report.aggregate.per_tag),
+        ("By Difficulty", &report.aggregate.per_difficulty),
+    ] {
+        if !groups.is_empty() {
+            html.push_str(&generate_heatmap(title, groups));
+        }
+    }
+
+    // Compile failures per model and error category
+    let mut failing: Vec<_> = report
+        .aggregate
+        .per_model
+        .iter()
+        .filter(|(_, stats)| !stats.failure_modes.is_empty())
+        .collect();
+    if !failing.is_empty() {
+        failing.sort_by_key(|(model, _)| *model);
+        let categories: Vec<ErrorCategory> = ErrorCategory::ALL
+            .into_iter()
+            .filter(|c| failing.iter().any(|(_, s)| s.failure_modes.contains_key(c)))
+            .collect();
+        html.push_str("<section>\n<h2>Failure Modes</h2>\n<table class=\"summary\">\n<thead><tr><th>Model</th>");
+        for category in &categories {
+            html.push_str(&format!("<th>{category}</th>"));
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+        for (model, stats) in failing {
+            html.push_str(&format!("<tr><td>{}</td>", html_escape(model)));
+            for category in &categories {
+                html.push_str(&format!(
+                    "<td>{}</td>",
+                    stats.failure_modes.get(category).copied().unwrap_or(0)
+                ));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>\n<p class=\"meta\">Attempts failing to compile with at least one error of each category.</p>\n</section>\n");
+    }
+
+    // Calls into dependency APIs that don't exist
+    let hallucinated: Vec<_> = report
+        .results
+        .iter()
+        .flat_map(|r| r.hallucinated_apis.iter().map(move |api| (r, api)))
+        .collect();
+    if !hallucinated.is_empty() {
+        html.push_str("<section>\n<h2>Non-existent APIs</h2>\n<table class=\"summary\">\n<thead><tr><th>Case</th><th>Model</th><th>Crate</th><th>Path</th><th>Did you mean</th></tr></thead>\n<tbody>\n");
+        for (r, api) in hallucinated {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>\n",
+                html_escape(&r.case_id),
+                html_escape(&r.model),
+                html_escape(&api.crate_name),
+                html_escape(&api.path),
+                api.suggestion.as_ref().map_or("-".to_string(), |s| format!(
+                    "<code>{}</code>",
+                    html_escape(s)
+                )),
+            ));
+        }
+        html.push_str("</tbody>\n</table>\n</section>\n");
+    }
+
     // Per-case results
     html.push_str("<section class=\"results\">\n");
     html.push_str("<h2>Results</h2>\n");
     html.push_str("<table class=\"results-table\" id=\"results\">\n");
-    html.push_str("<thead><tr><th onclick=\"sortTable(0)\">Case</th><th onclick=\"sortTable(1)\">Model</th><th onclick=\"sortTable(2)\">Compile</th><th onclick=\"sortTable(3)\">Tests</th><th onclick=\"sortTable(4)\">Attempt</th></tr></thead>\n");
+    html.push_str("<thead><tr><th onclick=\"sortTable(0)\">Case</th><th onclick=\"sortTable(1)\">Model</th><th onclick=\"sortTable(2)\">Compile</th><th onclick=\"sortTable(3)\">Tests</th><th onclick=\"sortTable(4)\">Attempt</th><th onclick=\"sortTable(5)\">Judge</th></tr></thead>\n");
     html.push_str("<tbody>\n");
 
     for r in &report.results {
@@ -86,16 +207,38 @@ pub fn generate_html(report: &EvalReport) -> String {
         } else {
             "fail"
         };
-        let compile_text = if r.compilation.success { "OK" } else { "FAIL" };
+        let compile_text = if r.compilation.success {
+            "OK".to_string()
+        } else {
+            let categories: Vec<String> = result_categories(r)
+                .into_iter()
+                .map(|c| c.to_string())
+                .collect();
+            if categories.is_empty() {
+                "FAIL".to_string()
+            } else {
+                format!("FAIL ({})", categories.join(", "))
+            }
+        };
 
         let test_text = match &r.test_execution {
             Some(t) => format!("{}/{}", t.passed, t.passed + t.failed),
             None => "-".to_string(),
         };
 
+        // The rationale is shown as a tooltip on the judge score
+        let judge_cell = match &r.judge {
+            Some(j) => format!(
+                "<td title=\"{}\">{:.1}%</td>",
+                html_escape(&j.rationale),
+                j.score * 100.0
+            ),
+            None => "<td>-</td>".to_string(),
+        };
+
         html.push_str(&format!(
-            "<tr class=\"{}\"><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td></tr>\n",
-            compile_class, html_escape(&r.case_id), html_escape(&r.model), compile_class, compile_text, test_text, r.attempt
+            "<tr class=\"{}\"><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td>{}</tr>\n",
+            compile_class, html_escape(&r.case_id), html_escape(&r.model), compile_class, compile_text, test_text, r.attempt, judge_cell
         ));
     }
 
@@ -134,6 +277,272 @@ pub fn write_html_report(report: &EvalReport, path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Heatmap table of pass rates with models as rows and groups as columns.
+fn generate_heatmap(
+    title: &str,
+    groups: &std::collections::HashMap<String, std::collections::HashMap<String, GroupStats>>,
+) -> String {
+    let labels = group_order(groups);
+    let mut models: Vec<&String> = groups.values().flat_map(|m| m.keys()).collect();
+    models.sort();
+    models.dedup();
+
+    let mut html = format!(
+        "<section>\n<h2>{title}</h2>\n<table class=\"heatmap\">\n<thead><tr><th>Model</th>"
+    );
+    for label in &labels {
+        html.push_str(&format!("<th>{}</th>", html_escape(label)));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for model in models {
+        html.push_str(&format!("<tr><td>{}</td>", html_escape(model)));
+        for label in &labels {
+            match groups[*label].get(model) {
+                // Red (0%) through yellow to green (100%)
+                Some(stats) => html.push_str(&format!(
+                    "<td style=\"background: hsla({:.0}, 75%, 50%, 0.35)\" title=\"{} cases, avg score {:.1}%, compiles {:.1}%\">{:.1}%</td>",
+                    stats.pass_rate * 120.0,
+                    stats.cases,
+                    stats.avg_score * 100.0,
+                    stats.compilation_rate * 100.0,
+                    stats.pass_rate * 100.0
+                )),
+                None => html.push_str("<td>-</td>"),
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n<p class=\"meta\">Pass rate: average fraction of correct attempts per case. Hover a cell for case count, score and compile rate.</p>\n</section>\n");
+    html
+}
+
+/// Generate an HTML page for a head-to-head model comparison.
+pub fn generate_head_to_head_html(h2h: &HeadToHead) -> String {
+    let (a, b) = (html_escape(&h2h.model_a), html_escape(&h2h.model_b));
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>forgetest — {a} vs {b}</title>\n"));
+    html.push_str("<style>\n");
+    html.push_str(CSS);
+    html.push_str("</style>\n");
+    html.push_str("</head>\n<body>\n");
+
+    html.push_str(&format!("<header>\n<h1>{a} vs {b}</h1>\n"));
+    html.push_str(&format!(
+        "<p class=\"meta\"><strong>{a}</strong> wins {} | <strong>{b}</strong> wins {} | ties {} (over {} shared cases)</p>\n</header>\n",
+        h2h.wins_a,
+        h2h.wins_b,
+        h2h.ties,
+        h2h.cases.len()
+    ));
+
+    // Solved matrix
+    html.push_str("<section>\n<h2>Solved</h2>\n<table class=\"summary\">\n");
+    html.push_str(&format!(
+        "<thead><tr><th></th><th>{b} solved</th><th>{b} unsolved</th></tr></thead>\n<tbody>\n"
+    ));
+    html.push_str(&format!(
+        "<tr><th>{a} solved</th><td>{}</td><td>{}</td></tr>\n<tr><th>{a} unsolved</th><td>{}</td><td>{}</td></tr>\n",
+        h2h.both_solved,
+        h2h.only_a_solved.len(),
+        h2h.only_b_solved.len(),
+        h2h.neither_solved
+    ));
+    html.push_str("</tbody>\n</table>\n");
+    html.push_str(&format!(
+        "<p class=\"meta\">McNemar exact test: p = {:.4}</p>\n",
+        h2h.mcnemar_p
+    ));
+    for (model, cases) in [(&a, &h2h.only_a_solved), (&b, &h2h.only_b_solved)] {
+        if !cases.is_empty() {
+            let list: Vec<String> = cases
+                .iter()
+                .map(|c| format!("<code>{}</code>", html_escape(c)))
+                .collect();
+            html.push_str(&format!(
+                "<p>Only {model} solved: {}</p>\n",
+                list.join(", ")
+            ));
+        }
+    }
+    html.push_str("</section>\n");
+
+    // Paired metric deltas
+    html.push_str("<section>\n<h2>Metrics</h2>\n<table class=\"summary\">\n");
+    html.push_str(&format!(
+        "<thead><tr><th>Metric</th><th>{a}</th><th>{b}</th><th>Delta</th><th>p</th></tr></thead>\n<tbody>\n"
+    ));
+    for d in &h2h.deltas {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.4}</td></tr>\n",
+            html_escape(&d.metric),
+            format_metric(&d.metric, d.a),
+            format_metric(&d.metric, d.b),
+            format_delta(&d.metric, d.delta),
+            d.p_value
+        ));
+    }
+    html.push_str("</tbody>\n</table>\n</section>\n");
+
+    // Per-case outcomes
+    html.push_str("<section>\n<h2>Cases</h2>\n<table id=\"results\">\n");
+    html.push_str(&format!(
+        "<thead><tr><th onclick=\"sortTable(0)\">Case</th><th onclick=\"sortTable(1)\">{a}</th><th onclick=\"sortTable(2)\">{b}</th><th onclick=\"sortTable(3)\">Winner</th></tr></thead>\n<tbody>\n"
+    ));
+    for c in &h2h.cases {
+        let cell = |score: f64, solved: bool| {
+            format!(
+                "<td class=\"{}\">{:.1}%</td>",
+                if solved { "pass" } else { "fail" },
+                score * 100.0
+            )
+        };
+        html.push_str(&format!(
+            "<tr><td>{}</td>{}{}<td>{}</td></tr>\n",
+            html_escape(&c.case_id),
+            cell(c.score_a, c.solved_a),
+            cell(c.score_b, c.solved_b),
+            html_escape(h2h.winner_name(c.winner))
+        ));
+    }
+    html.push_str("</tbody>\n</table>\n</section>\n");
+
+    html.push_str("<script>\n");
+    html.push_str(JS);
+    html.push_str("</script>\n");
+    html.push_str("</body>\n</html>");
+    html
+}
+
+/// Generate an HTML page for a leaderboard.
+pub fn generate_leaderboard_html(board: &Leaderboard) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str("<title>forgetest leaderboard</title>\n");
+    html.push_str("<style>\n");
+    html.push_str(CSS);
+    html.push_str("</style>\n");
+    html.push_str("</head>\n<body>\n");
+
+    html.push_str("<header>\n<h1>forgetest leaderboard</h1>\n");
+    html.push_str(&format!(
+        "<p class=\"meta\">{} models | {} shared cases | {} reports | Bradley-Terry ratings on the Elo scale with {:.0}% bootstrap intervals</p>\n</header>\n",
+        board.entries.len(),
+        board.cases,
+        board.reports,
+        board.level * 100.0
+    ));
+    if board.groups > 1 {
+        html.push_str("<p>Some models never ran on the same cases, so they are ranked in separate groups. Ratings are not comparable across groups.</p>\n");
+    }
+
+    for group in 0..board.groups {
+        html.push_str("<section>\n");
+        if board.groups > 1 {
+            html.push_str(&format!("<h2>Group {}</h2>\n", group + 1));
+        }
+        html.push_str("<table class=\"summary\">\n");
+        html.push_str("<thead><tr><th>Rank</th><th>Model</th><th>Rating</th><th>Interval</th><th>Cases</th><th>W-L-T</th></tr></thead>\n<tbody>\n");
+        let entries: Vec<_> = board.entries.iter().filter(|e| e.group == group).collect();
+        for e in &entries {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{:.0}</td><td>{:.0}-{:.0}</td><td>{}</td><td>{}-{}-{}</td></tr>\n",
+                e.rank,
+                html_escape(&e.model),
+                e.rating,
+                e.interval.lower,
+                e.interval.upper,
+                e.cases,
+                e.wins,
+                e.losses,
+                e.ties
+            ));
+        }
+        html.push_str("</tbody>\n</table>\n");
+        html.push_str(&generate_interval_chart(&entries));
+        html.push_str("</section>\n");
+    }
+
+    if !board.unrated.is_empty() {
+        let list: Vec<String> = board
+            .unrated
+            .iter()
+            .map(|m| format!("<code>{}</code>", html_escape(m)))
+            .collect();
+        html.push_str(&format!(
+            "<p>Unrated (no cases shared with another model): {}</p>\n",
+            list.join(", ")
+        ));
+    }
+
+    html.push_str("</body>\n</html>");
+    html
+}
+
+/// Write a leaderboard HTML page to a file.
+pub fn write_leaderboard_html(board: &Leaderboard, path: &Path) -> Result<()> {
+    let html = generate_leaderboard_html(board);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, html)?;
+    Ok(())
+}
+
+/// SVG plot of each model's rating interval, with a dot at the rating.
+fn generate_interval_chart(entries: &[&forgetest_core::leaderboard::LeaderboardEntry]) -> String {
+    let row_height = 30;
+    let max_width = 400.0;
+    let label_width = 200;
+
+    let lowest = entries
+        .iter()
+        .map(|e| e.interval.lower)
+        .fold(f64::INFINITY, f64::min);
+    let highest = entries
+        .iter()
+        .map(|e| e.interval.upper)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let span = (highest - lowest).max(1.0);
+    let x = |rating: f64| label_width as f64 + (rating - lowest) / span * max_width;
+
+    let mut svg = format!(
+        "<svg width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">\n",
+        label_width + max_width as usize + 60,
+        entries.len() * row_height + 10
+    );
+    for (i, e) in entries.iter().enumerate() {
+        let y = i * row_height + row_height / 2 + 5;
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" font-size=\"14\" fill=\"currentColor\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>\n",
+            label_width - 10,
+            y,
+            html_escape(&e.model)
+        ));
+        svg.push_str(&format!(
+            "  <line x1=\"{:.1}\" y1=\"{y}\" x2=\"{:.1}\" y2=\"{y}\" stroke=\"#6b7280\" stroke-width=\"3\"/>\n",
+            x(e.interval.lower),
+            x(e.interval.upper)
+        ));
+        svg.push_str(&format!(
+            "  <circle cx=\"{:.1}\" cy=\"{y}\" r=\"5\" fill=\"#2563eb\"/>\n",
+            x(e.rating)
+        ));
+        svg.push_str(&format!(
+            "  <text x=\"{:.1}\" y=\"{y}\" font-size=\"12\" fill=\"currentColor\" dominant-baseline=\"middle\">{:.0}</text>\n",
+            x(e.interval.upper) + 8.0,
+            e.rating
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
 fn generate_bar_chart(
     per_model: &std::collections::HashMap<String, forgetest_core::statistics::ModelStats>,
 ) -> String {
@@ -197,10 +606,12 @@ const CSS: &str = r#"
 body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif; margin: 0; padding: 2rem; background: var(--bg); color: var(--fg); }
 h1, h2 { margin-top: 2rem; }
 .meta { color: #6b7280; }
+.partial { background: var(--fail); padding: 0.5rem 1rem; border-radius: 8px; }
 table { border-collapse: collapse; width: 100%; margin: 1rem 0; }
 th, td { border: 1px solid var(--border); padding: 0.5rem 1rem; text-align: left; }
 th { background: var(--border); cursor: pointer; }
 .pass { background: var(--pass); }
+.heatmap td { text-align: center; }
 .fail { background: var(--fail); }
 pre { overflow-x: auto; padding: 1rem; background: var(--border); border-radius: 8px; }
 code { font-family: 'JetBrains Mono', 'Fira Code', monospace; font-size: 0.85rem; }
@@ -263,6 +674,14 @@ mod tests {
                     failures: vec![],
                 }),
                 clippy: None,
+                compile_fail: vec![],
+                hallucinated_apis: vec![],
+                formatting: None,
+                miri: None,
+                performance: None,
+                metrics: None,
+                judge: None,
+                score: None,
                 timing: TimingInfo {
                     llm_request_ms: 500,
                     compilation_ms: 100,
@@ -275,6 +694,7 @@ mod tests {
                     total_tokens: 150,
                     estimated_cost_usd: 0.001,
                 },
+                cached: false,
                 attempt: 1,
                 run_id: uuid::Uuid::nil(),
             }],
@@ -296,12 +716,28 @@ mod tests {
                             total_tokens: 150,
                             total_cost_usd: 0.001,
                             avg_latency_ms: 650,
+                            cached_results: 0,
+                            cached_cost_usd: 0.0,
+                            metrics: None,
+                            avg_judge_score: None,
+                            judge_tokens: 0,
+                            judge_cost_usd: 0.0,
+                            failure_modes: Default::default(),
+                            hallucinated_apis: 0,
+                            hallucination_rate: 0.0,
+                            intervals: None,
                         },
                     );
                     m
                 },
                 per_case: HashMap::new(),
+                per_tag: HashMap::new(),
+                per_difficulty: HashMap::new(),
             },
+            scoring: None,
+            cases: Default::default(),
+            rescored_from: None,
+            budget: None,
             duration_ms: 1000,
         }
     }
@@ -318,6 +754,178 @@ mod tests {
         assert!(html.contains("Test Set"));
     }
 
+    #[test]
+    fn html_report_shows_judge_scores() {
+        let mut report = make_test_report();
+        report.results[0].judge = Some(JudgeResult {
+            model: "judge".into(),
+            criteria: Default::default(),
+            score: 0.75,
+            rationale: "Uses <unwrap> everywhere".into(),
+            token_usage: TokenUsage {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+                estimated_cost_usd: 0.0,
+            },
+            cached: false,
+        });
+        let html = generate_html(&report);
+
+        assert!(html.contains("<td title=\"Uses &lt;unwrap&gt; everywhere\">75.0%</td>"));
+    }
+
+    #[test]
+    fn html_report_shows_tag_heatmap() {
+        let mut report = make_test_report();
+        let stats = |pass_rate| GroupStats {
+            cases: 2,
+            pass_rate,
+            avg_score: pass_rate,
+            compilation_rate: 1.0,
+        };
+        report.aggregate.per_tag = HashMap::from([
+            (
+                "basics".into(),
+                HashMap::from([("model-1".into(), stats(1.0))]),
+            ),
+            (
+                "async".into(),
+                HashMap::from([("model-1".into(), stats(0.0))]),
+            ),
+        ]);
+        let html = generate_html(&report);
+
+        assert!(html.contains("<h2>By Tag</h2>"));
+        assert!(!html.contains("<h2>By Difficulty</h2>"));
+        assert!(html.contains("<th>Model</th><th>async</th><th>basics</th>"));
+        assert!(html.contains("hsla(120, 75%, 50%, 0.35)\" title=\"2 cases, avg score 100.0%, compiles 100.0%\">100.0%</td>"));
+        assert!(html.contains("hsla(0, 75%, 50%, 0.35)"));
+    }
+
+    #[test]
+    fn html_report_shows_failure_modes() {
+        use forgetest_core::taxonomy::ErrorCategory;
+
+        let mut report = make_test_report();
+        report.results[0].compilation.success = false;
+        report.results[0].compilation.errors = vec![CompilerDiagnostic {
+            level: DiagnosticLevel::Error,
+            message: "cannot borrow `v` as mutable more than once at a time".into(),
+            code: Some("E0499".into()),
+            spans: vec![],
+        }];
+        report
+            .aggregate
+            .per_model
+            .get_mut("model-1")
+            .unwrap()
+            .failure_modes = [(ErrorCategory::BorrowCheck, 1)].into();
+        let html = generate_html(&report);
+
+        assert!(html.contains("<h2>Failure Modes</h2>"));
+        assert!(html.contains("<th>Model</th><th>borrow check</th></tr>"));
+        assert!(html.contains("<tr><td>model-1</td><td>1</td></tr>"));
+        assert!(html.contains("FAIL (borrow check)"));
+    }
+
+    #[test]
+    fn html_report_lists_hallucinated_apis() {
+        use forgetest_core::hallucination::HallucinatedApi;
+
+        let mut report = make_test_report();
+        report.results[0].hallucinated_apis = vec![HallucinatedApi {
+            crate_name: "tokio".into(),
+            path: "tokio::task::spawn_blockin".into(),
+            item: "spawn_blockin".into(),
+            code: "E0425".into(),
+            suggestion: Some("tokio::task::spawn_blocking".into()),
+        }];
+        let html = generate_html(&report);
+
+        assert!(html.contains("<h2>Non-existent APIs</h2>"));
+        assert!(html.contains("<code>tokio::task::spawn_blockin</code>"));
+        assert!(html.contains("<code>tokio::task::spawn_blocking</code>"));
+    }
+
+    #[test]
+    fn head_to_head_html_shows_matrix_and_deltas() {
+        use forgetest_core::head_to_head::{CaseOutcome, MetricDelta, Winner};
+
+        let h2h = HeadToHead {
+            model_a: "model-a".into(),
+            model_b: "<b>".into(),
+            cases: vec![CaseOutcome {
+                case_id: "case1".into(),
+                score_a: 1.0,
+                score_b: 0.25,
+                solved_a: true,
+                solved_b: false,
+                winner: Winner::A,
+            }],
+            wins_a: 1,
+            wins_b: 0,
+            ties: 0,
+            both_solved: 0,
+            neither_solved: 0,
+            only_a_solved: vec!["case1".into()],
+            only_b_solved: vec![],
+            mcnemar_p: 1.0,
+            deltas: vec![MetricDelta {
+                metric: "cost_usd".into(),
+                a: 0.002,
+                b: 0.001,
+                delta: -0.001,
+                p_value: 0.5,
+            }],
+        };
+
+        let html = generate_head_to_head_html(&h2h);
+        assert!(html.contains("<h1>model-a vs &lt;b&gt;</h1>"));
+        assert!(html.contains("<tr><th>model-a solved</th><td>0</td><td>1</td></tr>"));
+        assert!(html.contains("Only model-a solved: <code>case1</code>"));
+        assert!(html.contains("<td>$0.0020</td><td>$0.0010</td><td>-$0.0010</td>"));
+        assert!(html.contains("<td class=\"fail\">25.0%</td><td>model-a</td>"));
+    }
+
+    #[test]
+    fn leaderboard_html_lists_groups_and_unrated() {
+        use forgetest_core::confidence::ConfidenceInterval;
+        use forgetest_core::leaderboard::{Leaderboard, LeaderboardEntry};
+
+        let entry = |model: &str, group: usize, rating: f64| LeaderboardEntry {
+            rank: 1,
+            model: model.into(),
+            group,
+            rating,
+            interval: ConfidenceInterval {
+                lower: rating - 50.0,
+                upper: rating + 50.0,
+            },
+            cases: 4,
+            wins: 3,
+            losses: 1,
+            ties: 0,
+        };
+        let board = Leaderboard {
+            entries: vec![entry("<m1>", 0, 1100.0), entry("m2", 1, 950.0)],
+            unrated: vec!["m3".into()],
+            groups: 2,
+            reports: 3,
+            cases: 8,
+            level: 0.95,
+        };
+
+        let html = generate_leaderboard_html(&board);
+        assert!(html.contains("<h2>Group 2</h2>"));
+        assert!(html.contains("not comparable across groups"));
+        assert!(html.contains(
+            "<tr><td>1</td><td>&lt;m1&gt;</td><td>1100</td><td>1050-1150</td><td>4</td><td>3-1-0</td></tr>"
+        ));
+        assert!(html.contains("Unrated (no cases shared with another model): <code>m3</code>"));
+        assert!(html.contains("<circle"));
+    }
+
     #[test]
     fn html_report_write_to_file() {
         let report = make_test_report();
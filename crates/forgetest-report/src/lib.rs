@@ -1,6 +1,7 @@
 //! forgetest-report — Report generation.
 //!
-//! Generates HTML, JSON, and SARIF reports from eval results.
+//! Generates HTML, JSON, markdown, and SARIF reports from eval results.
 
 pub mod html;
+pub mod markdown;
 pub mod sarif;
@@ -0,0 +1,275 @@
+//! Markdown report generator.
+//!
+//! Produces a summary suitable for PR comments and job summaries.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use forgetest_core::confidence::percent_with_interval;
+use forgetest_core::report::EvalReport;
+
+/// Escape characters that would break a markdown table cell.
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Generate a markdown report from an eval report.
+pub fn generate_markdown(report: &EvalReport) -> String {
+    let mut md = String::new();
+
+    md.push_str(&format!(
+        "# forgetest report — {}\n\n",
+        cell(&report.eval_set.name)
+    ));
+    md.push_str(&format!(
+        "{} cases | {} models | {}\n\n",
+        report.eval_set.case_count,
+        report.models_evaluated.len(),
+        report.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+    ));
+    if let Some(budget) = report.budget.as_ref().filter(|b| b.is_partial()) {
+        let reasons: Vec<String> = budget.exhausted.iter().map(|e| e.to_string()).collect();
+        md.push_str(&format!(
+            "> **Partial report:** {}. {} generations were skipped.\n\n",
+            reasons.join("; "),
+            budget.skipped
+        ));
+    }
+    if let Some(original) = report.rescored_from {
+        md.push_str(&format!(
+            "Rescored from the generations of report `{original}`.\n\n"
+        ));
+    }
+
+    // Model summary, sorted for stable output
+    let mut models: Vec<_> = report.aggregate.per_model.iter().collect();
+    models.sort_by(|a, b| a.0.cmp(b.0));
+    let mut k_values: Vec<u32> = models
+        .iter()
+        .flat_map(|(_, s)| s.pass_at_k.keys().copied())
+        .collect();
+    k_values.sort_unstable();
+    k_values.dedup();
+
+    md.push_str("## Summary\n\n| Model |");
+    for k in &k_values {
+        md.push_str(&format!(" Pass@{k} |"));
+    }
+    md.push_str(
+        " Compile % | Test Pass % | Clippy | Judge | Hallucinated APIs | Cost | Avg Latency |\n|-------|",
+    );
+    md.push_str(&"--------|".repeat(k_values.len()));
+    md.push_str(
+        "-----------|-------------|--------|-------|-------------------|------|-------------|\n",
+    );
+
+    for (model, stats) in &models {
+        let ci = stats.intervals.as_ref();
+        md.push_str(&format!("| {} |", cell(model)));
+        for k in &k_values {
+            let value = stats.pass_at_k.get(k).copied().unwrap_or(0.0);
+            md.push_str(&format!(
+                " {} |",
+                percent_with_interval(value, ci.and_then(|c| c.pass_at_k.get(k)))
+            ));
+        }
+        let judge = match stats.avg_judge_score {
+            Some(score) => percent_with_interval(score, ci.and_then(|c| c.judge_score.as_ref())),
+            None => "-".to_string(),
+        };
+        md.push_str(&format!(
+            " {} | {} | {} | {} | {:.1}% ({}) | ${:.4} | {}ms |\n",
+            percent_with_interval(
+                stats.avg_compilation_rate,
+                ci.and_then(|c| c.compilation_rate.as_ref())
+            ),
+            percent_with_interval(
+                stats.avg_test_pass_rate,
+                ci.and_then(|c| c.test_pass_rate.as_ref())
+            ),
+            percent_with_interval(
+                stats.avg_clippy_score,
+                ci.and_then(|c| c.clippy_score.as_ref())
+            ),
+            judge,
+            stats.hallucination_rate * 100.0,
+            stats.hallucinated_apis,
+            stats.total_cost_usd,
+            stats.avg_latency_ms,
+        ));
+    }
+    if let Some(ci) = models.iter().find_map(|(_, s)| s.intervals.as_ref()) {
+        md.push_str(&format!("\n_Ranges in brackets: {}._\n", ci.describe()));
+    }
+
+    // Calls into dependency APIs that don't exist
+    let hallucinated: Vec<_> = report
+        .results
+        .iter()
+        .flat_map(|r| r.hallucinated_apis.iter().map(move |api| (r, api)))
+        .collect();
+    if !hallucinated.is_empty() {
+        md.push_str("\n## Non-existent APIs\n\n");
+        md.push_str("| Case | Model | Crate | Path | Did you mean |\n");
+        md.push_str("|------|-------|-------|------|--------------|\n");
+        for (r, api) in hallucinated {
+            md.push_str(&format!(
+                "| {} | {} | {} | `{}` | {} |\n",
+                cell(&r.case_id),
+                cell(&r.model),
+                api.crate_name,
+                api.path,
+                api.suggestion
+                    .as_ref()
+                    .map_or("-".to_string(), |s| format!("`{s}`")),
+            ));
+        }
+    }
+
+    // Per-result table
+    md.push_str("\n## Results\n\n");
+    md.push_str("| Case | Model | Attempt | Compile | Tests | Score |\n");
+    md.push_str("|------|-------|---------|---------|-------|-------|\n");
+    for r in &report.results {
+        let tests = match &r.test_execution {
+            Some(t) => format!("{}/{}", t.passed, t.passed + t.failed),
+            None => "-".to_string(),
+        };
+        let score = match &r.score {
+            Some(score) => format!("{:.1}%", score.overall * 100.0),
+            None => "-".to_string(),
+        };
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            cell(&r.case_id),
+            cell(&r.model),
+            r.attempt,
+            if r.compilation.success { "OK" } else { "FAIL" },
+            tests,
+            score,
+        ));
+    }
+
+    md
+}
+
+/// Write a markdown report to a file.
+pub fn write_markdown_report(report: &EvalReport, path: &Path) -> Result<()> {
+    let md = generate_markdown(report);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, md)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forgetest_core::confidence::{ConfidenceInterval, IntervalMethod};
+    use forgetest_core::report::*;
+    use forgetest_core::statistics::*;
+    use std::collections::HashMap;
+
+    fn make_report() -> EvalReport {
+        let ci = ConfidenceInterval {
+            lower: 0.5,
+            upper: 0.9,
+        };
+        let stats = ModelStats {
+            model: "model|1".into(),
+            pass_at_k: HashMap::from([(1, 0.75), (5, 0.9)]),
+            avg_compilation_rate: 1.0,
+            avg_test_pass_rate: 0.8,
+            avg_clippy_score: 1.0,
+            total_tokens: 100,
+            total_cost_usd: 0.01,
+            avg_latency_ms: 500,
+            cached_results: 0,
+            cached_cost_usd: 0.0,
+            metrics: None,
+            avg_judge_score: None,
+            judge_tokens: 0,
+            judge_cost_usd: 0.0,
+            failure_modes: Default::default(),
+            hallucinated_apis: 0,
+            hallucination_rate: 0.0,
+            intervals: Some(ModelIntervals {
+                method: IntervalMethod::Bootstrap,
+                level: 0.95,
+                cases: 12,
+                pass_at_k: HashMap::from([(1, ci)]),
+                compilation_rate: None,
+                test_pass_rate: Some(ci),
+                clippy_score: None,
+                judge_score: None,
+            }),
+        };
+        EvalReport {
+            id: uuid::Uuid::nil(),
+            created_at: chrono::Utc::now(),
+            eval_set: EvalSetSummary {
+                id: "test".into(),
+                name: "Test Set".into(),
+                case_count: 12,
+            },
+            models_evaluated: vec!["model|1".into()],
+            results: vec![],
+            aggregate: AggregateStats {
+                per_model: HashMap::from([("model|1".to_string(), stats)]),
+                per_case: HashMap::new(),
+                per_tag: HashMap::new(),
+                per_difficulty: HashMap::new(),
+            },
+            scoring: None,
+            cases: Default::default(),
+            rescored_from: None,
+            budget: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn markdown_summary_shows_intervals() {
+        let md = generate_markdown(&make_report());
+
+        assert!(md.starts_with("# forgetest report — Test Set"));
+        assert!(md.contains("| Model | Pass@1 | Pass@5 | Compile % |"));
+        assert!(
+            md.contains("| model\\|1 | 75.0% [50.0-90.0%] | 90.0% | 100.0% | 80.0% [50.0-90.0%] |")
+        );
+        assert!(md.contains("95% bootstrap intervals over 12 cases"));
+    }
+
+    #[test]
+    fn markdown_marks_budget_stopped_reports_partial() {
+        use forgetest_core::budget::{BudgetExceeded, BudgetSummary};
+
+        let mut report = make_report();
+        assert!(!generate_markdown(&report).contains("Partial report"));
+        report.budget = Some(BudgetSummary {
+            exhausted: vec![BudgetExceeded {
+                model: None,
+                reason: "cost limit $5.00 reached ($5.0300 spent)".into(),
+            }],
+            skipped: 12,
+            ..Default::default()
+        });
+        let md = generate_markdown(&report);
+        assert!(md.contains(
+            "> **Partial report:** run budget exhausted: cost limit $5.00 reached ($5.0300 spent). 12 generations were skipped."
+        ));
+    }
+
+    #[test]
+    fn markdown_report_write_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out").join("report.md");
+
+        write_markdown_report(&make_report(), &path).unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("## Results"));
+    }
+}
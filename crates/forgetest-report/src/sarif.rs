@@ -27,6 +27,11 @@ pub fn generate_sarif(report: &EvalReport) -> serde_json::Value {
             "Clippy Warning",
             "Clippy reported a warning",
         ),
+        (
+            "undefined-behavior",
+            "Undefined Behavior",
+            "Miri detected undefined behavior while running the tests",
+        ),
     ];
 
     for (id, name, desc) in &rule_defs {
@@ -86,6 +91,18 @@ pub fn generate_sarif(report: &EvalReport) -> serde_json::Value {
                 }));
             }
         }
+
+        // Undefined behaviour found by Miri
+        if let Some(miri) = &r.miri {
+            for finding in &miri.findings {
+                results.push(json!({
+                    "ruleId": "undefined-behavior",
+                    "level": "error",
+                    "message": { "text": format!("[{}] {}: {}", r.model, r.case_id, finding.message) },
+                    "locations": [location.clone()]
+                }));
+            }
+        }
     }
 
     json!({
@@ -153,6 +170,14 @@ mod tests {
                 },
                 test_execution: None,
                 clippy: None,
+                compile_fail: vec![],
+                hallucinated_apis: vec![],
+                formatting: None,
+                miri: None,
+                performance: None,
+                metrics: None,
+                judge: None,
+                score: None,
                 timing: TimingInfo {
                     llm_request_ms: 0,
                     compilation_ms: 0,
@@ -165,13 +190,20 @@ mod tests {
                     total_tokens: 0,
                     estimated_cost_usd: 0.0,
                 },
+                cached: false,
                 attempt: 1,
                 run_id: uuid::Uuid::nil(),
             }],
             aggregate: AggregateStats {
                 per_model: HashMap::new(),
                 per_case: HashMap::new(),
+                per_tag: HashMap::new(),
+                per_difficulty: HashMap::new(),
             },
+            scoring: None,
+            cases: Default::default(),
+            rescored_from: None,
+            budget: None,
             duration_ms: 0,
         };
 
@@ -0,0 +1,430 @@
+//! Dependency API indexes from the local cargo registry.
+//!
+//! Scans the unpacked sources of a dependency under
+//! `$CARGO_HOME/registry/src` for the items it declares, so hallucinated-API
+//! detection can tell a missing item from one behind a disabled feature and
+//! suggest the closest real one. The scan is textual: it over-approximates
+//! the public API (private functions count too), which errs on the side of
+//! not flagging.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use forgetest_core::hallucination::{crate_ident, CrateApi};
+use forgetest_core::traits::Dependency;
+
+/// Item keywords whose next identifier is the item's name.
+const ITEM_KEYWORDS: &[&str] = &["fn", "struct", "enum", "trait", "type", "mod", "union"];
+
+/// Indexes by dependency name and version requirement (`None` if the crate
+/// isn't in the registry).
+type IndexCache = HashMap<(String, String), Option<Arc<CrateApi>>>;
+
+/// Lazily built, cached indexes of dependency crates.
+#[derive(Debug)]
+pub struct ApiIndex {
+    /// `registry/src` directory of the cargo home, if there is one.
+    registry: Option<PathBuf>,
+    cache: Mutex<IndexCache>,
+}
+
+impl ApiIndex {
+    /// Index crates unpacked under `cargo_home`.
+    pub fn new(cargo_home: &Path) -> Self {
+        let registry = cargo_home.join("registry").join("src");
+        Self {
+            registry: registry.is_dir().then_some(registry),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Index crates in `$CARGO_HOME`, or `~/.cargo` if it isn't set.
+    pub fn from_env() -> Self {
+        let cargo_home = std::env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")));
+        match cargo_home {
+            Some(cargo_home) => Self::new(&cargo_home),
+            None => Self {
+                registry: None,
+                cache: Mutex::new(HashMap::new()),
+            },
+        }
+    }
+
+    /// The index of `dependency`, or `None` if no matching version of it is
+    /// unpacked in the registry.
+    ///
+    /// The first lookup of a crate reads its sources, so call this from a
+    /// blocking task. The cache isn't locked during the scan.
+    pub fn get(&self, dependency: &Dependency) -> Option<Arc<CrateApi>> {
+        let key = (dependency.name.clone(), dependency.version.clone());
+        if let Some(cached) = self
+            .cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            return cached.clone();
+        }
+
+        let api = self.find_source(dependency).map(|dir| {
+            let api = index_crate(&dependency.name, &dir.join("src"));
+            tracing::debug!(
+                "indexed {} items of {} from {}",
+                api.items.len(),
+                dependency.name,
+                dir.display()
+            );
+            Arc::new(api)
+        });
+        // Another task may have indexed the crate meanwhile; keep its index
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_insert(api)
+            .clone()
+    }
+
+    /// The highest unpacked version of the dependency that its requirement
+    /// accepts, across all registries.
+    fn find_source(&self, dependency: &Dependency) -> Option<PathBuf> {
+        let registry = self.registry.as_ref()?;
+        let requirement =
+            parse_version(dependency.version.trim_start_matches(['^', '~', '=', ' ']));
+        let prefix = format!("{}-", dependency.name);
+        let mut best: Option<(Vec<u64>, PathBuf)> = None;
+        for source in std::fs::read_dir(registry).ok()?.flatten() {
+            let Ok(entries) = std::fs::read_dir(source.path()) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(version) = name.to_str().and_then(|n| n.strip_prefix(&prefix)) else {
+                    continue;
+                };
+                // `tokio-macros-2.0.0` is not a version of `tokio`
+                if !version.starts_with(|c: char| c.is_ascii_digit()) {
+                    continue;
+                }
+                let version = parse_version(version);
+                if !compatible(&requirement, &version) {
+                    continue;
+                }
+                if best.as_ref().is_none_or(|(v, _)| version > *v) {
+                    best = Some((version, entry.path()));
+                }
+            }
+        }
+        best.map(|(_, path)| path)
+    }
+}
+
+/// Numeric components of a version, ignoring pre-release and build metadata.
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map_while(|part| part.trim().parse().ok())
+        .collect()
+}
+
+/// Caret compatibility: the version matches every component the requirement
+/// gives, up to and including the first non-zero one. Wildcard and empty
+/// requirements accept anything.
+fn compatible(requirement: &[u64], version: &[u64]) -> bool {
+    let significant = requirement
+        .iter()
+        .position(|&part| part != 0)
+        .map_or(requirement.len(), |i| i + 1);
+    requirement[..significant]
+        .iter()
+        .enumerate()
+        .all(|(i, part)| version.get(i) == Some(part))
+}
+
+/// Index the items declared and re-exported in a crate's `src` directory.
+pub fn index_crate(name: &str, src: &Path) -> CrateApi {
+    let mut api = CrateApi::new(name);
+    let mut files = Vec::new();
+    collect_rust_files(src, &mut files);
+    for file in files {
+        let Ok(contents) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let module = module_path(src, &file);
+        index_source(&mut api, &module, &contents);
+    }
+    api
+}
+
+fn collect_rust_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rust_files(&path, out);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+/// Module path of a source file: `src/time/mod.rs` and `src/time.rs` are
+/// both `time`, `src/lib.rs` is the root.
+fn module_path(src: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(src).unwrap_or(file).with_extension("");
+    let mut segments: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if matches!(
+        segments.last().map(String::as_str),
+        Some("mod" | "lib" | "main")
+    ) {
+        segments.pop();
+    }
+    segments.join("::")
+}
+
+/// Record the items a source file declares and re-exports under `module`.
+fn index_source(api: &mut CrateApi, module: &str, contents: &str) {
+    let mut pending_use: Option<String> = None;
+    for line in contents.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if let Some(statement) = pending_use.as_mut() {
+            statement.push(' ');
+            statement.push_str(line);
+            if line.contains(';') {
+                index_reexport(api, module, statement);
+                pending_use = None;
+            }
+            continue;
+        }
+        let unqualified = strip_visibility(line);
+        if unqualified.len() != line.len() {
+            if let Some(tree) = unqualified.strip_prefix("use ") {
+                if tree.contains(';') {
+                    index_reexport(api, module, tree);
+                } else {
+                    pending_use = Some(tree.to_string());
+                }
+                continue;
+            }
+        }
+        if let Some(name) = item_name(unqualified) {
+            api.insert(module, name);
+            // `#[macro_export]` puts macros at the crate root
+            if unqualified.starts_with("macro_rules!") {
+                api.insert("", name);
+            }
+        }
+    }
+}
+
+/// Strip a leading visibility such as `pub` or `pub(crate)`.
+fn strip_visibility(line: &str) -> &str {
+    let Some(rest) = line.strip_prefix("pub") else {
+        return line;
+    };
+    let rest = match rest.strip_prefix('(') {
+        Some(restricted) => restricted.split_once(')').map_or("", |(_, r)| r),
+        None if rest.starts_with(char::is_whitespace) => rest,
+        None => return line,
+    };
+    rest.trim_start()
+}
+
+/// The name an item declaration introduces, e.g. `spawn` for
+/// `async fn spawn<T>(...)` and `select` for `macro_rules! select {`.
+fn item_name(declaration: &str) -> Option<&str> {
+    if let Some(rest) = declaration.strip_prefix("macro_rules!") {
+        return identifier(rest.trim_start());
+    }
+    let mut words = declaration.split_whitespace();
+    let mut word = words.next()?;
+    while matches!(word, "async" | "const" | "unsafe" | "extern" | "default")
+        || word.starts_with('"')
+    {
+        let next = words.next()?;
+        // `const NAME: T` is an item of its own
+        if word == "const" && !matches!(next, "fn" | "unsafe" | "async" | "extern") {
+            return identifier(next);
+        }
+        word = next;
+    }
+    if word == "static" {
+        let next = words.next()?;
+        return identifier(if next == "mut" { words.next()? } else { next });
+    }
+    if ITEM_KEYWORDS.contains(&word) {
+        return identifier(words.next()?);
+    }
+    None
+}
+
+/// The leading identifier of `text`.
+fn identifier(text: &str) -> Option<&str> {
+    let text = text.strip_prefix("r#").unwrap_or(text);
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    (end > 0).then(|| &text[..end])
+}
+
+/// Record the names a `pub use` brings into `module`.
+fn index_reexport(api: &mut CrateApi, module: &str, tree: &str) {
+    let tree = tree.split(';').next().unwrap_or_default();
+    for leaf in tree.split([',', '{', '}']) {
+        let leaf = leaf.trim();
+        let name = match leaf.split_once(" as ") {
+            Some((_, alias)) => alias.trim(),
+            None => leaf.rsplit("::").next().unwrap_or_default(),
+        };
+        if !matches!(name, "" | "*" | "self" | "_") {
+            if let Some(name) = identifier(name) {
+                api.insert(module, name);
+            }
+        }
+    }
+}
+
+/// Index every dependency that's unpacked in the registry.
+pub fn crate_apis(index: &ApiIndex, dependencies: &[Dependency]) -> Vec<CrateApi> {
+    let mut apis: Vec<CrateApi> = Vec::new();
+    for dep in dependencies {
+        if apis.iter().any(|a| a.name == crate_ident(&dep.name)) {
+            continue;
+        }
+        if let Some(api) = index.get(dep) {
+            apis.push(CrateApi::clone(&api));
+        }
+    }
+    apis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(name: &str, version: &str) -> Dependency {
+        Dependency {
+            name: name.into(),
+            version: version.into(),
+            features: vec![],
+        }
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn indexes_declarations_and_reexports() {
+        let mut api = CrateApi::new("demo");
+        index_source(
+            &mut api,
+            "task",
+            r#"
+pub use self::spawn::{spawn, JoinHandle as Handle};
+pub use crate::blocking::{
+    spawn_blocking,
+    block_in_place,
+};
+pub(crate) async fn helper() {}
+pub const unsafe fn raw() {}
+pub const LIMIT: usize = 4;
+pub static mut COUNT: u32 = 0;
+pub struct Builder<T> {}
+impl Builder {
+    pub fn name(self) -> Self {}
+}
+macro_rules! ready { () => {} }
+// pub fn commented_out() {}
+pub trait Ext {
+    fn read_all(&mut self);
+}
+"#,
+        );
+        for item in [
+            "spawn",
+            "Handle",
+            "spawn_blocking",
+            "block_in_place",
+            "helper",
+            "raw",
+            "LIMIT",
+            "COUNT",
+            "Builder",
+            "name",
+            "ready",
+            "Ext",
+            "read_all",
+        ] {
+            assert!(api.contains("task", item), "{item}");
+        }
+        assert!(!api.items.contains_key("commented_out"));
+        assert!(!api.items.contains_key("JoinHandle"));
+        assert!(api.contains("", "ready"));
+    }
+
+    #[test]
+    fn module_paths_follow_the_file_layout() {
+        let src = Path::new("/c/src");
+        assert_eq!(module_path(src, Path::new("/c/src/lib.rs")), "");
+        assert_eq!(module_path(src, Path::new("/c/src/time/mod.rs")), "time");
+        assert_eq!(
+            module_path(src, Path::new("/c/src/time/sleep.rs")),
+            "time::sleep"
+        );
+    }
+
+    #[test]
+    fn caret_requirements() {
+        assert!(compatible(&[1], &[1, 40, 0]));
+        assert!(!compatible(&[1], &[2, 0, 0]));
+        assert!(compatible(&[0, 8], &[0, 8, 5]));
+        assert!(!compatible(&[0, 8], &[0, 9, 0]));
+        assert!(compatible(&[], &[3, 1, 0]));
+        assert_eq!(parse_version("1.2.3-alpha.1"), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn finds_the_highest_matching_version_in_the_registry() {
+        let home = tempfile::tempdir().unwrap();
+        let registry = home.path().join("registry/src/index.crates.io-abc");
+        write(
+            &registry.join("demo-1.2.0/src/lib.rs"),
+            "pub fn old_api() {}",
+        );
+        write(
+            &registry.join("demo-1.10.0/src/lib.rs"),
+            "pub mod time;\npub fn new_api() {}",
+        );
+        write(
+            &registry.join("demo-1.10.0/src/time.rs"),
+            "pub async fn sleep() {}",
+        );
+        write(&registry.join("demo-2.0.0/src/lib.rs"), "pub fn next() {}");
+        write(
+            &registry.join("demo-macros-1.0.0/src/lib.rs"),
+            "pub fn macros() {}",
+        );
+
+        let index = ApiIndex::new(home.path());
+        let api = index.get(&dep("demo", "1")).unwrap();
+        assert!(api.contains("", "new_api"));
+        assert!(api.contains("time", "sleep"));
+        assert!(!api.items.contains_key("old_api"));
+        assert!(!api.items.contains_key("macros"));
+        assert!(index.get(&dep("demo", "3")).is_none());
+        assert!(index.get(&dep("missing", "1")).is_none());
+    }
+}
@@ -0,0 +1,428 @@
+//! In-sandbox benchmarking for performance expectations.
+//!
+//! The case's benchmark snippet is wrapped in a harness example that installs
+//! a counting global allocator, runs `setup(n)` untimed and `run(input)` timed
+//! for each input size, and prints one stats line per size. The example is
+//! built and run in release mode.
+
+use std::process::Stdio;
+
+use anyhow::{Context, Result};
+use tokio::process::Command;
+
+use forgetest_core::model::PerformanceExpectation;
+use forgetest_core::results::{BenchMeasurement, PerformanceResult};
+
+use crate::sandbox::Sandbox;
+
+/// Name of the generated benchmark example target.
+const BENCH_TARGET: &str = "forgetest_bench";
+
+/// Prefix of the stats lines printed by the harness.
+const STATS_PREFIX: &str = "FORGETEST_BENCH";
+
+/// Benchmark the generated code and check it against the budgets.
+///
+/// A benchmark that fails to build, panics or times out fails the gate, and
+/// so does a reference solution that can't be measured.
+pub async fn run_benchmark(
+    sandbox: &Sandbox,
+    spec: &PerformanceExpectation,
+    reference: Option<&Sandbox>,
+) -> Result<PerformanceResult> {
+    let measurements = match measure(sandbox, spec).await {
+        Ok(measurements) => measurements,
+        Err(e) => {
+            return Ok(PerformanceResult {
+                measurements: vec![],
+                reference: vec![],
+                passed: false,
+                failure: Some(format!("benchmark failed: {e:#}")),
+            })
+        }
+    };
+
+    let reference = match reference {
+        Some(reference) => match measure(reference, spec).await {
+            Ok(reference) => reference,
+            Err(e) => {
+                return Ok(PerformanceResult {
+                    measurements,
+                    reference: vec![],
+                    passed: false,
+                    failure: Some(format!("reference solution benchmark failed: {e:#}")),
+                })
+            }
+        },
+        None => vec![],
+    };
+
+    Ok(evaluate(spec, measurements, reference))
+}
+
+/// Build and run the harness, returning one measurement per input size.
+pub async fn measure(
+    sandbox: &Sandbox,
+    spec: &PerformanceExpectation,
+) -> Result<Vec<BenchMeasurement>> {
+    let examples_dir = sandbox.work_dir().join("examples");
+    std::fs::create_dir_all(&examples_dir).context("failed to create examples directory")?;
+    let harness = examples_dir.join(format!("{BENCH_TARGET}.rs"));
+    std::fs::write(&harness, harness_source(&sandbox.crate_name()?, spec))
+        .context("failed to write benchmark harness")?;
+
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--release")
+        .arg("--quiet")
+        .arg("--example")
+        .arg(BENCH_TARGET)
+        .current_dir(sandbox.work_dir())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    for (key, val) in sandbox.build_env() {
+        cmd.env(&key, &val);
+    }
+
+    let output = tokio::time::timeout(sandbox.timeout(), cmd.output()).await;
+    // Don't leave the harness behind for later stages (miri, clippy, tests)
+    let _ = std::fs::remove_file(&harness);
+    let output = output
+        .context("benchmark timed out")?
+        .context("failed to run benchmark")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+        let tail: Vec<&str> = tail.into_iter().rev().collect();
+        anyhow::bail!(
+            "benchmark exited with {}:\n{}",
+            output.status,
+            tail.join("\n")
+        );
+    }
+
+    let measurements = parse_stats(&String::from_utf8_lossy(&output.stdout));
+    if measurements.len() != spec.sizes.len() {
+        anyhow::bail!(
+            "expected {} measurements, got {}",
+            spec.sizes.len(),
+            measurements.len()
+        );
+    }
+    Ok(measurements)
+}
+
+/// Check measurements against the budgets. Budgets apply to the largest size.
+pub fn evaluate(
+    spec: &PerformanceExpectation,
+    measurements: Vec<BenchMeasurement>,
+    reference: Vec<BenchMeasurement>,
+) -> PerformanceResult {
+    let mut failures = Vec::new();
+
+    if let Some(largest) = measurements.iter().max_by_key(|m| m.size) {
+        let median_ms = largest.median_ns as f64 / 1_000_000.0;
+
+        if let Some(max_ms) = spec.max_time_ms {
+            if median_ms > max_ms {
+                failures.push(format!(
+                    "n={}: median {median_ms:.3}ms exceeds budget {max_ms}ms",
+                    largest.size
+                ));
+            }
+        }
+
+        if let Some(max_allocs) = spec.max_allocations {
+            if largest.allocations > max_allocs {
+                failures.push(format!(
+                    "n={}: {} allocations exceeds budget {max_allocs}",
+                    largest.size, largest.allocations
+                ));
+            }
+        }
+
+        if let Some(max_ratio) = spec.max_ratio {
+            match reference.iter().find(|r| r.size == largest.size) {
+                Some(baseline) => {
+                    let ratio = largest.median_ns as f64 / baseline.median_ns.max(1) as f64;
+                    if ratio > max_ratio {
+                        failures.push(format!(
+                            "n={}: {ratio:.2}x the reference solution exceeds budget {max_ratio}x",
+                            largest.size
+                        ));
+                    }
+                }
+                None => {
+                    failures.push("max_ratio set but no reference solution was measured".into())
+                }
+            }
+        }
+    } else {
+        failures.push("no measurements".into());
+    }
+
+    PerformanceResult {
+        measurements,
+        reference,
+        passed: failures.is_empty(),
+        failure: (!failures.is_empty()).then(|| failures.join("; ")),
+    }
+}
+
+/// Parse `FORGETEST_BENCH <size> <median_ns> <mean_ns> <min_ns> <allocations>` lines.
+fn parse_stats(output: &str) -> Vec<BenchMeasurement> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.strip_prefix(STATS_PREFIX)?.split_whitespace();
+            Some(BenchMeasurement {
+                size: fields.next()?.parse().ok()?,
+                median_ns: fields.next()?.parse().ok()?,
+                mean_ns: fields.next()?.parse().ok()?,
+                min_ns: fields.next()?.parse().ok()?,
+                allocations: fields.next()?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Generate the harness example around the benchmark snippet.
+fn harness_source(crate_name: &str, spec: &PerformanceExpectation) -> String {
+    let sizes = spec
+        .sizes
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let warmup = spec.warmup;
+    let repetitions = spec.repetitions.max(1);
+    let bench = &spec.bench;
+
+    format!(
+        r#"#![allow(unused_imports)]
+use {crate_name}::*;
+
+{bench}
+
+mod forgetest_harness {{
+    use std::alloc::{{GlobalAlloc, Layout, System}};
+    use std::sync::atomic::{{AtomicU64, Ordering}};
+
+    pub static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {{
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {{
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            System.alloc(layout)
+        }}
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {{
+            System.dealloc(ptr, layout)
+        }}
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {{
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            System.realloc(ptr, layout, new_size)
+        }}
+    }}
+
+    pub fn allocations() -> u64 {{
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }}
+}}
+
+#[global_allocator]
+static FORGETEST_ALLOCATOR: forgetest_harness::CountingAllocator =
+    forgetest_harness::CountingAllocator;
+
+fn main() {{
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    for n in [{sizes}] {{
+        for _ in 0..{warmup} {{
+            let input = setup(n);
+            black_box(run(black_box(input)));
+        }}
+
+        let mut times = Vec::with_capacity({repetitions});
+        let mut allocations = Vec::with_capacity({repetitions});
+        for _ in 0..{repetitions} {{
+            let input = setup(n);
+            let allocs_before = forgetest_harness::allocations();
+            let start = Instant::now();
+            let output = run(black_box(input));
+            let elapsed = start.elapsed().as_nanos() as u64;
+            let allocs_after = forgetest_harness::allocations();
+            black_box(output);
+            times.push(elapsed);
+            allocations.push(allocs_after - allocs_before);
+        }}
+
+        times.sort_unstable();
+        allocations.sort_unstable();
+        let mean = times.iter().sum::<u64>() / times.len() as u64;
+        println!(
+            "{STATS_PREFIX} {{}} {{}} {{}} {{}} {{}}",
+            n,
+            times[times.len() / 2],
+            mean,
+            times[0],
+            allocations[allocations.len() / 2]
+        );
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forgetest_core::model::Language;
+    use std::time::Duration;
+
+    fn spec(bench: &str) -> PerformanceExpectation {
+        PerformanceExpectation {
+            bench: bench.into(),
+            sizes: vec![10, 100],
+            warmup: 1,
+            repetitions: 3,
+            max_time_ms: None,
+            max_allocations: None,
+            max_ratio: None,
+        }
+    }
+
+    fn measurement(size: usize, median_ns: u64, allocations: u64) -> BenchMeasurement {
+        BenchMeasurement {
+            size,
+            median_ns,
+            mean_ns: median_ns,
+            min_ns: median_ns,
+            allocations,
+        }
+    }
+
+    #[test]
+    fn parse_stats_lines() {
+        let output = "noise\nFORGETEST_BENCH 10 120 130 100 2\nFORGETEST_BENCH 100 900 950 800 7\n";
+        let stats = parse_stats(output);
+        assert_eq!(
+            stats,
+            vec![
+                BenchMeasurement {
+                    size: 10,
+                    median_ns: 120,
+                    mean_ns: 130,
+                    min_ns: 100,
+                    allocations: 2
+                },
+                BenchMeasurement {
+                    size: 100,
+                    median_ns: 900,
+                    mean_ns: 950,
+                    min_ns: 800,
+                    allocations: 7
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluate_budgets_use_largest_size() {
+        let mut spec = spec("");
+        spec.max_time_ms = Some(1.0);
+        spec.max_allocations = Some(5);
+
+        let ok = evaluate(
+            &spec,
+            vec![
+                measurement(10, 5_000_000, 100),
+                measurement(100, 500_000, 5),
+            ],
+            vec![],
+        );
+        assert!(ok.passed, "{:?}", ok.failure);
+
+        let slow = evaluate(&spec, vec![measurement(100, 2_000_000, 9)], vec![]);
+        assert!(!slow.passed);
+        let failure = slow.failure.unwrap();
+        assert!(failure.contains("exceeds budget 1ms"));
+        assert!(failure.contains("9 allocations"));
+    }
+
+    #[test]
+    fn evaluate_ratio_to_reference() {
+        let mut spec = spec("");
+        spec.max_ratio = Some(2.0);
+
+        let within = evaluate(
+            &spec,
+            vec![measurement(100, 1_500, 0)],
+            vec![measurement(100, 1_000, 0)],
+        );
+        assert!(within.passed);
+
+        let quadratic = evaluate(
+            &spec,
+            vec![measurement(100, 50_000, 0)],
+            vec![measurement(100, 1_000, 0)],
+        );
+        assert!(!quadratic.passed);
+        assert!(quadratic.failure.unwrap().contains("50.00x"));
+
+        let missing = evaluate(&spec, vec![measurement(100, 1_000, 0)], vec![]);
+        assert!(!missing.passed);
+    }
+
+    #[tokio::test]
+    async fn measure_runs_harness() {
+        let target = tempfile::tempdir().unwrap();
+        let sandbox =
+            Sandbox::new(Language::Rust, Duration::from_secs(120), target.path()).unwrap();
+        sandbox
+            .write_source("pub fn total(v: &[u64]) -> u64 { v.iter().sum() }")
+            .unwrap();
+
+        let bench = "fn setup(n: usize) -> Vec<u64> { (0..n as u64).collect() }\n\
+                     fn run(input: Vec<u64>) -> u64 { total(&input) }";
+        let stats = measure(&sandbox, &spec(bench)).await.unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].size, 10);
+        assert_eq!(stats[1].size, 100);
+        assert_eq!(stats[1].allocations, 0);
+        assert!(!sandbox
+            .work_dir()
+            .join(format!("examples/{BENCH_TARGET}.rs"))
+            .exists());
+
+        let broken = run_benchmark(&sandbox, &spec("fn setup() {}"), None)
+            .await
+            .unwrap();
+        assert!(!broken.passed);
+        assert!(broken.failure.unwrap().starts_with("benchmark failed"));
+
+        let reference_dir = tempfile::tempdir().unwrap();
+        let reference = Sandbox::new(
+            Language::Rust,
+            Duration::from_secs(120),
+            reference_dir.path(),
+        )
+        .unwrap();
+        reference.write_source("pub fn other() {}").unwrap();
+        let result = run_benchmark(&sandbox, &spec(bench), Some(&reference))
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.measurements.len(), 2);
+        assert!(result
+            .failure
+            .unwrap()
+            .starts_with("reference solution benchmark failed"));
+    }
+}
@@ -1,22 +1,32 @@
 //! Clippy analysis runner.
 
+use std::collections::{BTreeMap, HashMap};
 use std::process::Stdio;
 
 use anyhow::{Context, Result};
 use tokio::process::Command;
 
+use forgetest_core::model::ClippyConfig;
 use forgetest_core::results::{ClippyResult, CompilerDiagnostic, DiagnosticLevel, DiagnosticSpan};
 
 use crate::sandbox::Sandbox;
 
-/// Run clippy on the code in the sandbox.
-pub async fn run_clippy(sandbox: &Sandbox) -> Result<ClippyResult> {
+/// Lint groups that make up `clippy::all`. They are passed individually so
+/// rustc reports which group enabled each lint, which gives us its category.
+const DEFAULT_GROUPS: &[&str] = &["correctness", "suspicious", "style", "complexity", "perf"];
+
+/// Run clippy on the code in the sandbox with the given lint profile.
+pub async fn run_clippy(sandbox: &Sandbox, config: &ClippyConfig) -> Result<ClippyResult> {
+    if let Some(contents) = &config.config {
+        std::fs::write(sandbox.work_dir().join("clippy.toml"), contents)
+            .context("failed to write clippy.toml")?;
+    }
+
     let mut cmd = Command::new("cargo");
     cmd.arg("clippy")
         .arg("--message-format=json")
         .arg("--")
-        .arg("-W")
-        .arg("clippy::all")
+        .args(lint_args(config))
         .current_dir(sandbox.work_dir())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -31,18 +41,77 @@ pub async fn run_clippy(sandbox: &Sandbox) -> Result<ClippyResult> {
         .context("failed to run cargo clippy")?;
 
     let stdout = String::from_utf8_lossy(&result.stdout);
-    let warnings = parse_clippy_output(&stdout);
+    let (warnings, categories) = parse_clippy_output(&stdout);
     let warning_count = warnings.len() as u32;
 
+    let mut by_category = BTreeMap::new();
+    for warning in &warnings {
+        if let Some(category) = warning.code.as_ref().and_then(|c| categories.get(c)) {
+            *by_category.entry(category.clone()).or_insert(0) += 1;
+        }
+    }
+
     Ok(ClippyResult {
         warnings,
         warning_count,
+        by_category,
     })
 }
 
-/// Parse clippy JSON output into diagnostics.
-fn parse_clippy_output(output: &str) -> Vec<CompilerDiagnostic> {
+/// Build the rustc lint flags for a profile.
+///
+/// Groups come first so the explicit allow/warn/deny lists override them.
+fn lint_args(config: &ClippyConfig) -> Vec<String> {
+    let mut groups: Vec<&str> = DEFAULT_GROUPS.to_vec();
+    if config.pedantic {
+        groups.push("pedantic");
+    }
+    if config.nursery {
+        groups.push("nursery");
+    }
+
+    let mut args = Vec::new();
+    for group in groups {
+        args.push("-W".to_string());
+        args.push(format!("clippy::{group}"));
+    }
+    for (flag, lints) in [
+        ("-A", &config.allow),
+        ("-W", &config.warn),
+        ("-D", &config.deny),
+    ] {
+        for lint in lints {
+            args.push(flag.to_string());
+            args.push(qualify_lint(lint));
+        }
+    }
+    args
+}
+
+/// Accept lint names with or without the `clippy::` prefix.
+fn qualify_lint(lint: &str) -> String {
+    if lint.contains("::") {
+        lint.to_string()
+    } else {
+        format!("clippy::{lint}")
+    }
+}
+
+/// Extract the lint group from a note like
+/// "`-W clippy::needless-return` implied by `-W clippy::style`".
+fn implied_category(note: &str) -> Option<String> {
+    let (_, group) = note.split_once("implied by `")?;
+    let group = group.split('`').next()?;
+    let (_, name) = group.split_once("clippy::")?;
+    Some(name.to_string())
+}
+
+/// Parse clippy JSON output into diagnostics, plus a map from lint code to
+/// category. rustc only names the enabling group on a lint's first
+/// occurrence, so the map is built up across all messages.
+fn parse_clippy_output(output: &str) -> (Vec<CompilerDiagnostic>, HashMap<String, String>) {
     let mut warnings = Vec::new();
+    let mut categories = HashMap::new();
 
     for line in output.lines() {
         let Ok(msg) = serde_json::from_str::<serde_json::Value>(line) else {
@@ -62,7 +131,8 @@ fn parse_clippy_output(output: &str) -> Vec<CompilerDiagnostic> {
             .and_then(|l| l.as_str())
             .unwrap_or("note");
 
-        if level != "warning" {
+        // Denied lints are reported as errors but still count
+        if level != "warning" && level != "error" {
             continue;
         }
 
@@ -77,6 +147,19 @@ fn parse_clippy_output(output: &str) -> Vec<CompilerDiagnostic> {
             continue;
         }
 
+        if let Some(code) = code {
+            let category = message
+                .get("children")
+                .and_then(|c| c.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|child| child.get("message").and_then(|m| m.as_str()))
+                .find_map(implied_category);
+            if let Some(category) = category {
+                categories.insert(code.to_string(), category);
+            }
+        }
+
         let text = message
             .get("message")
             .and_then(|m| m.as_str())
@@ -110,7 +193,7 @@ fn parse_clippy_output(output: &str) -> Vec<CompilerDiagnostic> {
         });
     }
 
-    warnings
+    (warnings, categories)
 }
 
 /// Check if the Rust toolchain and clippy are available.
@@ -125,3 +208,36 @@ pub async fn check_clippy_available() -> bool {
         .map(|s| s.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_args_order_groups_before_overrides() {
+        let config = ClippyConfig {
+            pedantic: true,
+            allow: vec!["module_name_repetitions".into()],
+            deny: vec!["clippy::unwrap_used".into()],
+            ..Default::default()
+        };
+        let args = lint_args(&config).join(" ");
+        assert!(args.starts_with("-W clippy::correctness"));
+        assert!(args.contains("-W clippy::pedantic"));
+        assert!(!args.contains("nursery"));
+        assert!(args.ends_with("-A clippy::module_name_repetitions -D clippy::unwrap_used"));
+    }
+
+    #[test]
+    fn parse_categories_from_first_occurrence() {
+        let first = r#"{"reason":"compiler-message","message":{"level":"warning","message":"unneeded `return` statement","code":{"code":"clippy::needless_return"},"spans":[],"children":[{"level":"note","message":"`-W clippy::needless-return` implied by `-W clippy::style`"}]}}"#;
+        let second = r#"{"reason":"compiler-message","message":{"level":"warning","message":"unneeded `return` statement","code":{"code":"clippy::needless_return"},"spans":[],"children":[]}}"#;
+        let denied = r#"{"reason":"compiler-message","message":{"level":"error","message":"used `unwrap()`","code":{"code":"clippy::unwrap_used"},"spans":[],"children":[{"level":"note","message":"requested on the command line with `-D clippy::unwrap-used`"}]}}"#;
+        let output = [first, second, denied].join("\n");
+
+        let (warnings, categories) = parse_clippy_output(&output);
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(categories.len(), 1);
+        assert_eq!(categories["clippy::needless_return"], "style");
+    }
+}
@@ -0,0 +1,196 @@
+//! Compile-fail checks (trybuild-style negative tests).
+//!
+//! Each snippet is built as its own example target that glob-imports the
+//! generated crate. A snippet passes when the compiler rejects it, and, if an
+//! error code is expected, when that code is among the reported errors.
+//! Without an expected code, a snippet rejected because a name didn't resolve
+//! fails: the crate lacks the API it misuses, so the check proves nothing.
+
+use anyhow::{Context, Result};
+
+use forgetest_core::model::CompileFailExpectation;
+use forgetest_core::results::CompileFailResult;
+
+use crate::compiler;
+use crate::sandbox::Sandbox;
+
+/// Error codes for names that don't resolve (missing functions, types,
+/// imports and paths).
+const RESOLUTION_ERRORS: &[&str] = &["E0412", "E0425", "E0432", "E0433"];
+
+/// Compile each snippet against the crate in the sandbox.
+pub async fn check_compile_failures(
+    sandbox: &Sandbox,
+    snippets: &[CompileFailExpectation],
+) -> Result<Vec<CompileFailResult>> {
+    let crate_name = sandbox.crate_name()?;
+
+    // Snippets can only misuse the API if the library builds
+    let library = compiler::compile_with_args(sandbox, &["--lib"]).await?;
+    if !library.success {
+        tracing::warn!("library does not build; compile-fail snippets can't be checked");
+        return Ok(snippets
+            .iter()
+            .enumerate()
+            .map(|(i, snippet)| CompileFailResult {
+                name: snippet_name(snippet, i),
+                rejected: false,
+                expected_code: snippet.error_code.clone(),
+                error_codes: vec![],
+                passed: false,
+            })
+            .collect());
+    }
+
+    let examples_dir = sandbox.work_dir().join("examples");
+    std::fs::create_dir_all(&examples_dir).context("failed to create examples directory")?;
+
+    let mut results = Vec::with_capacity(snippets.len());
+    for (i, snippet) in snippets.iter().enumerate() {
+        let name = snippet_name(snippet, i);
+        let target = format!("forgetest_compile_fail_{}", i + 1);
+        let path = examples_dir.join(format!("{target}.rs"));
+
+        std::fs::write(&path, wrap_snippet(&crate_name, &snippet.code))
+            .with_context(|| format!("failed to write compile-fail snippet '{name}'"))?;
+        let compilation = compiler::compile_with_args(sandbox, &["--example", &target]).await;
+        // Don't leave the snippet behind for later stages (clippy, tests)
+        let _ = std::fs::remove_file(&path);
+        let compilation = compilation?;
+
+        let mut error_codes: Vec<String> = compilation
+            .errors
+            .iter()
+            .filter_map(|e| e.code.clone())
+            .collect();
+        error_codes.sort_unstable();
+        error_codes.dedup();
+
+        let rejected = !compilation.success;
+        let passed = rejected && is_expected_rejection(snippet.error_code.as_deref(), &error_codes);
+
+        results.push(CompileFailResult {
+            name,
+            rejected,
+            expected_code: snippet.error_code.clone(),
+            error_codes,
+            passed,
+        });
+    }
+
+    Ok(results)
+}
+
+/// The snippet's label, or a numbered default.
+fn snippet_name(snippet: &CompileFailExpectation, index: usize) -> String {
+    snippet
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("compile_fail_{}", index + 1))
+}
+
+/// Whether a rejection with `error_codes` is the one the snippet expects:
+/// the expected code if there is one, otherwise anything but an unresolved
+/// name.
+fn is_expected_rejection(expected: Option<&str>, error_codes: &[String]) -> bool {
+    match expected {
+        Some(code) => error_codes.iter().any(|c| c == code),
+        None => !error_codes
+            .iter()
+            .any(|c| RESOLUTION_ERRORS.contains(&c.as_str())),
+    }
+}
+
+/// Turn a snippet into a standalone example that sees the crate's public API.
+fn wrap_snippet(crate_name: &str, code: &str) -> String {
+    let mut source = format!("#[allow(unused_imports)]\nuse {crate_name}::*;\n\n{code}\n");
+    if !code.contains("fn main") {
+        source.push_str("\nfn main() {}\n");
+    }
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forgetest_core::model::Language;
+    use std::time::Duration;
+
+    #[test]
+    fn wrap_snippet_adds_import_and_main() {
+        let wrapped = wrap_snippet("eval_target", "fn misuse() { let _ = Door::new(); }");
+        assert!(wrapped.starts_with("#[allow(unused_imports)]\nuse eval_target::*;"));
+        assert!(wrapped.contains("fn main() {}"));
+
+        let with_main = wrap_snippet("eval_target", "fn main() { Door::new(); }");
+        assert_eq!(with_main.matches("fn main").count(), 1);
+    }
+
+    #[test]
+    fn unresolved_names_only_pass_when_expected() {
+        let codes = |codes: &[&str]| codes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        assert!(is_expected_rejection(None, &codes(&["E0382"])));
+        assert!(!is_expected_rejection(None, &codes(&["E0382", "E0425"])));
+        assert!(!is_expected_rejection(None, &codes(&["E0433"])));
+        assert!(is_expected_rejection(Some("E0425"), &codes(&["E0425"])));
+        assert!(!is_expected_rejection(Some("E0382"), &codes(&["E0308"])));
+    }
+
+    #[tokio::test]
+    async fn snippets_fail_against_a_binary_crate() {
+        let target = tempfile::tempdir().unwrap();
+        let sandbox =
+            Sandbox::new(Language::Rust, Duration::from_secs(120), target.path()).unwrap();
+        // Goes to src/main.rs, so the library exports nothing
+        sandbox
+            .write_source("pub fn consume(_t: String) {}\nfn main() {}")
+            .unwrap();
+
+        let snippets = vec![CompileFailExpectation {
+            name: None,
+            code: "fn misuse() { consume(1); }".into(),
+            error_code: None,
+        }];
+        let results = check_compile_failures(&sandbox, &snippets).await.unwrap();
+        assert!(results[0].rejected);
+        assert!(!results[0].passed, "got {:?}", results[0].error_codes);
+    }
+
+    #[tokio::test]
+    async fn rejected_snippets_pass() {
+        let target = tempfile::tempdir().unwrap();
+        let sandbox =
+            Sandbox::new(Language::Rust, Duration::from_secs(120), target.path()).unwrap();
+        sandbox
+            .write_source("pub struct Token(String);\npub fn consume(_t: Token) {}\npub fn token() -> Token { Token(String::new()) }")
+            .unwrap();
+
+        let snippets = vec![
+            CompileFailExpectation {
+                name: Some("use_after_move".into()),
+                code: "fn main() { let t = token(); consume(t); consume(t); }".into(),
+                error_code: Some("E0382".into()),
+            },
+            CompileFailExpectation {
+                name: None,
+                code: "fn main() { consume(token()); }".into(),
+                error_code: None,
+            },
+        ];
+
+        let results = check_compile_failures(&sandbox, &snippets).await.unwrap();
+        assert!(results[0].rejected);
+        assert!(
+            results[0].passed,
+            "E0382 expected, got {:?}",
+            results[0].error_codes
+        );
+        assert_eq!(results[1].name, "compile_fail_2");
+        assert!(!results[1].rejected);
+        assert!(!results[1].passed);
+        assert!(!sandbox
+            .work_dir()
+            .join("examples/forgetest_compile_fail_1.rs")
+            .exists());
+    }
+}
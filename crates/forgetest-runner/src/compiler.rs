@@ -14,11 +14,21 @@ use crate::sandbox::Sandbox;
 
 /// Compile the code in a sandbox.
 pub async fn compile(sandbox: &Sandbox) -> Result<CompilationResult> {
+    compile_with_args(sandbox, &[]).await
+}
+
+/// Compile the code in a sandbox, passing extra arguments to `cargo build`
+/// (e.g. `--example name` to build a single target).
+pub async fn compile_with_args(
+    sandbox: &Sandbox,
+    extra_args: &[&str],
+) -> Result<CompilationResult> {
     let start = Instant::now();
 
     let mut cmd = Command::new("cargo");
     cmd.arg("build")
         .arg("--message-format=json")
+        .args(extra_args)
         .current_dir(sandbox.work_dir())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -46,7 +56,9 @@ pub async fn compile(sandbox: &Sandbox) -> Result<CompilationResult> {
 }
 
 /// Parse cargo's JSON output into diagnostics.
-fn parse_cargo_json_output(output: &str) -> (Vec<CompilerDiagnostic>, Vec<CompilerDiagnostic>) {
+pub(crate) fn parse_cargo_json_output(
+    output: &str,
+) -> (Vec<CompilerDiagnostic>, Vec<CompilerDiagnostic>) {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
 
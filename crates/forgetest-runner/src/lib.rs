@@ -3,23 +3,34 @@
 //! Creates isolated Cargo projects for each eval, compiles generated code,
 //! runs tests, and collects clippy diagnostics.
 
+pub mod api_check;
+pub mod bench;
 pub mod clippy;
+pub mod compile_fail;
 pub mod compiler;
+pub mod miri;
+pub mod rustfmt;
 pub mod sandbox;
 pub mod test_runner;
 
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use forgetest_core::model::{EvalCase, Language};
+use forgetest_core::hallucination::{find_hallucinated_apis, HallucinatedApi};
+use forgetest_core::model::{EvalCase, Fixture, Language};
 use forgetest_core::results::{
-    ClippyResult, CompilationResult, EvalResult, TestResult, TimingInfo, TokenUsage,
+    ClippyResult, CompilationResult, CompileFailResult, CompilerDiagnostic, EvalResult,
+    FormattingResult, MiriResult, PerformanceResult, Score, TestResult, TimingInfo, TokenUsage,
+};
+use forgetest_core::traits::{
+    ApiCheckRequest, BenchmarkRequest, ClippyRequest, CodeRunner, CompileFailRequest,
+    CompileRequest, Dependency, FormatRequest, TestRequest,
 };
-use forgetest_core::traits::{ClippyRequest, CodeRunner, CompileRequest, Dependency, TestRequest};
 
 /// Local code runner that uses sandboxed Cargo projects.
 pub struct LocalRunner {
@@ -29,6 +40,8 @@ pub struct LocalRunner {
     default_timeout: Duration,
     /// Default dependencies added to every sandbox.
     default_dependencies: Vec<Dependency>,
+    /// Indexes of dependency crates for hallucinated-API checks.
+    api_index: Arc<api_check::ApiIndex>,
 }
 
 impl LocalRunner {
@@ -37,6 +50,7 @@ impl LocalRunner {
             shared_target_dir,
             default_timeout: Duration::from_secs(120),
             default_dependencies: Vec::new(),
+            api_index: Arc::new(api_check::ApiIndex::from_env()),
         }
     }
 
@@ -50,61 +64,202 @@ impl LocalRunner {
         self
     }
 
-    fn create_sandbox(&self, language: Language, timeout_secs: u64) -> Result<sandbox::Sandbox> {
+    fn create_sandbox(
+        &self,
+        language: Language,
+        timeout_secs: u64,
+        fixture: Option<&Fixture>,
+    ) -> Result<sandbox::Sandbox> {
         let timeout = if timeout_secs > 0 {
             Duration::from_secs(timeout_secs)
         } else {
             self.default_timeout
         };
-        sandbox::Sandbox::new(language, timeout, &self.shared_target_dir)
+        match fixture {
+            Some(fixture) => sandbox::Sandbox::from_fixture(
+                &fixture.path,
+                language,
+                timeout,
+                &self.shared_target_dir,
+            ),
+            None => sandbox::Sandbox::new(language, timeout, &self.shared_target_dir),
+        }
+    }
+
+    /// Create a sandbox holding `code` and the request's dependencies.
+    fn prepare_sandbox(
+        &self,
+        language: Language,
+        timeout_secs: u64,
+        fixture: Option<&Fixture>,
+        code: &str,
+        dependencies: &[Dependency],
+    ) -> Result<sandbox::Sandbox> {
+        let sandbox = self.create_sandbox(language, timeout_secs, fixture)?;
+        write_generated(&sandbox, fixture, code)?;
+        for dep in self.default_dependencies.iter().chain(dependencies) {
+            sandbox.add_dependency(dep)?;
+        }
+        Ok(sandbox)
+    }
+
+    /// Find non-existent dependency APIs among compile errors, checking them
+    /// against the dependencies' sources in the local cargo registry.
+    async fn find_hallucinated_apis(
+        &self,
+        code: &str,
+        errors: &[CompilerDiagnostic],
+        dependencies: &[Dependency],
+    ) -> Result<Vec<HallucinatedApi>> {
+        let dependencies: Vec<Dependency> = self
+            .default_dependencies
+            .iter()
+            .chain(dependencies)
+            .cloned()
+            .collect();
+        // Indexing a crate reads its whole source tree
+        let index = Arc::clone(&self.api_index);
+        let (dependencies, apis) = tokio::task::spawn_blocking(move || {
+            let apis = api_check::crate_apis(&index, &dependencies);
+            (dependencies, apis)
+        })
+        .await
+        .context("dependency API indexing panicked")?;
+        Ok(find_hallucinated_apis(errors, code, &dependencies, &apis))
+    }
+}
+
+/// Write generated code into the sandbox, honouring the fixture's target file.
+fn write_generated(
+    sandbox: &sandbox::Sandbox,
+    fixture: Option<&Fixture>,
+    code: &str,
+) -> Result<()> {
+    match fixture {
+        Some(fixture) => sandbox.write_fixture_source(&fixture.target_file, code),
+        None => sandbox.write_source(code),
+    }
+}
+
+/// Append test code to the file that received the generated code.
+fn write_generated_test(
+    sandbox: &sandbox::Sandbox,
+    fixture: Option<&Fixture>,
+    test_code: &str,
+) -> Result<()> {
+    match fixture {
+        Some(fixture) => sandbox.write_fixture_test(&fixture.target_file, test_code),
+        None => sandbox.write_test(test_code),
     }
 }
 
 #[async_trait]
 impl CodeRunner for LocalRunner {
     async fn compile(&self, request: &CompileRequest) -> Result<CompilationResult> {
-        let sandbox = self.create_sandbox(request.language, request.timeout_secs)?;
-        sandbox.write_source(&request.code)?;
-        for dep in self
-            .default_dependencies
-            .iter()
-            .chain(request.dependencies.iter())
-        {
-            sandbox.add_dependency(dep)?;
-        }
+        let sandbox = self.prepare_sandbox(
+            request.language,
+            request.timeout_secs,
+            request.fixture.as_ref(),
+            &request.code,
+            &request.dependencies,
+        )?;
         compiler::compile(&sandbox).await
     }
 
     async fn run_tests(&self, request: &TestRequest) -> Result<TestResult> {
-        let sandbox = self.create_sandbox(request.language, request.timeout_secs)?;
-        sandbox.write_source(&request.code)?;
-        sandbox.write_test(&request.test_code)?;
-        for dep in self
-            .default_dependencies
-            .iter()
-            .chain(request.dependencies.iter())
-        {
-            sandbox.add_dependency(dep)?;
+        let sandbox = self.prepare_sandbox(
+            request.language,
+            request.timeout_secs,
+            request.fixture.as_ref(),
+            &request.code,
+            &request.dependencies,
+        )?;
+        if !request.test_code.is_empty() {
+            write_generated_test(&sandbox, request.fixture.as_ref(), &request.test_code)?;
         }
 
         test_runner::run_tests(&sandbox).await
     }
 
+    async fn run_benchmark(&self, request: &BenchmarkRequest) -> Result<PerformanceResult> {
+        let fixture = request.fixture.as_ref();
+        let sandbox = self.prepare_sandbox(
+            request.language,
+            request.timeout_secs,
+            fixture,
+            &request.code,
+            &request.dependencies,
+        )?;
+        let reference = match &request.reference_solution {
+            Some(reference) if request.performance.max_ratio.is_some() => {
+                Some(self.prepare_sandbox(
+                    request.language,
+                    request.timeout_secs,
+                    fixture,
+                    reference,
+                    &request.dependencies,
+                )?)
+            }
+            _ => None,
+        };
+        bench::run_benchmark(&sandbox, &request.performance, reference.as_ref()).await
+    }
+
+    async fn run_miri(&self, request: &TestRequest) -> Result<MiriResult> {
+        let sandbox = self.prepare_sandbox(
+            request.language,
+            request.timeout_secs,
+            request.fixture.as_ref(),
+            &request.code,
+            &request.dependencies,
+        )?;
+        if !request.test_code.is_empty() {
+            write_generated_test(&sandbox, request.fixture.as_ref(), &request.test_code)?;
+        }
+
+        miri::run_miri(&sandbox).await
+    }
+
     async fn run_clippy(&self, request: &ClippyRequest) -> Result<ClippyResult> {
-        let sandbox = self.create_sandbox(request.language, request.timeout_secs)?;
-        sandbox.write_source(&request.code)?;
-        for dep in self
-            .default_dependencies
-            .iter()
-            .chain(request.dependencies.iter())
-        {
-            sandbox.add_dependency(dep)?;
+        let sandbox = self.prepare_sandbox(
+            request.language,
+            request.timeout_secs,
+            request.fixture.as_ref(),
+            &request.code,
+            &request.dependencies,
+        )?;
+        clippy::run_clippy(&sandbox, &request.config).await
+    }
+
+    async fn check_compile_failures(
+        &self,
+        request: &CompileFailRequest,
+    ) -> Result<Vec<CompileFailResult>> {
+        let sandbox = self.prepare_sandbox(
+            request.language,
+            request.timeout_secs,
+            request.fixture.as_ref(),
+            &request.code,
+            &request.dependencies,
+        )?;
+        compile_fail::check_compile_failures(&sandbox, &request.snippets).await
+    }
+
+    async fn check_formatting(&self, request: &FormatRequest) -> Result<Option<FormattingResult>> {
+        if request.language != Language::Rust {
+            return Ok(None);
         }
-        clippy::run_clippy(&sandbox).await
+        let sandbox = self.create_sandbox(request.language, request.timeout_secs, None)?;
+        rustfmt::check_formatting(&sandbox, &request.code, &request.config).await
+    }
+
+    async fn check_api_usage(&self, request: &ApiCheckRequest) -> Result<Vec<HallucinatedApi>> {
+        self.find_hallucinated_apis(&request.code, &request.errors, &request.dependencies)
+            .await
     }
 }
 
-/// Run a full eval: compile, test, clippy, compute score.
+/// Run a full eval: compile, test, clippy, compute score with the default scorer.
 #[allow(clippy::too_many_arguments)]
 pub async fn run_eval(
     runner: &LocalRunner,
@@ -119,9 +274,14 @@ pub async fn run_eval(
 ) -> Result<EvalResult> {
     let language = case.language.unwrap_or(Language::Rust);
     let timeout_secs = case.timeout_secs.unwrap_or(60);
-    let sandbox = runner.create_sandbox(language, timeout_secs)?;
-
-    sandbox.write_source(generated_code)?;
+    let fixture = case.fixture.as_ref();
+    let sandbox = runner.prepare_sandbox(
+        language,
+        timeout_secs,
+        fixture,
+        generated_code,
+        &case.dependencies,
+    )?;
 
     // Compile
     let compilation = compiler::compile(&sandbox).await?;
@@ -130,10 +290,13 @@ pub async fn run_eval(
     // Run tests if compilation succeeded and tests are expected
     let test_execution = if compilation.success && case.expectations.should_pass_tests {
         if let Some(test_file) = &case.expectations.test_file {
-            sandbox.write_test(test_file)?;
+            write_generated_test(&sandbox, fixture, test_file)?;
             // Need to recompile with tests
             let _recompile = compiler::compile(&sandbox).await?;
             Some(test_runner::run_tests(&sandbox).await?)
+        } else if fixture.is_some() {
+            // The fixture's own test suite is the oracle
+            Some(test_runner::run_tests(&sandbox).await?)
         } else {
             None
         }
@@ -142,16 +305,71 @@ pub async fn run_eval(
     };
     let test_execution_ms = test_execution.as_ref().map(|t| t.duration_ms).unwrap_or(0);
 
+    // Benchmark against the performance budget
+    let performance = match &case.expectations.performance {
+        Some(perf) if compilation.success => {
+            let reference = match &case.expectations.reference_solution {
+                Some(reference) if perf.max_ratio.is_some() => Some(runner.prepare_sandbox(
+                    language,
+                    timeout_secs,
+                    fixture,
+                    reference,
+                    &case.dependencies,
+                )?),
+                _ => None,
+            };
+            Some(bench::run_benchmark(&sandbox, perf, reference.as_ref()).await?)
+        }
+        _ => None,
+    };
+
+    // Look for undefined behaviour if the case opts in (tests are already written)
+    let miri = if compilation.success && case.expectations.run_miri {
+        Some(miri::run_miri(&sandbox).await?)
+    } else {
+        None
+    };
+
+    // Check that misuse snippets are rejected
+    let compile_fail = if compilation.success {
+        compile_fail::check_compile_failures(&sandbox, &case.expectations.expected_compile_failures)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    // Look for calls into APIs the dependencies don't have
+    let hallucinated_apis = if !compilation.success && language == Language::Rust {
+        runner
+            .find_hallucinated_apis(generated_code, &compilation.errors, &case.dependencies)
+            .await?
+    } else {
+        Vec::new()
+    };
+
     // Run clippy if compilation succeeded
     let clippy_result = if compilation.success {
-        Some(clippy::run_clippy(&sandbox).await?)
+        Some(clippy::run_clippy(&sandbox, &case.expectations.clippy).await?)
+    } else {
+        None
+    };
+
+    // Check formatting if compilation succeeded
+    let formatting = if compilation.success && language == Language::Rust {
+        rustfmt::check_formatting(&sandbox, generated_code, &case.expectations.formatting).await?
+    } else {
+        None
+    };
+
+    let metrics = if language == Language::Rust {
+        forgetest_core::metrics::analyze(generated_code)
     } else {
         None
     };
 
     let total_ms = llm_request_ms + compilation_ms + test_execution_ms;
 
-    Ok(EvalResult {
+    let mut result = EvalResult {
         case_id: case.id.clone(),
         model: model.to_string(),
         provider: provider.to_string(),
@@ -159,6 +377,14 @@ pub async fn run_eval(
         compilation,
         test_execution,
         clippy: clippy_result,
+        compile_fail,
+        hallucinated_apis,
+        formatting,
+        miri,
+        performance,
+        metrics,
+        judge: None,
+        score: None,
         timing: TimingInfo {
             llm_request_ms,
             compilation_ms,
@@ -166,9 +392,12 @@ pub async fn run_eval(
             total_ms,
         },
         token_usage,
+        cached: false,
         attempt,
         run_id,
-    })
+    };
+    result.score = Some(Score::compute(&result, &case.expectations));
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -185,6 +414,7 @@ mod tests {
             language: Language::Rust,
             dependencies: vec![],
             timeout_secs: 120,
+            fixture: None,
         };
 
         let result = runner.compile(&request).await.unwrap();
@@ -213,6 +443,7 @@ mod tests {
             language: Language::Rust,
             dependencies: vec![],
             timeout_secs: 120,
+            fixture: None,
         };
 
         let result = runner.run_tests(&request).await.unwrap();
@@ -245,6 +476,7 @@ mod tests {
             language: Language::Rust,
             dependencies: vec![],
             timeout_secs: 120,
+            fixture: None,
         };
 
         let result = runner.run_tests(&request).await.unwrap();
@@ -281,9 +513,12 @@ mod tests {
                 ..Default::default()
             },
             tags: vec![],
+            difficulty: None,
             dependencies: vec![],
             timeout_secs: Some(120),
             max_tokens: None,
+            fixture: None,
+            fim: None,
         };
 
         let code = "pub fn add(a: i32, b: i32) -> i32 { a + b }";
@@ -0,0 +1,233 @@
+//! Miri execution for detecting undefined behaviour.
+
+use std::process::Stdio;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use tokio::process::Command;
+
+use forgetest_core::results::{CompilerDiagnostic, DiagnosticLevel, DiagnosticSpan, MiriResult};
+
+use crate::sandbox::Sandbox;
+
+/// Diagnostic code attached to undefined behaviour findings.
+pub const MIRI_UB_CODE: &str = "miri::ub";
+
+/// Run the sandbox's tests under `cargo +nightly miri test`.
+///
+/// Returns a skipped result if the nightly `miri` component is not installed,
+/// Miri times out, there are no tests to run, or it fails without reporting
+/// undefined behaviour (e.g. because the sandbox doesn't build under Miri).
+pub async fn run_miri(sandbox: &Sandbox) -> Result<MiriResult> {
+    if !check_miri_available().await {
+        return Ok(MiriResult::skipped(
+            "miri is not installed (rustup +nightly component add miri)",
+        ));
+    }
+
+    let start = Instant::now();
+
+    let mut cmd = Command::new("cargo");
+    cmd.arg("+nightly")
+        .arg("miri")
+        .arg("test")
+        .current_dir(sandbox.work_dir())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    for (key, val) in sandbox.build_env() {
+        cmd.env(&key, &val);
+    }
+
+    let Ok(result) = tokio::time::timeout(sandbox.timeout(), cmd.output()).await else {
+        return Ok(MiriResult::skipped(format!(
+            "miri timed out after {}s",
+            sandbox.timeout().as_secs()
+        )));
+    };
+    let result = result.context("failed to run cargo miri test")?;
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    let mut miri = interpret_miri_output(result.status.success(), &stdout, &stderr);
+    miri.duration_ms = start.elapsed().as_millis() as u64;
+    Ok(miri)
+}
+
+/// Turn a finished Miri run into a result. A failed run without undefined
+/// behaviour findings, or a run without tests, says nothing about UB either
+/// way, so it is skipped.
+fn interpret_miri_output(success: bool, stdout: &str, stderr: &str) -> MiriResult {
+    let findings = parse_miri_output(stderr);
+    if success && tests_run(stdout) == 0 {
+        return MiriResult::skipped("no tests to run under miri");
+    }
+    if !success && findings.is_empty() {
+        let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+        let tail: Vec<&str> = tail.into_iter().rev().collect();
+        return MiriResult::skipped(format!(
+            "miri failed without reporting undefined behaviour:\n{}",
+            tail.join("\n")
+        ));
+    }
+    MiriResult {
+        skipped: false,
+        skip_reason: None,
+        findings,
+        duration_ms: 0,
+    }
+}
+
+/// Total of the `running N tests` lines printed by the test harnesses.
+fn tests_run(stdout: &str) -> u32 {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("running ")?
+                .split_whitespace()
+                .next()?
+                .parse::<u32>()
+                .ok()
+        })
+        .sum()
+}
+
+/// Parse undefined behaviour reports from Miri's human-readable output.
+///
+/// A report starts with `error: Undefined Behavior: ...` and is followed by a
+/// `--> file:line:col` location line.
+fn parse_miri_output(output: &str) -> Vec<CompilerDiagnostic> {
+    let mut findings: Vec<CompilerDiagnostic> = Vec::new();
+    let mut awaiting_location = false;
+
+    for line in output.lines() {
+        if let Some(message) = line.strip_prefix("error: Undefined Behavior: ") {
+            findings.push(CompilerDiagnostic {
+                level: DiagnosticLevel::Error,
+                message: format!("Undefined Behavior: {}", message.trim()),
+                code: Some(MIRI_UB_CODE.to_string()),
+                spans: vec![],
+            });
+            awaiting_location = true;
+        } else if awaiting_location {
+            if let Some(location) = line.trim_start().strip_prefix("--> ") {
+                if let (Some(span), Some(finding)) = (parse_location(location), findings.last_mut())
+                {
+                    finding.spans.push(span);
+                }
+                awaiting_location = false;
+            }
+        }
+    }
+
+    findings
+}
+
+/// Parse a `file:line:col` location.
+fn parse_location(location: &str) -> Option<DiagnosticSpan> {
+    let mut parts = location.trim().rsplitn(3, ':');
+    let column: u32 = parts.next()?.parse().ok()?;
+    let line: u32 = parts.next()?.parse().ok()?;
+    let file = parts.next()?.to_string();
+    Some(DiagnosticSpan {
+        file,
+        line_start: line,
+        line_end: line,
+        column_start: column,
+        column_end: column,
+        text: None,
+    })
+}
+
+/// Check if the nightly toolchain has the Miri component.
+pub async fn check_miri_available() -> bool {
+    Command::new("cargo")
+        .arg("+nightly")
+        .arg("miri")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ub_report() {
+        let output = "\
+error: Undefined Behavior: memory access failed: alloc1 has size 4, so pointer to 4 bytes starting at offset 4 is out-of-bounds
+ --> src/lib.rs:3:14
+  |
+3 |     unsafe { *p.add(1) }
+  |              ^^^^^^^^^ memory access failed
+  |
+  = help: this indicates a bug in the program: it performed an invalid operation, and caused Undefined Behavior
+
+error: test failed, to rerun pass `--lib`
+";
+        let findings = parse_miri_output(output);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("out-of-bounds"));
+        assert_eq!(findings[0].code.as_deref(), Some(MIRI_UB_CODE));
+        assert_eq!(findings[0].spans[0].file, "src/lib.rs");
+        assert_eq!(findings[0].spans[0].line_start, 3);
+        assert_eq!(findings[0].spans[0].column_start, 14);
+    }
+
+    #[test]
+    fn parse_clean_run() {
+        let output = "running 2 tests\ntest a ... ok\ntest b ... ok\n\ntest result: ok. 2 passed";
+        assert!(parse_miri_output(output).is_empty());
+    }
+
+    #[test]
+    fn failed_run_without_ub_is_skipped() {
+        let stderr =
+            "error[E0425]: cannot find function `missing`\nerror: could not compile `sandbox`";
+        let result = interpret_miri_output(false, "", stderr);
+        assert!(result.skipped);
+        assert!(result.skip_reason.unwrap().contains("could not compile"));
+
+        let ub = "error: Undefined Behavior: use of uninitialized memory\n --> src/lib.rs:2:5\n";
+        let result = interpret_miri_output(false, "running 1 test\n", ub);
+        assert!(!result.skipped);
+        assert!(result.ub_detected());
+
+        let clean = "running 1 test\ntest a ... ok\n\ntest result: ok. 1 passed";
+        assert!(!interpret_miri_output(true, clean, "").skipped);
+    }
+
+    #[test]
+    fn run_without_tests_is_skipped() {
+        let stdout = "running 0 tests\n\ntest result: ok. 0 passed\n\nrunning 0 tests\n";
+        let result = interpret_miri_output(true, stdout, "");
+        assert!(result.skipped);
+        assert_eq!(
+            result.skip_reason.as_deref(),
+            Some("no tests to run under miri")
+        );
+    }
+
+    #[tokio::test]
+    async fn skipped_when_unavailable() {
+        if check_miri_available().await {
+            return;
+        }
+        let target = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(
+            forgetest_core::model::Language::Rust,
+            std::time::Duration::from_secs(30),
+            target.path(),
+        )
+        .unwrap();
+        let result = run_miri(&sandbox).await.unwrap();
+        assert!(result.skipped);
+        assert!(result.skip_reason.as_deref().unwrap().contains("miri"));
+        assert!(!result.ub_detected());
+    }
+}
@@ -0,0 +1,178 @@
+//! rustfmt conformance check.
+
+use std::process::Stdio;
+
+use anyhow::{Context, Result};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use forgetest_core::model::FormattingConfig;
+use forgetest_core::results::FormattingResult;
+
+use crate::sandbox::Sandbox;
+
+/// Run `rustfmt --check` on the code, using the sandbox for `rustfmt.toml`.
+///
+/// Only the generated code is checked (passed on stdin), not appended test
+/// code or fixture files. Returns `None` if rustfmt is not installed or
+/// can't check the code, e.g. because the case's `rustfmt.toml` is invalid.
+pub async fn check_formatting(
+    sandbox: &Sandbox,
+    code: &str,
+    config: &FormattingConfig,
+) -> Result<Option<FormattingResult>> {
+    if let Some(contents) = &config.config {
+        std::fs::write(sandbox.work_dir().join("rustfmt.toml"), contents)
+            .context("failed to write rustfmt.toml")?;
+    }
+
+    let mut cmd = Command::new("rustfmt");
+    cmd.arg("--check")
+        .arg("--edition")
+        .arg("2021")
+        .arg("--color")
+        .arg("never");
+    // Without a config file to point at, rustfmt refuses to run at all
+    if config.config.is_some() {
+        cmd.arg("--config-path").arg(sandbox.work_dir());
+    }
+    cmd.current_dir(sandbox.work_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!("rustfmt not found, skipping formatting check");
+            return Ok(None);
+        }
+        Err(e) => return Err(e).context("failed to run rustfmt"),
+    };
+
+    let mut stdin = child.stdin.take().context("rustfmt stdin unavailable")?;
+    stdin
+        .write_all(code.as_bytes())
+        .await
+        .context("failed to write code to rustfmt")?;
+    drop(stdin);
+
+    let output = tokio::time::timeout(sandbox.timeout(), child.wait_with_output())
+        .await
+        .context("rustfmt timed out")?
+        .context("failed to run rustfmt")?;
+
+    // Exit code 1 means "not formatted" only if there's a diff to show;
+    // otherwise rustfmt failed and the code wasn't checked
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let checked = match output.status.code() {
+        Some(0) => true,
+        Some(1) => !stdout.trim().is_empty(),
+        _ => false,
+    };
+    if !checked {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        tracing::warn!(
+            "rustfmt failed ({}), skipping formatting check: {}",
+            output.status,
+            stderr.trim()
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(parse_rustfmt_diff(&stdout)))
+}
+
+/// Parse `rustfmt --check` output into a hunk count and normalized diff.
+///
+/// Hunk headers like `Diff in <stdin>:12:` become `@@ line 12 @@`.
+fn parse_rustfmt_diff(output: &str) -> FormattingResult {
+    let mut hunks = 0;
+    let mut diff = String::new();
+
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("Diff in ") {
+            hunks += 1;
+            let line_no = rest
+                .trim_end_matches(':')
+                .rsplit(':')
+                .next()
+                .unwrap_or_default();
+            diff.push_str(&format!("@@ line {line_no} @@\n"));
+        } else if hunks > 0 {
+            diff.push_str(line.trim_end());
+            diff.push('\n');
+        }
+    }
+
+    FormattingResult { hunks, diff }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forgetest_core::model::Language;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_diff_counts_hunks() {
+        let output = "Diff in <stdin>:1:\n-pub fn f( x:u32){}\n+pub fn f(x: u32) {}\n \nDiff in <stdin>:9:\n-    let a=1;\n+    let a = 1;\n";
+        let result = parse_rustfmt_diff(output);
+        assert_eq!(result.hunks, 2);
+        assert!(result.diff.starts_with("@@ line 1 @@\n-pub fn f( x:u32){}"));
+        assert!(result.diff.contains("@@ line 9 @@"));
+        assert!(!result.diff.contains("<stdin>"));
+
+        assert_eq!(parse_rustfmt_diff("").hunks, 0);
+    }
+
+    #[tokio::test]
+    async fn check_formatting_with_config() {
+        let target = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(Language::Rust, Duration::from_secs(30), target.path()).unwrap();
+        let code = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+
+        let Some(result) = check_formatting(&sandbox, code, &FormattingConfig::default())
+            .await
+            .unwrap()
+        else {
+            return; // rustfmt not installed
+        };
+        assert_eq!(result.hunks, 0);
+
+        let vertical = FormattingConfig {
+            config: Some("fn_params_layout = \"Vertical\"".into()),
+            weight: 0.0,
+        };
+        let result = check_formatting(&sandbox, code, &vertical)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.hunks, 1);
+
+        // A broken config skips the check rather than passing it
+        let broken = FormattingConfig {
+            config: Some("max_width = \"wide\"".into()),
+            weight: 0.0,
+        };
+        assert!(check_formatting(&sandbox, code, &broken)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn check_formatting_without_config_finds_hunks() {
+        let target = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(Language::Rust, Duration::from_secs(30), target.path()).unwrap();
+        let code = "pub fn add( a:i32,b:i32)->i32{a+b}\n";
+
+        let Some(result) = check_formatting(&sandbox, code, &FormattingConfig::default())
+            .await
+            .unwrap()
+        else {
+            return; // rustfmt not installed
+        };
+        assert!(result.hunks > 0);
+    }
+}
@@ -1,12 +1,12 @@
 //! Sandboxed Cargo project for compiling and testing generated code.
 
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
 use tempfile::TempDir;
 
-use forgetest_core::model::Language;
+use forgetest_core::model::{Language, FIXTURE_FILL_MARKER};
 use forgetest_core::traits::Dependency;
 
 /// A sandboxed Cargo project for compiling and testing generated code.
@@ -57,6 +57,37 @@ edition = "2021"
         })
     }
 
+    /// Create a sandbox from a copy of an existing fixture crate.
+    ///
+    /// The fixture directory is copied as-is (minus `target/` and `.git/`),
+    /// so its Cargo.toml, tests and data files are all available.
+    pub fn from_fixture(
+        fixture_dir: &Path,
+        language: Language,
+        timeout: Duration,
+        shared_target_dir: &Path,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            fixture_dir.join("Cargo.toml").is_file(),
+            "fixture directory {} does not contain a Cargo.toml",
+            fixture_dir.display()
+        );
+
+        let work_dir = TempDir::new().context("failed to create temp directory")?;
+        copy_dir_recursive(fixture_dir, work_dir.path())
+            .with_context(|| format!("failed to copy fixture from {}", fixture_dir.display()))?;
+
+        std::fs::create_dir_all(shared_target_dir)
+            .context("failed to create shared target directory")?;
+
+        Ok(Self {
+            work_dir,
+            shared_target_dir: shared_target_dir.to_path_buf(),
+            timeout,
+            language,
+        })
+    }
+
     /// Get the path to the sandbox working directory.
     pub fn work_dir(&self) -> &Path {
         self.work_dir.path()
@@ -77,6 +108,21 @@ edition = "2021"
         self.language
     }
 
+    /// The library crate name, as used in `use` paths (hyphens become underscores).
+    pub fn crate_name(&self) -> Result<String> {
+        let content = std::fs::read_to_string(self.work_dir.path().join("Cargo.toml"))
+            .context("failed to read Cargo.toml")?;
+        let doc = content
+            .parse::<toml_edit::DocumentMut>()
+            .context("failed to parse Cargo.toml")?;
+        let name = doc
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .context("Cargo.toml has no package name")?;
+        Ok(name.replace('-', "_"))
+    }
+
     /// Write source code to the sandbox.
     ///
     /// If the code contains `fn main`, it goes to `src/main.rs`.
@@ -92,17 +138,73 @@ edition = "2021"
         Ok(())
     }
 
+    /// Write generated code into a designated file of a fixture crate.
+    ///
+    /// If the file has [`FIXTURE_FILL_MARKER`] on a line of its own, that
+    /// line is replaced by the code. Otherwise the file is overwritten (or
+    /// created). A marker sharing its line with other code is an error, since
+    /// the code would have nowhere to go.
+    pub fn write_fixture_source(&self, target_file: &str, code: &str) -> Result<()> {
+        let path = self.fixture_path(target_file)?;
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
+        let has_marker_line = existing
+            .lines()
+            .any(|line| line.trim() == FIXTURE_FILL_MARKER);
+        anyhow::ensure!(
+            has_marker_line || !existing.contains(FIXTURE_FILL_MARKER),
+            "{FIXTURE_FILL_MARKER} in {target_file} must be on a line by itself"
+        );
+        let content = if has_marker_line {
+            existing
+                .lines()
+                .map(|line| {
+                    if line.trim() == FIXTURE_FILL_MARKER {
+                        code
+                    } else {
+                        line
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            code.to_string()
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content).with_context(|| format!("failed to write {target_file}"))?;
+        Ok(())
+    }
+
     /// Write test code into the sandbox.
     ///
     /// Appends the test code to `src/lib.rs` after the main source code.
     pub fn write_test(&self, test_code: &str) -> Result<()> {
-        let lib_path = self.work_dir.path().join("src").join("lib.rs");
-        let existing = std::fs::read_to_string(&lib_path).unwrap_or_default();
+        self.write_fixture_test("src/lib.rs", test_code)
+    }
+
+    /// Append test code to a fixture's target file, next to the generated
+    /// code it exercises.
+    pub fn write_fixture_test(&self, target_file: &str, test_code: &str) -> Result<()> {
+        let path = self.fixture_path(target_file)?;
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
         let combined = format!("{existing}\n\n{test_code}");
-        std::fs::write(&lib_path, combined).context("failed to write test code")?;
+        std::fs::write(&path, combined).context("failed to write test code")?;
         Ok(())
     }
 
+    /// Path of a file inside the sandbox, which `relative` must not escape.
+    fn fixture_path(&self, relative: &str) -> Result<PathBuf> {
+        anyhow::ensure!(
+            Path::new(relative)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_))),
+            "fixture target file must be a relative path inside the fixture: {relative}"
+        );
+        Ok(self.work_dir.path().join(relative))
+    }
+
     /// Add a dependency to the sandbox's Cargo.toml.
     pub fn add_dependency(&self, dep: &Dependency) -> Result<()> {
         let cargo_path = self.work_dir.path().join("Cargo.toml");
@@ -111,9 +213,12 @@ edition = "2021"
             .parse::<toml_edit::DocumentMut>()
             .context("failed to parse Cargo.toml")?;
 
-        let deps = doc["dependencies"]
+        // Fixture crates without dependencies may omit the table entirely
+        let deps = doc
+            .entry("dependencies")
+            .or_insert(toml_edit::table())
             .as_table_mut()
-            .context("missing [dependencies] table")?;
+            .context("[dependencies] is not a table")?;
 
         if dep.features.is_empty() {
             deps[&dep.name] = toml_edit::value(&dep.version);
@@ -167,6 +272,26 @@ edition = "2021"
     }
 }
 
+/// Recursively copy a directory, skipping build output and VCS metadata.
+fn copy_dir_recursive(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "target" || name == ".git" {
+            continue;
+        }
+        let src = entry.path();
+        let dst = to.join(&name);
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&src, &dst)?;
+        } else {
+            std::fs::copy(&src, &dst)?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,4 +359,102 @@ mod tests {
         assert!(content.contains("pub fn add"));
         assert!(content.contains("test_add"));
     }
+
+    fn make_fixture() -> TempDir {
+        let fixture = tempfile::tempdir().unwrap();
+        std::fs::write(
+            fixture.path().join("Cargo.toml"),
+            "[package]\nname = \"fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(fixture.path().join("src")).unwrap();
+        std::fs::create_dir_all(fixture.path().join("tests/data")).unwrap();
+        std::fs::write(
+            fixture.path().join("src/lib.rs"),
+            "pub mod client;\n\n// forgetest:fill\n",
+        )
+        .unwrap();
+        std::fs::write(fixture.path().join("tests/data/input.txt"), "hello").unwrap();
+        std::fs::create_dir_all(fixture.path().join("target/debug")).unwrap();
+        fixture
+    }
+
+    #[test]
+    fn from_fixture_copies_crate() {
+        let target = tempfile::tempdir().unwrap();
+        let fixture = make_fixture();
+        let sandbox = Sandbox::from_fixture(
+            fixture.path(),
+            Language::Rust,
+            Duration::from_secs(60),
+            target.path(),
+        )
+        .unwrap();
+
+        assert!(sandbox.work_dir().join("Cargo.toml").exists());
+        assert!(sandbox.work_dir().join("tests/data/input.txt").exists());
+        assert!(!sandbox.work_dir().join("target").exists());
+        assert_eq!(sandbox.crate_name().unwrap(), "fixture");
+    }
+
+    #[test]
+    fn write_fixture_source_fills_marker_or_replaces() {
+        let target = tempfile::tempdir().unwrap();
+        let fixture = make_fixture();
+        let sandbox = Sandbox::from_fixture(
+            fixture.path(),
+            Language::Rust,
+            Duration::from_secs(60),
+            target.path(),
+        )
+        .unwrap();
+
+        sandbox
+            .write_fixture_source("src/lib.rs", "pub fn filled() {}")
+            .unwrap();
+        let lib = std::fs::read_to_string(sandbox.work_dir().join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub mod client;"));
+        assert!(lib.contains("pub fn filled() {}"));
+        assert!(!lib.contains(FIXTURE_FILL_MARKER));
+
+        sandbox
+            .write_fixture_source("src/client.rs", "pub struct Client;")
+            .unwrap();
+        let client = std::fs::read_to_string(sandbox.work_dir().join("src/client.rs")).unwrap();
+        assert_eq!(client, "pub struct Client;");
+
+        assert!(sandbox
+            .write_fixture_source("../escape.rs", "fn main() {}")
+            .is_err());
+
+        // Tests go next to the code they exercise
+        sandbox
+            .write_fixture_test("src/client.rs", "#[test] fn t() {}")
+            .unwrap();
+        let client = std::fs::read_to_string(sandbox.work_dir().join("src/client.rs")).unwrap();
+        assert!(client.starts_with("pub struct Client;") && client.ends_with("#[test] fn t() {}"));
+    }
+
+    #[test]
+    fn inline_fill_marker_is_rejected() {
+        let target = tempfile::tempdir().unwrap();
+        let fixture = make_fixture();
+        std::fs::write(
+            fixture.path().join("src/lib.rs"),
+            "pub fn f() { // forgetest:fill\n}\n",
+        )
+        .unwrap();
+        let sandbox = Sandbox::from_fixture(
+            fixture.path(),
+            Language::Rust,
+            Duration::from_secs(60),
+            target.path(),
+        )
+        .unwrap();
+
+        let err = sandbox
+            .write_fixture_source("src/lib.rs", "pub fn g() {}")
+            .unwrap_err();
+        assert!(err.to_string().contains("on a line by itself"));
+    }
 }
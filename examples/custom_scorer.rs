@@ -1,16 +1,48 @@
-//! Custom scorer example — compute custom scores from eval results.
+//! Custom scorer example — rescore a report with a custom `Scorer`.
 //!
-//! This example shows how to load an eval report and compute custom
-//! scores using the forgetest library.
+//! This example shows how to implement the `Scorer` trait and apply it to a
+//! previously generated report, both per result and per model, and how to
+//! use it for regression comparison.
 //!
 //! ```bash
 //! cargo run --example custom_scorer -- results/report.json
 //! ```
 
+use std::collections::HashMap;
 use std::env;
 
+use forgetest_core::model::Expectations;
 use forgetest_core::report::EvalReport;
-use forgetest_core::results::Score;
+use forgetest_core::results::{EvalResult, Score};
+use forgetest_core::scoring::{self, DefaultScorer, Scorer};
+
+/// Weights tests heavily and treats any `unwrap` in the solution as a defect.
+struct StrictScorer;
+
+impl Scorer for StrictScorer {
+    fn id(&self) -> &str {
+        "strict"
+    }
+
+    fn score(&self, result: &EvalResult, expectations: &Expectations) -> Score {
+        // Start from the default components and re-weight them
+        let mut score = DefaultScorer::default().score(result, expectations);
+
+        let unwraps = result.metrics.as_ref().map_or(0, |m| m.unwrap_calls);
+        let robustness = if unwraps == 0 { 1.0 } else { 0.5 };
+        score.extra.insert("robustness".into(), robustness);
+
+        score.overall = if result.compilation.success {
+            let tests = scoring::test_score(result, expectations);
+            let clippy = scoring::clippy_score(result, expectations);
+            0.25 + tests * 0.60 + clippy * 0.05 + robustness * 0.10
+        } else {
+            0.0
+        };
+        score.scorer = self.id().into();
+        score
+    }
+}
 
 fn main() -> anyhow::Result<()> {
     let args: Vec<String> = env::args().collect();
@@ -22,79 +54,41 @@ fn main() -> anyhow::Result<()> {
     let report = EvalReport::load_json(report_path.as_ref())?;
     println!("Loaded report: {} results", report.results.len());
 
-    // Custom scoring: weight tests more heavily
-    println!("\n--- Custom Scoring (70% tests, 25% compile, 5% clippy) ---\n");
-    println!("{:<30} {:<15} {:<10} {:<10}", "Case", "Model", "Default", "Custom");
+    let default_scorer = DefaultScorer::default();
+    let strict = StrictScorer;
+    let expectations = Expectations::default();
+
+    println!("\n--- Default vs Strict Scoring ---\n");
+    println!("{:<30} {:<15} {:<10} {:<10}", "Case", "Model", "Default", "Strict");
     println!("{}", "-".repeat(65));
 
+    let mut model_scores: HashMap<&str, Vec<f64>> = HashMap::new();
     for result in &report.results {
-        // Default scoring
-        let default_expectations = forgetest_core::model::Expectations::default();
-        let default_score = Score::compute(result, &default_expectations);
-
-        // Custom scoring: change the weights
-        let custom_score = if !result.compilation.success {
-            0.0
-        } else {
-            let compile = 1.0;
-            let test = result
-                .test_execution
-                .as_ref()
-                .map(|t| {
-                    let total = t.passed + t.failed;
-                    if total == 0 { 0.0 } else { t.passed as f64 / total as f64 }
-                })
-                .unwrap_or(0.0);
-            let clippy = result
-                .clippy
-                .as_ref()
-                .map(|c| (1.0 - c.warning_count as f64 * 0.1).max(0.0))
-                .unwrap_or(1.0);
-
-            // Custom weights: 25% compile, 70% tests, 5% clippy
-            compile * 0.25 + test * 0.70 + clippy * 0.05
-        };
+        let default_score = default_scorer.score(result, &expectations);
+        let strict_score = strict.score(result, &expectations);
 
         println!(
             "{:<30} {:<15} {:<10.1}% {:<10.1}%",
             result.case_id,
             result.model,
             default_score.overall * 100.0,
-            custom_score * 100.0,
+            strict_score.overall * 100.0,
         );
+        model_scores
+            .entry(&result.model)
+            .or_default()
+            .push(strict_score.overall);
     }
 
-    // Compute aggregate custom score per model
-    println!("\n--- Per-Model Custom Averages ---\n");
-    let mut model_scores: std::collections::HashMap<&str, Vec<f64>> =
-        std::collections::HashMap::new();
-
-    for result in &report.results {
-        let custom = if !result.compilation.success {
-            0.0
-        } else {
-            let test = result
-                .test_execution
-                .as_ref()
-                .map(|t| {
-                    let total = t.passed + t.failed;
-                    if total == 0 { 0.0 } else { t.passed as f64 / total as f64 }
-                })
-                .unwrap_or(0.0);
-            let clippy = result
-                .clippy
-                .as_ref()
-                .map(|c| (1.0 - c.warning_count as f64 * 0.1).max(0.0))
-                .unwrap_or(1.0);
-            0.25 + test * 0.70 + clippy * 0.05
-        };
-        model_scores.entry(&result.model).or_default().push(custom);
-    }
-
+    println!("\n--- Per-Model Strict Averages ---\n");
     for (model, scores) in &model_scores {
         let avg = scores.iter().sum::<f64>() / scores.len() as f64;
-        println!("  {model}: {:.1}% average custom score", avg * 100.0);
+        println!("  {model}: {:.1}% average strict score", avg * 100.0);
     }
 
+    // Regression checks can use the same scorer
+    let self_compare = report.compare_with(&report, 0.05, &strict);
+    println!("\nSelf-compare with strict scorer: {} unchanged", self_compare.unchanged);
+
     Ok(())
 }